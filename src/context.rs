@@ -8,7 +8,17 @@ use tetrio_api::{http::clients::reqwest_client::RedisReqwestClient, models::{pac
 use twilight_http::Client;
 use twilight_model::{guild::Guild, oauth::Application, http::interaction::{InteractionResponse, InteractionResponseType, InteractionResponseData}, gateway::payload::incoming::InteractionCreate, id::{marker::InteractionMarker, Id}};
 
-use crate::utils::box_commands::PhantomCommandTrait;
+use crate::utils::{box_commands::PhantomCommandTrait, charts::ChartBackend};
+#[cfg(all(feature = "tetrio", feature = "html_server_image_generation"))]
+use crate::utils::image_server::ImageServerClient;
+#[cfg(feature = "tetrio")]
+use crate::utils::replay_fetcher::ReplayFetcher;
+#[cfg(all(feature = "database", feature = "tetrio"))]
+use crate::utils::account_link::LinkVerifier;
+#[cfg(all(feature = "database", feature = "tetrio"))]
+use crate::utils::guild_members::MemberCache;
+#[cfg(feature = "tetrio")]
+use crate::utils::leaderboard_cache::LeaderboardCache;
 
 
 
@@ -20,11 +30,25 @@ pub struct Context<'a> {
     pub test_guild: Guild,
     pub local_server_url: String,
     pub api_url: String,
+    pub chart_backend: ChartBackend,
+    #[cfg(all(feature = "tetrio", feature = "html_server_image_generation"))]
+    pub image_server: Box<dyn ImageServerClient>,
+    #[cfg(feature = "tetrio")]
+    pub replay_fetcher: Box<dyn ReplayFetcher>,
+    #[cfg(feature = "tetrio")]
+    pub leaderboard_cache: LeaderboardCache,
 
     pub commands: Vec<Box<dyn PhantomCommandTrait>>,
     pub author_id: u64,
     #[cfg(feature = "database")]
     pub sql_connection: sqlx::postgres::PgPool,
+    /// Directory of the archived replays
+    #[cfg(all(feature = "database", feature = "tetrio"))]
+    pub replay_storage_dir: std::path::PathBuf,
+    #[cfg(all(feature = "database", feature = "tetrio"))]
+    pub link_verifier: Box<dyn LinkVerifier>,
+    #[cfg(all(feature = "database", feature = "tetrio"))]
+    pub member_cache: MemberCache,
     #[cfg(feature = "ai")]
     pub ai_channel: u64,
     #[cfg(feature = "ai")]
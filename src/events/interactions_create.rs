@@ -34,6 +34,13 @@ pub async fn handle_interaction_create(
                     };
                     log::error!("An error has occured: {e}");
                 }
+                #[cfg(feature = "tetrio")]
+                _ if data.custom_id.starts_with(crate::utils::leaderboard_browser::CUSTOM_ID_PREFIX) => {
+                    let Err(e) = crate::utils::leaderboard_browser::handle_component(shard, it, data, &context).await else {
+                        return;
+                    };
+                    log::error!("An error has occured: {e}");
+                }
                 _ => {}
             }
         }
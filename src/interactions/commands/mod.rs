@@ -8,8 +8,9 @@ use crate::{
 
 #[cfg(feature = "tetrio")]
 use crate::interactions::commands::tetrio_commands::{
-    lb::LbCommand, psq::PsqCommand,
-    rlb::RLbCommand, sq::SqCommand, 
+    alb::AlbCommand, lb::LbCommand, psq::PsqCommand,
+    replay::ReplayCommand, rlb::RLbCommand, sq::SqCommand, 
+    similar::SimilarCommand, target::TargetCommand,
     ts::TsCommand, vs::VsCommand, vsr::VsrCommand,
     vst::VstCommand,
 };
@@ -40,6 +41,13 @@ pub fn get_commands() -> Vec<Box<dyn PhantomCommandTrait>> {
         create_silly_command::CreateSillyCommand,    
     };
 
+    #[cfg(all(feature = "tetrio", feature = "database"))]
+    use crate::interactions::commands::tetrio_commands::{
+        announcements::AnnouncementsCommand, cutoffs::CutoffsCommand, formula::FormulaCommand, glb::GlbCommand,
+        leaderboard_export::LeaderboardExportCommand, link::LinkCommand, match_feed::MatchFeedCommand,
+        rank_roles::RankRolesCommand, replays::ReplaysCommand,
+    };
+
     #[cfg(feature = "html_server_image_generation")]
     use crate::
         interactions::commands::
@@ -72,6 +80,32 @@ pub fn get_commands() -> Vec<Box<dyn PhantomCommandTrait>> {
         Box::new(PhantomCommand::<LbCommand>::new()),
         #[cfg(feature = "tetrio")]
         Box::new(PhantomCommand::<RLbCommand>::new()),
+        #[cfg(feature = "tetrio")]
+        Box::new(PhantomCommand::<AlbCommand>::new()),
+        #[cfg(feature = "tetrio")]
+        Box::new(PhantomCommand::<TargetCommand>::new()),
+        #[cfg(feature = "tetrio")]
+        Box::new(PhantomCommand::<SimilarCommand>::new()),
+        #[cfg(feature = "tetrio")]
+        Box::new(PhantomCommand::<ReplayCommand>::new()),
+        #[cfg(all(feature = "tetrio", feature = "database"))]
+        Box::new(PhantomCommand::<FormulaCommand>::new()),
+        #[cfg(all(feature = "tetrio", feature = "database"))]
+        Box::new(PhantomCommand::<ReplaysCommand>::new()),
+        #[cfg(all(feature = "tetrio", feature = "database"))]
+        Box::new(PhantomCommand::<LinkCommand>::new()),
+        #[cfg(all(feature = "tetrio", feature = "database"))]
+        Box::new(PhantomCommand::<RankRolesCommand>::new()),
+        #[cfg(all(feature = "tetrio", feature = "database"))]
+        Box::new(PhantomCommand::<AnnouncementsCommand>::new()),
+        #[cfg(all(feature = "tetrio", feature = "database"))]
+        Box::new(PhantomCommand::<MatchFeedCommand>::new()),
+        #[cfg(all(feature = "tetrio", feature = "database"))]
+        Box::new(PhantomCommand::<GlbCommand>::new()),
+        #[cfg(all(feature = "tetrio", feature = "database"))]
+        Box::new(PhantomCommand::<CutoffsCommand>::new()),
+        #[cfg(all(feature = "tetrio", feature = "database"))]
+        Box::new(PhantomCommand::<LeaderboardExportCommand>::new()),
         Box::new(PhantomCommand::<HelpCommand>::new()),
         Box::new(PhantomCommand::<RngCommand>::new()),
         Box::new(PhantomCommand::<EightBallCommand>::new()),
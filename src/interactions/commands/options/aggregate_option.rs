@@ -0,0 +1,27 @@
+use twilight_interactions::command::{CommandOption, CreateOption};
+
+use crate::utils::aggregate_leaderboard::{mean, median};
+
+#[derive(CreateOption, CommandOption, Clone, Copy, Debug)]
+pub enum AggregateOption {
+    #[option(name = "mean", value = "mean")]
+    Mean,
+    #[option(name = "median", value = "median")]
+    Median,
+}
+
+impl AggregateOption {
+    pub fn name(&self) -> &'static str {
+        match self {
+            AggregateOption::Mean => "Mean",
+            AggregateOption::Median => "Median",
+        }
+    }
+
+    pub fn apply(&self, values: &[f64]) -> Option<f64> {
+        match self {
+            AggregateOption::Mean => mean(values),
+            AggregateOption::Median => median(values),
+        }
+    }
+}
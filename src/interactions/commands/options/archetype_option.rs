@@ -0,0 +1,35 @@
+use twilight_interactions::command::{CommandOption, CreateOption};
+
+use crate::utils::archetype::Archetype;
+
+#[derive(CreateOption, CommandOption, Clone, Copy, Debug)]
+pub enum ArchetypeOption {
+    #[option(name = "Balanced", value = "balanced")]
+    Balanced,
+    #[option(name = "Opener-heavy attacker", value = "opener")]
+    OpenerHeavyAttacker,
+    #[option(name = "Downstack-focused", value = "downstack")]
+    DownstackFocused,
+    #[option(name = "Aggressive downstacker", value = "aggressive_downstack")]
+    AggressiveDownstacker,
+    #[option(name = "Plonker", value = "plonker")]
+    Plonker,
+    #[option(name = "Speed plonker", value = "speed_plonker")]
+    SpeedPlonker,
+    #[option(name = "Speed stacker", value = "speed_stacker")]
+    SpeedStacker,
+}
+
+impl From<ArchetypeOption> for Archetype {
+    fn from(val: ArchetypeOption) -> Self {
+        match val {
+            ArchetypeOption::Balanced => Archetype::Balanced,
+            ArchetypeOption::OpenerHeavyAttacker => Archetype::OpenerHeavyAttacker,
+            ArchetypeOption::DownstackFocused => Archetype::DownstackFocused,
+            ArchetypeOption::AggressiveDownstacker => Archetype::AggressiveDownstacker,
+            ArchetypeOption::Plonker => Archetype::Plonker,
+            ArchetypeOption::SpeedPlonker => Archetype::SpeedPlonker,
+            ArchetypeOption::SpeedStacker => Archetype::SpeedStacker,
+        }
+    }
+}
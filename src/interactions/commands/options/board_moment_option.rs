@@ -0,0 +1,13 @@
+use twilight_interactions::command::{CommandOption, CreateOption};
+
+#[derive(CreateOption, CommandOption, Clone, Copy, Debug)]
+pub enum BoardMomentOption {
+    #[option(name = "Final board", value = "final")]
+    Final,
+    #[option(name = "Top-out", value = "topout")]
+    TopOut,
+    #[option(name = "Biggest spike", value = "spike")]
+    BiggestSpike,
+    #[option(name = "Animation", value = "animation")]
+    Animation,
+}
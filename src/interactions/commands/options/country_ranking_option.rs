@@ -0,0 +1,11 @@
+use twilight_interactions::command::{CommandOption, CreateOption};
+
+#[derive(CreateOption, CommandOption, Clone, Copy, Debug)]
+pub enum CountryRankingOption {
+    #[option(name = "players", value = "players")]
+    Players,
+    #[option(name = "X rank players", value = "xranks")]
+    XRanks,
+    #[option(name = "stat", value = "stat")]
+    Stat,
+}
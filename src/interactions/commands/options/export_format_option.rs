@@ -0,0 +1,27 @@
+use twilight_interactions::command::{CommandOption, CreateOption};
+
+#[derive(CreateOption, CommandOption, Clone, Copy, Debug)]
+pub enum ExportFormatOption {
+    #[option(name = "CSV", value = "csv")]
+    Csv,
+    #[option(name = "JSON", value = "json")]
+    Json,
+}
+
+impl ExportFormatOption {
+    /// The value of the option, also the extension of the exported file.
+    pub fn value(&self) -> &'static str {
+        match self {
+            ExportFormatOption::Csv => "csv",
+            ExportFormatOption::Json => "json",
+        }
+    }
+
+    pub fn from_value(value: &str) -> Option<Self> {
+        match value {
+            "csv" => Some(ExportFormatOption::Csv),
+            "json" => Some(ExportFormatOption::Json),
+            _ => None,
+        }
+    }
+}
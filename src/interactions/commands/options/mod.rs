@@ -1,3 +1,13 @@
 #[cfg(feature = "tetrio")]
+pub mod aggregate_option;
+#[cfg(feature = "tetrio")]
+pub mod archetype_option;
+#[cfg(feature = "tetrio")]
+pub mod board_moment_option;
+#[cfg(feature = "tetrio")]
+pub mod country_ranking_option;
+#[cfg(feature = "tetrio")]
+pub mod export_format_option;
+#[cfg(feature = "tetrio")]
 pub mod user_rank_option;
 pub mod user_stat_options;
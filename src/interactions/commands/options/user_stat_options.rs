@@ -48,3 +48,89 @@ pub enum UserStatOption {
     #[option(name = "win rate", value = "winrate")]
     WR,
 }
+
+impl UserStatOption {
+    pub const ALL: [UserStatOption; 22] = [
+        UserStatOption::APM,
+        UserStatOption::PPS,
+        UserStatOption::VS,
+        UserStatOption::APP,
+        UserStatOption::DSPIECE,
+        UserStatOption::DSSECOND,
+        UserStatOption::CHEESE,
+        UserStatOption::GE,
+        UserStatOption::AREA,
+        UserStatOption::WAPP,
+        UserStatOption::VSAPM,
+        UserStatOption::DSAPPPIECE,
+        UserStatOption::TR,
+        UserStatOption::ESTTR,
+        UserStatOption::ATR,
+        UserStatOption::OPENER,
+        UserStatOption::PLONK,
+        UserStatOption::STRIDE,
+        UserStatOption::INFDS,
+        UserStatOption::WINS,
+        UserStatOption::GAMES,
+        UserStatOption::WR,
+    ];
+
+    /// The value of the option, as sent by discord.
+    pub fn value(&self) -> &'static str {
+        match self {
+            UserStatOption::APM => "apm",
+            UserStatOption::PPS => "pps",
+            UserStatOption::VS => "vs",
+            UserStatOption::APP => "app",
+            UserStatOption::DSPIECE => "dspiece",
+            UserStatOption::DSSECOND => "dssecond",
+            UserStatOption::CHEESE => "cheese",
+            UserStatOption::GE => "ge",
+            UserStatOption::AREA => "area",
+            UserStatOption::WAPP => "wapp",
+            UserStatOption::VSAPM => "vsapm",
+            UserStatOption::DSAPPPIECE => "dsapppiece",
+            UserStatOption::TR => "tr",
+            UserStatOption::ESTTR => "esttr",
+            UserStatOption::ATR => "atr",
+            UserStatOption::OPENER => "opener",
+            UserStatOption::PLONK => "plonk",
+            UserStatOption::STRIDE => "stride",
+            UserStatOption::INFDS => "infds",
+            UserStatOption::WINS => "wins",
+            UserStatOption::GAMES => "games",
+            UserStatOption::WR => "winrate",
+        }
+    }
+
+    pub fn from_value(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|stat| stat.value() == value)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            UserStatOption::APM => "APM",
+            UserStatOption::PPS => "PPS",
+            UserStatOption::VS => "VS",
+            UserStatOption::APP => "APP",
+            UserStatOption::DSPIECE => "DS/Piece",
+            UserStatOption::DSSECOND => "DS/Second",
+            UserStatOption::CHEESE => "Cheese Index",
+            UserStatOption::GE => "Garbage Effi.",
+            UserStatOption::AREA => "Area",
+            UserStatOption::WAPP => "Weighted APP",
+            UserStatOption::VSAPM => "VS/APM",
+            UserStatOption::DSAPPPIECE => "APP+DS/Piece",
+            UserStatOption::TR => "TR",
+            UserStatOption::ESTTR => "Est. of TR",
+            UserStatOption::ATR => "Acc. of Est. TR",
+            UserStatOption::OPENER => "Opener",
+            UserStatOption::PLONK => "Plonk",
+            UserStatOption::STRIDE => "Stride",
+            UserStatOption::INFDS => "Inf. DS",
+            UserStatOption::WINS => "Wins",
+            UserStatOption::GAMES => "Games",
+            UserStatOption::WR => "Win Rate",
+        }
+    }
+}
@@ -0,0 +1,27 @@
+use twilight_interactions::command::{CommandModel, CreateCommand};
+
+use crate::interactions::commands::options::{
+    aggregate_option::AggregateOption, country_ranking_option::CountryRankingOption,
+    user_stat_options::UserStatOption,
+};
+
+#[derive(CreateCommand, CommandModel, Debug)]
+#[command(name = "countries", desc = "Rank the countries by their players in the leaderboard")]
+pub struct CountriesSubCommand {
+    /// What to rank the countries by
+    pub ranking: CountryRankingOption,
+    /// The stat to aggregate when ranking by stat, defaults to TR
+    pub stat: Option<UserStatOption>,
+    /// A saved formula name or an expression to aggregate instead of the stat
+    pub formula: Option<String>,
+    /// How to aggregate the stat of the players, defaults to the mean
+    pub aggregate: Option<AggregateOption>,
+    #[command(min_value = 1)]
+    /// Only countries with at least this many ranked players, defaults to 5
+    pub min_players: Option<i64>,
+    #[command(min_value = 1)]
+    /// The page to display, defaults to the first one
+    pub page: Option<i64>,
+    /// Get a dark mode chart
+    pub dark_mode: Option<bool>,
+}
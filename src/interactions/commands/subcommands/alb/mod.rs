@@ -0,0 +1,2 @@
+pub mod countries_sub_command;
+pub mod ranks_sub_command;
@@ -0,0 +1,16 @@
+use twilight_interactions::command::{CommandModel, CreateCommand};
+
+use crate::interactions::commands::options::{aggregate_option::AggregateOption, user_stat_options::UserStatOption};
+
+#[derive(CreateCommand, CommandModel, Debug)]
+#[command(name = "ranks", desc = "Compare the stats of the players of every rank side by side")]
+pub struct RanksSubCommand {
+    /// An extra stat to compare, also used for the chart, which shows the TR otherwise
+    pub stat: Option<UserStatOption>,
+    /// A saved formula name or an expression to compare instead of the extra stat
+    pub formula: Option<String>,
+    /// How to aggregate the stats of the players, defaults to the mean
+    pub aggregate: Option<AggregateOption>,
+    /// Get a dark mode chart
+    pub dark_mode: Option<bool>,
+}
@@ -0,0 +1,9 @@
+use twilight_interactions::command::{CommandModel, CreateCommand};
+use twilight_model::id::{marker::ChannelMarker, Id};
+
+#[derive(CreateCommand, CommandModel, Debug)]
+#[command(name = "channel", desc = "Post the announcements of this server in a channel")]
+pub struct ChannelSubCommand {
+    /// The channel of the announcements
+    pub channel: Id<ChannelMarker>,
+}
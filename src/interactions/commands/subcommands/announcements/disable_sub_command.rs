@@ -0,0 +1,5 @@
+use twilight_interactions::command::{CommandModel, CreateCommand};
+
+#[derive(CreateCommand, CommandModel, Debug)]
+#[command(name = "disable", desc = "Stop posting announcements in this server")]
+pub struct DisableSubCommand {}
@@ -0,0 +1,5 @@
+use twilight_interactions::command::{CommandModel, CreateCommand};
+
+#[derive(CreateCommand, CommandModel, Debug)]
+#[command(name = "list", desc = "Show the announcement channel and the tracked players of this server")]
+pub struct ListSubCommand {}
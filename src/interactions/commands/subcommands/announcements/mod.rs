@@ -0,0 +1,7 @@
+pub mod channel_sub_command;
+pub mod disable_sub_command;
+pub mod list_sub_command;
+pub mod opt_in_sub_command;
+pub mod opt_out_sub_command;
+pub mod track_sub_command;
+pub mod untrack_sub_command;
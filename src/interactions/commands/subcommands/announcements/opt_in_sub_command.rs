@@ -0,0 +1,5 @@
+use twilight_interactions::command::{CommandModel, CreateCommand};
+
+#[derive(CreateCommand, CommandModel, Debug)]
+#[command(name = "optin", desc = "Announce the progress of your linked tetrio account")]
+pub struct OptInSubCommand {}
@@ -0,0 +1,5 @@
+use twilight_interactions::command::{CommandModel, CreateCommand};
+
+#[derive(CreateCommand, CommandModel, Debug)]
+#[command(name = "optout", desc = "Never announce the progress of your linked tetrio account")]
+pub struct OptOutSubCommand {}
@@ -0,0 +1,8 @@
+use twilight_interactions::command::{CommandModel, CreateCommand};
+
+#[derive(CreateCommand, CommandModel, Debug)]
+#[command(name = "track", desc = "Announce the progress of a tetrio player in this server")]
+pub struct TrackSubCommand {
+    /// The tetrio username
+    pub tetrio_user: String,
+}
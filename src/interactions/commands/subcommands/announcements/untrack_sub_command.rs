@@ -0,0 +1,8 @@
+use twilight_interactions::command::{CommandModel, CreateCommand};
+
+#[derive(CreateCommand, CommandModel, Debug)]
+#[command(name = "untrack", desc = "Stop announcing a tracked tetrio player in this server")]
+pub struct UntrackSubCommand {
+    /// The tetrio username
+    pub tetrio_user: String,
+}
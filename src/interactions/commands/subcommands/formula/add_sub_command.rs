@@ -0,0 +1,12 @@
+use twilight_interactions::command::{CommandModel, CreateCommand};
+
+#[derive(CreateCommand, CommandModel, Debug)]
+#[command(name = "add", desc = "Save a formula for this server")]
+pub struct AddSubCommand {
+    /// The name of the formula
+    #[command(max_length = 32)]
+    pub name: String,
+    /// The formula, e.g. (apm / 60) / pps or max(app, dspiece)
+    #[command(max_length = 200)]
+    pub expression: String,
+}
@@ -0,0 +1,5 @@
+use twilight_interactions::command::{CommandModel, CreateCommand};
+
+#[derive(CreateCommand, CommandModel, Debug)]
+#[command(name = "list", desc = "List the formulas of this server and the available variables")]
+pub struct ListSubCommand {}
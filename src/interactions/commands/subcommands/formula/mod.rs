@@ -0,0 +1,5 @@
+pub mod add_sub_command;
+pub mod list_sub_command;
+pub mod remove_sub_command;
+pub mod test_sub_command;
+pub mod variables_sub_command;
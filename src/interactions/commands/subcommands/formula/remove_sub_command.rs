@@ -0,0 +1,8 @@
+use twilight_interactions::command::{CommandModel, CreateCommand};
+
+#[derive(CreateCommand, CommandModel, Debug)]
+#[command(name = "remove", desc = "Remove a formula from this server")]
+pub struct RemoveSubCommand {
+    /// The name of the formula
+    pub name: String,
+}
@@ -0,0 +1,10 @@
+use twilight_interactions::command::{CommandModel, CreateCommand};
+
+#[derive(CreateCommand, CommandModel, Debug)]
+#[command(name = "test", desc = "Evaluate a formula for a user")]
+pub struct TestSubCommand {
+    /// A saved formula name or an expression
+    pub formula: String,
+    /// A tetrio user, (pps, apm, vs), discord ping, $avgX where X is a rank, e.g S+ or $avgX:COUNTRY_CODE
+    pub user: String,
+}
@@ -0,0 +1,5 @@
+use twilight_interactions::command::{CommandModel, CreateCommand};
+
+#[derive(CreateCommand, CommandModel, Debug)]
+#[command(name = "variables", desc = "List the variables a formula can use, with what they mean")]
+pub struct VariablesSubCommand {}
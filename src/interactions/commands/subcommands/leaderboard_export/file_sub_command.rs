@@ -0,0 +1,16 @@
+use twilight_interactions::command::{CommandModel, CreateCommand};
+
+use crate::interactions::commands::options::{
+    export_format_option::ExportFormatOption, user_rank_option::UserRankOption,
+};
+
+#[derive(CreateCommand, CommandModel, Debug)]
+#[command(name = "file", desc = "Attach the full leaderboard with every stat as a file")]
+pub struct FileSubCommand {
+    /// The format of the file
+    pub format: ExportFormatOption,
+    /// Only export the players in this rank
+    pub rank: Option<UserRankOption>,
+    /// Only export the players of this country
+    pub country_code: Option<String>,
+}
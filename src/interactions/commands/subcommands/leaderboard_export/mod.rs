@@ -0,0 +1,4 @@
+pub mod file_sub_command;
+pub mod list_sub_command;
+pub mod schedule_sub_command;
+pub mod unschedule_sub_command;
@@ -0,0 +1,19 @@
+use twilight_interactions::command::{CommandModel, CreateCommand};
+use twilight_model::id::{marker::ChannelMarker, Id};
+
+use crate::interactions::commands::options::{
+    export_format_option::ExportFormatOption, user_rank_option::UserRankOption,
+};
+
+#[derive(CreateCommand, CommandModel, Debug)]
+#[command(name = "schedule", desc = "Post an export of the leaderboard in a channel every day")]
+pub struct ScheduleSubCommand {
+    /// The format of the file
+    pub format: ExportFormatOption,
+    /// Where to post the exports, defaults to this channel
+    pub channel: Option<Id<ChannelMarker>>,
+    /// Only export the players in this rank
+    pub rank: Option<UserRankOption>,
+    /// Only export the players of this country
+    pub country_code: Option<String>,
+}
@@ -0,0 +1,9 @@
+use twilight_interactions::command::{CommandModel, CreateCommand};
+use twilight_model::id::{marker::ChannelMarker, Id};
+
+#[derive(CreateCommand, CommandModel, Debug)]
+#[command(name = "unschedule", desc = "Stop posting the daily export in a channel")]
+pub struct UnscheduleSubCommand {
+    /// The channel of the export, defaults to this channel
+    pub channel: Option<Id<ChannelMarker>>,
+}
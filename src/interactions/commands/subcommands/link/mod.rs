@@ -0,0 +1,4 @@
+pub mod remove_sub_command;
+pub mod show_sub_command;
+pub mod start_sub_command;
+pub mod verify_sub_command;
@@ -0,0 +1,5 @@
+use twilight_interactions::command::{CommandModel, CreateCommand};
+
+#[derive(CreateCommand, CommandModel, Debug)]
+#[command(name = "remove", desc = "Unlink your tetrio account")]
+pub struct RemoveSubCommand {}
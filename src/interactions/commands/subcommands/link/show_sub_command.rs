@@ -0,0 +1,5 @@
+use twilight_interactions::command::{CommandModel, CreateCommand};
+
+#[derive(CreateCommand, CommandModel, Debug)]
+#[command(name = "show", desc = "Show the tetrio account linked to your discord account")]
+pub struct ShowSubCommand {}
@@ -0,0 +1,8 @@
+use twilight_interactions::command::{CommandModel, CreateCommand};
+
+#[derive(CreateCommand, CommandModel, Debug)]
+#[command(name = "start", desc = "Get a code to put in the bio of your tetrio account")]
+pub struct StartSubCommand {
+    /// Your tetrio username or id
+    pub tetrio_user: String,
+}
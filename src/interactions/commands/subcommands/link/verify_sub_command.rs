@@ -0,0 +1,5 @@
+use twilight_interactions::command::{CommandModel, CreateCommand};
+
+#[derive(CreateCommand, CommandModel, Debug)]
+#[command(name = "verify", desc = "Check the code in your tetrio bio and link the account")]
+pub struct VerifySubCommand {}
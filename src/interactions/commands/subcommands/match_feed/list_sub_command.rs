@@ -0,0 +1,5 @@
+use twilight_interactions::command::{CommandModel, CreateCommand};
+
+#[derive(CreateCommand, CommandModel, Debug)]
+#[command(name = "list", desc = "List the players whose matches are posted in this server")]
+pub struct ListSubCommand {}
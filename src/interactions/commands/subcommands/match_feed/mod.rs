@@ -0,0 +1,3 @@
+pub mod list_sub_command;
+pub mod subscribe_sub_command;
+pub mod unsubscribe_sub_command;
@@ -0,0 +1,11 @@
+use twilight_interactions::command::{CommandModel, CreateCommand};
+use twilight_model::id::{marker::ChannelMarker, Id};
+
+#[derive(CreateCommand, CommandModel, Debug)]
+#[command(name = "subscribe", desc = "Post the new tetra league matches of a player")]
+pub struct SubscribeSubCommand {
+    /// The tetrio username
+    pub tetrio_user: String,
+    /// Where to post the matches, defaults to this channel
+    pub channel: Option<Id<ChannelMarker>>,
+}
@@ -0,0 +1,8 @@
+use twilight_interactions::command::{CommandModel, CreateCommand};
+
+#[derive(CreateCommand, CommandModel, Debug)]
+#[command(name = "unsubscribe", desc = "Stop posting the matches of a player in this server")]
+pub struct UnsubscribeSubCommand {
+    /// The tetrio username
+    pub tetrio_user: String,
+}
@@ -1,4 +1,20 @@
 #[cfg(feature = "tetrio")]
+pub mod alb;
+#[cfg(all(feature = "tetrio", feature = "database"))]
+pub mod announcements;
+#[cfg(all(feature = "tetrio", feature = "database"))]
+pub mod formula;
+#[cfg(all(feature = "tetrio", feature = "database"))]
+pub mod leaderboard_export;
+#[cfg(all(feature = "tetrio", feature = "database"))]
+pub mod link;
+#[cfg(all(feature = "tetrio", feature = "database"))]
+pub mod match_feed;
+#[cfg(all(feature = "tetrio", feature = "database"))]
+pub mod rank_roles;
+#[cfg(feature = "tetrio")]
+pub mod replay;
+#[cfg(feature = "tetrio")]
 pub mod teto;
 #[cfg(feature = "tetrio")]
 pub mod tetra;
@@ -0,0 +1,9 @@
+use twilight_interactions::command::{CommandModel, CreateCommand};
+
+#[derive(CreateCommand, CommandModel, Debug)]
+#[command(name = "audit", desc = "Show the last rank roles given and removed")]
+pub struct AuditSubCommand {
+    /// How many changes to show
+    #[command(min_value = 1, max_value = 25)]
+    pub limit: Option<i64>,
+}
@@ -0,0 +1,6 @@
+pub mod audit_sub_command;
+pub mod list_sub_command;
+pub mod preview_sub_command;
+pub mod remove_sub_command;
+pub mod set_sub_command;
+pub mod sync_sub_command;
@@ -0,0 +1,5 @@
+use twilight_interactions::command::{CommandModel, CreateCommand};
+
+#[derive(CreateCommand, CommandModel, Debug)]
+#[command(name = "preview", desc = "Show the roles a sync would add and remove, without changing them")]
+pub struct PreviewSubCommand {}
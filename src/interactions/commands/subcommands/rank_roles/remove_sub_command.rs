@@ -0,0 +1,9 @@
+use twilight_interactions::command::{CommandModel, CreateCommand};
+use twilight_model::id::{marker::RoleMarker, Id};
+
+#[derive(CreateCommand, CommandModel, Debug)]
+#[command(name = "remove", desc = "Stop managing a role, members keep it until it is removed by hand")]
+pub struct RemoveSubCommand {
+    /// The managed role
+    pub role: Id<RoleMarker>,
+}
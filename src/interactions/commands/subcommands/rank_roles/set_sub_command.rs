@@ -0,0 +1,22 @@
+use twilight_interactions::command::{CommandModel, CreateCommand};
+use twilight_model::id::{marker::RoleMarker, Id};
+
+use crate::interactions::commands::options::user_rank_option::UserRankOption;
+
+#[derive(CreateCommand, CommandModel, Debug)]
+#[command(name = "set", desc = "Give a role to the linked members matching a rank, a TR band or a country")]
+pub struct SetSubCommand {
+    /// The role to give
+    pub role: Id<RoleMarker>,
+    /// Rank of the members getting the role
+    pub rank: Option<UserRankOption>,
+    /// Lowest TR of the members getting the role
+    #[command(min_value = 0, max_value = 25000)]
+    pub min_tr: Option<f64>,
+    /// TR from which members don't get the role anymore
+    #[command(min_value = 0, max_value = 25000)]
+    pub max_tr: Option<f64>,
+    /// Country code of the members getting the role, e.g. FR
+    #[command(min_length = 2, max_length = 8)]
+    pub country_code: Option<String>,
+}
@@ -0,0 +1,5 @@
+use twilight_interactions::command::{CommandModel, CreateCommand};
+
+#[derive(CreateCommand, CommandModel, Debug)]
+#[command(name = "sync", desc = "Update the rank roles of the linked members now")]
+pub struct SyncSubCommand {}
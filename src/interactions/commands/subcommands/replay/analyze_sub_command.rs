@@ -0,0 +1,17 @@
+use twilight_interactions::command::{CommandModel, CreateCommand};
+use twilight_model::channel::Attachment;
+
+#[derive(CreateCommand, CommandModel, Debug)]
+#[command(name = "analyze", desc = "Break down a ttrm replay round by round")]
+pub struct AnalyzeSubCommand {
+    /// The replay to analyze
+    pub replay: Option<Attachment>,
+    /// Id of an archived replay, instead of uploading it again
+    pub replay_id: Option<i64>,
+    /// A tetr.io replay id or url
+    pub tetrio_replay: Option<String>,
+    /// Only show this user
+    pub user: Option<String>,
+    /// Get a dark mode chart
+    pub dark_mode: Option<bool>,
+}
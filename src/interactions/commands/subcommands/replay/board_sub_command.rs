@@ -0,0 +1,21 @@
+use twilight_interactions::command::{CommandModel, CreateCommand};
+use twilight_model::channel::Attachment;
+
+use crate::interactions::commands::options::board_moment_option::BoardMomentOption;
+
+#[derive(CreateCommand, CommandModel, Debug)]
+#[command(name = "board", desc = "Rebuild the board of a player at a key moment of a ttrm replay")]
+pub struct BoardSubCommand {
+    /// The player whose board is shown
+    pub user: String,
+    /// The replay to rebuild
+    pub replay: Option<Attachment>,
+    /// Id of an archived replay, instead of uploading it again
+    pub replay_id: Option<i64>,
+    /// A tetr.io replay id or url
+    pub tetrio_replay: Option<String>,
+    /// Round number, defaults to 1
+    pub round: Option<i64>,
+    /// Moment of the round to show, defaults to the final board
+    pub moment: Option<BoardMomentOption>,
+}
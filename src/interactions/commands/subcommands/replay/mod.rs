@@ -0,0 +1,4 @@
+pub mod analyze_sub_command;
+pub mod board_sub_command;
+pub mod versus_sub_command;
+pub mod solo_sub_command;
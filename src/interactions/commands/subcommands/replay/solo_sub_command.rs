@@ -0,0 +1,15 @@
+use twilight_interactions::command::{CommandModel, CreateCommand};
+use twilight_model::channel::Attachment;
+
+#[derive(CreateCommand, CommandModel, Debug)]
+#[command(name = "solo", desc = "Analyze a 40 lines or blitz ttr replay")]
+pub struct SoloSubCommand {
+    /// The replay to analyze
+    pub replay: Option<Attachment>,
+    /// Id of an archived replay, instead of uploading it again
+    pub replay_id: Option<i64>,
+    /// A tetr.io replay id or url
+    pub tetrio_replay: Option<String>,
+    /// Compare against the personal best of the player
+    pub compare_pb: Option<bool>,
+}
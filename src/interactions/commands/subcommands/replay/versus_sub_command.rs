@@ -0,0 +1,15 @@
+use twilight_interactions::command::{CommandModel, CreateCommand};
+use twilight_model::channel::Attachment;
+
+#[derive(CreateCommand, CommandModel, Debug)]
+#[command(name = "versus", desc = "Compare both players of a ttrm replay round by round")]
+pub struct VersusSubCommand {
+    /// The replay to compare
+    pub replay: Option<Attachment>,
+    /// Id of an archived replay, instead of uploading it again
+    pub replay_id: Option<i64>,
+    /// A tetr.io replay id or url
+    pub tetrio_replay: Option<String>,
+    /// Get a dark mode chart
+    pub dark_mode: Option<bool>,
+}
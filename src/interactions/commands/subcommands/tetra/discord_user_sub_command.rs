@@ -3,8 +3,8 @@ use twilight_interactions::command::{CommandModel, CreateCommand, ResolvedUser};
 #[derive(CreateCommand, CommandModel, Debug)]
 #[command(name = "discord", desc = "Use a discord user")]
 pub struct DiscordUserSubCommand {
-    /// the discord user to be selected
-    pub user: ResolvedUser,
+    /// the discord user to be selected, defaults to you
+    pub user: Option<ResolvedUser>,
     /// which game to choose from the record
     pub game_number: Option<i64>,
 }
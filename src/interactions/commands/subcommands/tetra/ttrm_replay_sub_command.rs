@@ -7,7 +7,10 @@
     #[command(name = "replay", desc = "Use a ttrm replay")]
     pub struct TetrioReplaySubCommand {
         /// The replay to analyze
-        pub replay: Attachment,
-
+        pub replay: Option<Attachment>,
+        /// Id of an archived replay, instead of uploading it again
+        pub replay_id: Option<i64>,
+        /// A tetr.io replay id or url
+        pub tetrio_replay: Option<String>,
     }
     
\ No newline at end of file
@@ -9,5 +9,9 @@ pub struct AverageSubCommand {
     pub rank: Option<UserRankOption>,
     /// use detailed informations
     pub details: Option<bool>,
+    /// render the stats as an image card
+    pub card: Option<bool>,
+    /// render the card in dark mode
+    pub dark_mode: Option<bool>,
 
 }
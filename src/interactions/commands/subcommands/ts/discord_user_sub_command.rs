@@ -3,10 +3,14 @@ use twilight_interactions::command::{CommandModel, CreateCommand, ResolvedUser};
 #[derive(CreateCommand, CommandModel, Debug)]
 #[command(name = "discord", desc = "Use a discord user")]
 pub struct DiscordUserSubCommand {
-    /// the discord user to be selected
-    pub user: ResolvedUser,
+    /// the discord user to be selected, defaults to you
+    pub user: Option<ResolvedUser>,
     /// use detailed informations
     pub details: Option<bool>,
+    /// render the stats as an image card
+    pub card: Option<bool>,
+    /// render the card in dark mode
+    pub dark_mode: Option<bool>,
     /// tetra league game number
     pub tetra_league_game: Option<i64>,
     /// tetra league round number
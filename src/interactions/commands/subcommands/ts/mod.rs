@@ -1,5 +1,6 @@
 pub mod average_sub_command;
 pub mod discord_user_sub_command;
+pub mod source_sub_command;
 pub mod stats_sub_command;
 pub mod tetrio_user_sub_command;
-pub mod ttrm_replay_sub_command;
\ No newline at end of file
+pub mod ttrm_replay_sub_command;
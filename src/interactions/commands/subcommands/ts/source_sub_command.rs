@@ -0,0 +1,14 @@
+use twilight_interactions::command::{CommandModel, CreateCommand};
+
+#[derive(CreateCommand, CommandModel, Debug)]
+#[command(name = "source", desc = "Use any player source")]
+pub struct SourceSubCommand {
+    /// A tetrio user[:game[:round]], (pps, apm, vs), discord ping, $avgX[:COUNTRY_CODE] or a .ttrm url#user[:round]
+    pub source: String,
+    /// Show details
+    pub show_details: Option<bool>,
+    /// render the stats as an image card
+    pub card: Option<bool>,
+    /// render the card in dark mode
+    pub dark_mode: Option<bool>,
+}
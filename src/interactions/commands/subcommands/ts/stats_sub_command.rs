@@ -14,4 +14,8 @@ pub struct StatsSubCommand {
     pub rd: Option<f64>,
     /// Show details
     pub show_details: Option<bool>,
+    /// render the stats as an image card
+    pub card: Option<bool>,
+    /// render the card in dark mode
+    pub dark_mode: Option<bool>,
 }
@@ -7,6 +7,10 @@ pub struct TetrioUserSubCommand {
     pub tetrio_user: String,
     /// use detailed informations
     pub details: Option<bool>,
+    /// render the stats as an image card
+    pub card: Option<bool>,
+    /// render the card in dark mode
+    pub dark_mode: Option<bool>,
     /// tetra league game number
     pub tetra_league_game: Option<i64>,
     /// tetra league round number
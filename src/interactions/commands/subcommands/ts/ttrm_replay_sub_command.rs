@@ -6,13 +6,27 @@
     #[derive(CreateCommand, CommandModel, Debug)]
     #[command(name = "replay", desc = "Use a ttrm replay")]
     pub struct TetrioReplaySubCommand {
-        /// The replay to analyze
-        pub replay: Attachment,
         /// The user to analyze
         pub user: String,
-        /// which game to choose from the record
+        /// The replay to analyze, or a zip of replays
+        pub replay: Option<Attachment>,
+        /// Id of an archived replay, instead of uploading it again
+        pub replay_id: Option<i64>,
+        /// A tetr.io replay id or url
+        pub tetrio_replay: Option<String>,
+        /// Another replay of the session
+        pub replay_2: Option<Attachment>,
+        /// Another replay of the session
+        pub replay_3: Option<Attachment>,
+        /// Another replay of the session
+        pub replay_4: Option<Attachment>,
+        /// which round of the replay to use, starting at 1
         pub game_number: Option<i64>,
         /// show details
         pub show_details: Option<bool>,
+        /// render the stats as an image card
+        pub card: Option<bool>,
+        /// render the card in dark mode
+        pub dark_mode: Option<bool>,
     }
     
\ No newline at end of file
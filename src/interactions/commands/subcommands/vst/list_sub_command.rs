@@ -0,0 +1,22 @@
+use twilight_interactions::command::{CommandModel, CreateCommand};
+
+#[derive(CreateCommand, CommandModel, Debug)]
+#[command(name = "list", desc = "Compare up to 6 tetrio users, pings, stats or averages")]
+pub struct ListSubCommand {
+    /// A tetrio user, (pps, apm, vs), discord ping, $avgX where X is a rank, e.g S+ or $avgX:COUNTRY_CODE
+    pub user_1: String,
+    /// A tetrio user, (pps, apm, vs), discord ping, $avgX where X is a rank, e.g S+ or $avgX:COUNTRY_CODE
+    pub user_2: String,
+    /// A tetrio user, (pps, apm, vs), discord ping, $avgX where X is a rank, e.g S+ or $avgX:COUNTRY_CODE
+    pub user_3: Option<String>,
+    /// A tetrio user, (pps, apm, vs), discord ping, $avgX where X is a rank, e.g S+ or $avgX:COUNTRY_CODE
+    pub user_4: Option<String>,
+    /// A tetrio user, (pps, apm, vs), discord ping, $avgX where X is a rank, e.g S+ or $avgX:COUNTRY_CODE
+    pub user_5: Option<String>,
+    /// A tetrio user, (pps, apm, vs), discord ping, $avgX where X is a rank, e.g S+ or $avgX:COUNTRY_CODE
+    pub user_6: Option<String>,
+    /// render the comparison as an image card
+    pub card: Option<bool>,
+    /// render the card in dark mode
+    pub dark_mode: Option<bool>,
+}
@@ -1,4 +1,5 @@
 pub mod average;
 pub mod discord;
+pub mod list_sub_command;
 pub mod stats;
 pub mod tetrio;
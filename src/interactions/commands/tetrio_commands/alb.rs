@@ -0,0 +1,304 @@
+use std::borrow::Cow;
+
+use anyhow::anyhow;
+use itertools::Itertools;
+use twilight_interactions::command::{CommandInputData, CommandModel, CreateCommand};
+use twilight_model::{
+    application::interaction::application_command::CommandData,
+    gateway::payload::incoming::InteractionCreate,
+};
+
+use crate::{
+    context::Context,
+    interactions::commands::{
+        options::{
+            aggregate_option::AggregateOption, country_ranking_option::CountryRankingOption,
+            user_stat_options::UserStatOption,
+        },
+        subcommands::alb::{countries_sub_command::CountriesSubCommand, ranks_sub_command::RanksSubCommand},
+    },
+    utils::{
+        aggregate_leaderboard::{by_country, by_rank, chart_max, PlayerGroup},
+        box_commands::RunnableCommand,
+        charts::{Chart, ChartDataset, ChartKind},
+        create_embed::create_embed,
+        stat_formula::{resolve_formula, StatFormula},
+        table::format_table,
+        timer::Timer,
+    },
+};
+
+use super::{lb::LbCommand, vs::VsCommand};
+
+const COUNTRIES_PAGE_SIZE: usize = 15;
+const DEFAULT_MIN_PLAYERS: i64 = 5;
+
+#[derive(CreateCommand, CommandModel)]
+#[command(name = "alb", desc = "Get aggregate leaderboards of the countries and the ranks")]
+pub enum AlbCommand {
+    #[command(name = "countries")]
+    /// Rank the countries by their players in the leaderboard
+    Countries(CountriesSubCommand),
+    #[command(name = "ranks")]
+    /// Compare the stats of the players of every rank side by side
+    Ranks(RanksSubCommand),
+}
+
+/// A stat aggregated over the players of a group.
+enum GroupStat {
+    Stat(UserStatOption),
+    Formula(StatFormula),
+}
+
+impl GroupStat {
+    fn name(&self) -> String {
+        match self {
+            GroupStat::Stat(stat) => stat.name().to_string(),
+            GroupStat::Formula(formula) => formula.source().to_string(),
+        }
+    }
+
+    fn values(&self, group: &PlayerGroup<'_>) -> Vec<f64> {
+        let iter = group.players.iter().copied();
+        let stats = match self {
+            GroupStat::Stat(stat) => LbCommand::get_stats(stat, iter),
+            GroupStat::Formula(formula) => LbCommand::get_formula_stats(formula, iter),
+        };
+
+        stats
+            .into_iter()
+            .map(|(_, _, value)| value)
+            .filter(|value| value.is_finite())
+            .collect()
+    }
+
+    /// The formula when one is given, the stat otherwise.
+    async fn resolve(
+        stat: Option<UserStatOption>,
+        formula: Option<&str>,
+        interaction: &InteractionCreate,
+        context: &Context<'_>,
+    ) -> anyhow::Result<anyhow::Result<Option<Self>>> {
+        Ok(match formula {
+            Some(formula) => resolve_formula(formula, interaction.guild_id, context)
+                .await?
+                .map(|formula| Some(GroupStat::Formula(formula))),
+            None => Ok(stat.map(GroupStat::Stat)),
+        })
+    }
+}
+
+impl AlbCommand {
+    async fn countries(
+        countries: CountriesSubCommand,
+        interaction: &InteractionCreate,
+        context: &Context<'_>,
+    ) -> anyhow::Result<anyhow::Result<()>> {
+        let stat = match GroupStat::resolve(countries.stat, countries.formula.as_deref(), interaction, context).await? {
+            Ok(stat) => stat.unwrap_or(GroupStat::Stat(UserStatOption::TR)),
+            Err(err) => return Ok(Err(err)),
+        };
+        let aggregate = countries.aggregate.unwrap_or(AggregateOption::Mean);
+
+        let leaderboard = context.fetch_full_leaderboard(None::<&str>).await?;
+        let Some(data) = &leaderboard.data else {
+            return Ok(Err(anyhow!("❌ Couldn't fetch leaderboard data!")));
+        };
+
+        let min_players = countries.min_players.unwrap_or(DEFAULT_MIN_PLAYERS) as usize;
+        let ranked = by_country(data)
+            .into_iter()
+            .filter(|group| group.players.len() >= min_players)
+            .filter_map(|group| {
+                let value = match countries.ranking {
+                    CountryRankingOption::Players => group.players.len() as f64,
+                    CountryRankingOption::XRanks => group.x_ranks() as f64,
+                    CountryRankingOption::Stat => aggregate.apply(&stat.values(&group))?,
+                };
+                Some((group, value))
+            })
+            .sorted_by(|(_, a), (_, b)| b.total_cmp(a))
+            .collect::<Vec<_>>();
+        if ranked.is_empty() {
+            return Ok(Err(anyhow!("❌ No country has at least {min_players} ranked players")));
+        }
+
+        let label = match countries.ranking {
+            CountryRankingOption::Players => "Players".to_string(),
+            CountryRankingOption::XRanks => "X rank players".to_string(),
+            CountryRankingOption::Stat => format!("{} {}", aggregate.name(), stat.name()),
+        };
+        let format_value = |value: f64| match countries.ranking {
+            CountryRankingOption::Stat => format!("{value:.4}"),
+            _ => format!("{value:.0}"),
+        };
+
+        let pages = ranked.len().div_ceil(COUNTRIES_PAGE_SIZE);
+        let page = (countries.page.unwrap_or(1) as usize).min(pages);
+        let shown = &ranked[(page - 1) * COUNTRIES_PAGE_SIZE..(page * COUNTRIES_PAGE_SIZE).min(ranked.len())];
+
+        let rows = std::iter::once(vec![
+            "#".to_string(),
+            "Country".to_string(),
+            "Players".to_string(),
+            label.clone(),
+        ])
+        .chain(shown.iter().enumerate().map(|(index, (group, value))| {
+            vec![
+                ((page - 1) * COUNTRIES_PAGE_SIZE + index + 1).to_string(),
+                group.name.clone(),
+                group.players.len().to_string(),
+                format_value(*value),
+            ]
+        }))
+        .collect::<Vec<_>>();
+
+        let embed = create_embed(None, context).await?
+            .title("COUNTRY LEADERBOARD")
+            .description(format!(
+                "```\n{}\n```Page {page}/{pages}, {} countries with at least {min_players} ranked players",
+                format_table(&rows),
+                ranked.len()
+            ))
+            .build();
+
+        let dark_mode = countries.dark_mode.unwrap_or(false);
+        let max = shown.iter().map(|(_, value)| *value).fold(0.0, f64::max);
+        let chart = Chart {
+            kind: ChartKind::Bar,
+            labels: shown.iter().map(|(group, _)| group.name.clone()).collect(),
+            datasets: vec![ChartDataset {
+                label,
+                data: shown.iter().map(|(_, value)| *value).collect(),
+                color: VsCommand::get_background_colors(dark_mode)[0],
+            }],
+            min: 0.0,
+            max: chart_max(max),
+            dark_mode,
+        }
+        .render(context.chart_backend)
+        .await?;
+
+        chart.send_with_embeds("", &[embed], interaction, context).await?;
+
+        Ok(Ok(()))
+    }
+
+    async fn ranks(
+        ranks: RanksSubCommand,
+        interaction: &InteractionCreate,
+        context: &Context<'_>,
+    ) -> anyhow::Result<anyhow::Result<()>> {
+        let extra = match GroupStat::resolve(ranks.stat, ranks.formula.as_deref(), interaction, context).await? {
+            Ok(extra) => extra,
+            Err(err) => return Ok(Err(err)),
+        };
+        let aggregate = ranks.aggregate.unwrap_or(AggregateOption::Mean);
+
+        let leaderboard = context.fetch_full_leaderboard(None::<&str>).await?;
+        let Some(data) = &leaderboard.data else {
+            return Ok(Err(anyhow!("❌ Couldn't fetch leaderboard data!")));
+        };
+
+        let groups = by_rank(data);
+        if groups.is_empty() {
+            return Ok(Err(anyhow!("❌ No ranked player in the leaderboard")));
+        }
+
+        let columns = [
+            GroupStat::Stat(UserStatOption::TR),
+            GroupStat::Stat(UserStatOption::APM),
+            GroupStat::Stat(UserStatOption::PPS),
+            GroupStat::Stat(UserStatOption::VS),
+        ]
+        .into_iter()
+        .chain(extra)
+        .collect::<Vec<_>>();
+
+        let values = groups
+            .iter()
+            .map(|group| {
+                columns
+                    .iter()
+                    .map(|column| aggregate.apply(&column.values(group)))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        let rows = std::iter::once(
+            ["Rank".to_string(), "Players".to_string()]
+                .into_iter()
+                .chain(columns.iter().map(GroupStat::name))
+                .collect::<Vec<_>>(),
+        )
+        .chain(groups.iter().zip(&values).map(|(group, values)| {
+            [group.name.clone(), group.players.len().to_string()]
+                .into_iter()
+                .chain(values.iter().map(|value| match value {
+                    Some(value) => format!("{value:.2}"),
+                    None => "-".to_string(),
+                }))
+                .collect::<Vec<_>>()
+        }))
+        .collect::<Vec<_>>();
+
+        let embed = create_embed(None, context).await?
+            .title("RANK LEADERBOARD")
+            .description(format!(
+                "```\n{}\n```{} of the players of each rank, {} ranked players",
+                format_table(&rows),
+                aggregate.name(),
+                groups.iter().map(|group| group.players.len()).sum::<usize>()
+            ))
+            .build();
+
+        // The extra stat is the last column when given, the TR the first one otherwise
+        let charted = if columns.len() > 4 { columns.len() - 1 } else { 0 };
+        let data = values
+            .iter()
+            .map(|values| values[charted].unwrap_or(0.0))
+            .collect::<Vec<_>>();
+        let dark_mode = ranks.dark_mode.unwrap_or(false);
+        let chart = Chart {
+            kind: ChartKind::Bar,
+            labels: groups.iter().map(|group| group.name.clone()).collect(),
+            min: 0.0,
+            max: chart_max(data.iter().copied().fold(0.0, f64::max)),
+            datasets: vec![ChartDataset {
+                label: format!("{} {}", aggregate.name(), columns[charted].name()),
+                data,
+                color: VsCommand::get_background_colors(dark_mode)[0],
+            }],
+            dark_mode,
+        }
+        .render(context.chart_backend)
+        .await?;
+
+        chart.send_with_embeds("", &[embed], interaction, context).await?;
+
+        Ok(Ok(()))
+    }
+}
+
+#[async_trait::async_trait]
+impl RunnableCommand for AlbCommand {
+    async fn run(
+        _shard: u64,
+        interaction: &InteractionCreate,
+        data: Box<CommandData>,
+        context: &Context<'_>,
+    ) -> anyhow::Result<anyhow::Result<()>> {
+        log::info!("alb command");
+        let _command_timer = Timer::new("alb command");
+        context.defer_response(interaction).await?;
+        let model = Self::from_interaction(CommandInputData {
+            options: data.options,
+            resolved: data.resolved.map(Cow::Owned),
+        })?;
+
+        match model {
+            AlbCommand::Countries(countries) => Self::countries(countries, interaction, context).await,
+            AlbCommand::Ranks(ranks) => Self::ranks(ranks, interaction, context).await,
+        }
+    }
+}
@@ -0,0 +1,204 @@
+use std::borrow::Cow;
+
+use anyhow::anyhow;
+use itertools::Itertools;
+use twilight_interactions::command::{CommandInputData, CommandModel, CreateCommand};
+use twilight_model::{
+    application::interaction::application_command::CommandData,
+    channel::message::embed::EmbedField,
+    gateway::payload::incoming::InteractionCreate,
+    guild::Permissions,
+};
+
+use crate::{
+    context::Context,
+    interactions::commands::subcommands::announcements::{
+        channel_sub_command::ChannelSubCommand, disable_sub_command::DisableSubCommand,
+        list_sub_command::ListSubCommand, opt_in_sub_command::OptInSubCommand,
+        opt_out_sub_command::OptOutSubCommand, track_sub_command::TrackSubCommand,
+        untrack_sub_command::UntrackSubCommand,
+    },
+    services::announcement::AnnouncementPDO,
+    utils::{box_commands::RunnableCommand, create_embed::create_embed, timer::Timer},
+};
+
+#[derive(CreateCommand, CommandModel)]
+#[command(name = "announcements", desc = "Announce the rank ups, milestones and personal bests of players")]
+pub enum AnnouncementsCommand {
+    #[command(name = "channel")]
+    /// Post the announcements of this server in a channel
+    Channel(ChannelSubCommand),
+    #[command(name = "disable")]
+    /// Stop posting announcements in this server
+    Disable(DisableSubCommand),
+    #[command(name = "track")]
+    /// Announce the progress of a tetrio player in this server
+    Track(TrackSubCommand),
+    #[command(name = "untrack")]
+    /// Stop announcing a tracked tetrio player in this server
+    Untrack(UntrackSubCommand),
+    #[command(name = "list")]
+    /// Show the announcement channel and the tracked players of this server
+    List(ListSubCommand),
+    #[command(name = "optin")]
+    /// Announce the progress of your linked tetrio account
+    OptIn(OptInSubCommand),
+    #[command(name = "optout")]
+    /// Never announce the progress of your linked tetrio account
+    OptOut(OptOutSubCommand),
+}
+
+impl AnnouncementsCommand {
+    fn can_manage(interaction: &InteractionCreate) -> bool {
+        interaction
+            .member
+            .as_ref()
+            .and_then(|member| member.permissions)
+            .map(|permissions| permissions.contains(Permissions::MANAGE_GUILD))
+            .unwrap_or(false)
+    }
+
+    /// The server the command is used in, checking that the member can configure it when needed.
+    fn guild(interaction: &InteractionCreate, manage: bool) -> anyhow::Result<u64> {
+        let Some(guild_id) = interaction.guild_id else {
+            return Err(anyhow!("❌ Announcements can only be configured in a server"));
+        };
+        if manage && !Self::can_manage(interaction) {
+            return Err(anyhow!("❌ You need the Manage Server permission to configure announcements"));
+        }
+
+        Ok(guild_id.get())
+    }
+}
+
+#[async_trait::async_trait]
+impl RunnableCommand for AnnouncementsCommand {
+    async fn run(
+        _shard: u64,
+        interaction: &InteractionCreate,
+        data: Box<CommandData>,
+        context: &Context<'_>,
+    ) -> anyhow::Result<anyhow::Result<()>> {
+        log::info!("announcements command");
+        let _command_timer = Timer::new("announcements command");
+        context.defer_response(interaction).await?;
+        let model = Self::from_interaction(CommandInputData {
+            options: data.options,
+            resolved: data.resolved.map(Cow::Owned),
+        })?;
+
+        let opt_in = matches!(model, AnnouncementsCommand::OptIn(_));
+        let content = match model {
+            AnnouncementsCommand::Channel(channel) => {
+                let guild_id = match Self::guild(interaction, true) {
+                    Ok(guild_id) => guild_id,
+                    Err(err) => return Ok(Err(err)),
+                };
+                AnnouncementPDO::upsert_channel(context, guild_id, channel.channel.get()).await?;
+
+                format!(
+                    "✅ Announcements will be posted in <#{}>, for the tracked players and the linked members of this server",
+                    channel.channel
+                )
+            }
+            AnnouncementsCommand::Disable(_) => {
+                let guild_id = match Self::guild(interaction, true) {
+                    Ok(guild_id) => guild_id,
+                    Err(err) => return Ok(Err(err)),
+                };
+                if !AnnouncementPDO::delete_channel(context, guild_id).await? {
+                    return Ok(Err(anyhow!("❌ Announcements are not enabled in this server")));
+                }
+
+                "✅ Announcements won't be posted in this server anymore".to_string()
+            }
+            AnnouncementsCommand::Track(track) => {
+                let guild_id = match Self::guild(interaction, true) {
+                    Ok(guild_id) => guild_id,
+                    Err(err) => return Ok(Err(err)),
+                };
+                let user = context
+                    .tetrio_client
+                    .fetch_user_info(&track.tetrio_user.to_lowercase())
+                    .await?;
+                let Some(data) = &user.data else {
+                    return Ok(Err(anyhow!("❌ Couldn't find tetrio user {}", track.tetrio_user)));
+                };
+
+                AnnouncementPDO::upsert_tracked_player(context, guild_id, &data.id.to_string(), &data.username).await?;
+
+                format!("✅ The progress of **{}** will be announced in this server", data.username)
+            }
+            AnnouncementsCommand::Untrack(untrack) => {
+                let guild_id = match Self::guild(interaction, true) {
+                    Ok(guild_id) => guild_id,
+                    Err(err) => return Ok(Err(err)),
+                };
+                if !AnnouncementPDO::delete_tracked_player(context, guild_id, &untrack.tetrio_user).await? {
+                    return Ok(Err(anyhow!("❌ {} is not tracked in this server", untrack.tetrio_user)));
+                }
+
+                format!("✅ {} is not tracked anymore", untrack.tetrio_user)
+            }
+            AnnouncementsCommand::List(_) => {
+                let guild_id = match Self::guild(interaction, false) {
+                    Ok(guild_id) => guild_id,
+                    Err(err) => return Ok(Err(err)),
+                };
+                let channel = AnnouncementPDO::fetch_channel(context, guild_id).await?;
+                let tracked = AnnouncementPDO::fetch_guild_tracked_players(context, guild_id).await?;
+
+                let embed = create_embed(None, context).await?
+                    .title("ANNOUNCEMENTS")
+                    .description(match channel {
+                        Some(channel) => format!(
+                            "Posted in <#{}>, linked members are announced unless they opted out",
+                            channel.channel_id
+                        ),
+                        None => "Announcements are disabled, use `/announcements channel` to enable them".to_string(),
+                    })
+                    .field(EmbedField {
+                        inline: false,
+                        name: "Tracked players".to_string(),
+                        value: if tracked.is_empty() {
+                            "None".to_string()
+                        } else {
+                            tracked.iter().map(|player| player.username.as_str()).join(", ")
+                        },
+                    })
+                    .build();
+
+                context
+                    .http_client
+                    .interaction(context.application.id)
+                    .update_response(&interaction.token)
+                    .embeds(Some(&[embed]))?
+                    .await?;
+
+                return Ok(Ok(()));
+            }
+            // Opting in and out follows the discord account in every server
+            AnnouncementsCommand::OptIn(_) | AnnouncementsCommand::OptOut(_) => {
+                let Some(discord_id) = interaction.author_id().map(|id| id.get()) else {
+                    return Ok(Err(anyhow!("❌ Couldn't find the author of the command")));
+                };
+                AnnouncementPDO::upsert_preference(context, discord_id, opt_in).await?;
+
+                if opt_in {
+                    "✅ The progress of your linked tetrio account will be announced in the servers you are in".to_string()
+                } else {
+                    "✅ The progress of your linked tetrio account won't be announced anymore, even where it is tracked".to_string()
+                }
+            }
+        };
+
+        context
+            .http_client
+            .interaction(context.application.id)
+            .update_response(&interaction.token)
+            .content(Some(&content))?
+            .await?;
+
+        Ok(Ok(()))
+    }
+}
@@ -0,0 +1,203 @@
+use std::{borrow::Cow, collections::HashMap, time::UNIX_EPOCH};
+
+use anyhow::anyhow;
+use tetrio_api::models::{packet::Packet, users::user_rank::UserRank};
+use twilight_interactions::command::{CommandInputData, CommandModel, CreateCommand};
+use twilight_model::{
+    application::interaction::application_command::CommandData,
+    channel::message::embed::EmbedField,
+    gateway::payload::incoming::InteractionCreate,
+};
+
+use crate::{
+    context::Context,
+    interactions::commands::options::user_rank_option::UserRankOption,
+    models::rank_cutoff::RankCutoffData,
+    services::rank_cutoff::RankCutoffPDO,
+    utils::{
+        aggregate_leaderboard::RANKS,
+        box_commands::RunnableCommand,
+        charts::{Chart, ChartDataset, ChartKind},
+        create_embed::create_embed,
+        rank_cutoffs::{season_end, Trend},
+        table::format_table,
+        timer::Timer,
+    },
+};
+
+use super::vs::VsCommand;
+
+const DEFAULT_HISTORY_DAYS: i64 = 7;
+/// TR between two grid lines of the chart is a multiple of this
+const CHART_ROUNDING: f64 = 100.0;
+
+#[derive(CreateCommand, CommandModel)]
+#[command(name = "cutoffs", desc = "Get the TR cutoffs of the ranks, their recent changes and a projection")]
+pub struct CutoffsCommand {
+    /// The rank to chart and project, defaults to X
+    rank: Option<UserRankOption>,
+
+    #[command(min_value = 1, max_value = 90)]
+    /// How many days of history to chart and project from, defaults to 7
+    days: Option<i64>,
+
+    /// Get a dark mode chart
+    dark_mode: Option<bool>,
+}
+
+impl CutoffsCommand {
+    fn cutoffs_by_rank(snapshots: Vec<RankCutoffData>) -> HashMap<String, f64> {
+        snapshots.into_iter().map(|snapshot| (snapshot.rank, snapshot.tr)).collect()
+    }
+
+    fn format_change(current: f64, previous: Option<f64>) -> String {
+        match previous {
+            Some(previous) => {
+                let change = current - previous;
+                format!("{}{change:.0}", if change >= 0.0 { "+" } else { "" })
+            }
+            None => "-".to_string(),
+        }
+    }
+
+    /// Relative age of a snapshot, as the label of the chart.
+    fn format_age(taken_at: i64, now: i64) -> String {
+        let hours = (now - taken_at) as f64 / 3600.0;
+        if hours < 48.0 {
+            format!("-{hours:.0}h")
+        } else {
+            format!("-{:.1}d", hours / 24.0)
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl RunnableCommand for CutoffsCommand {
+    async fn run(
+        _shard: u64,
+        interaction: &InteractionCreate,
+        data: Box<CommandData>,
+        context: &Context<'_>,
+    ) -> anyhow::Result<anyhow::Result<()>> {
+        log::info!("cutoffs command");
+        let _command_timer = Timer::new("cutoffs command");
+        context.defer_response(interaction).await?;
+        let model = Self::from_interaction(CommandInputData {
+            options: data.options,
+            resolved: data.resolved.map(Cow::Owned),
+        })?;
+
+        let Packet { data: Some(data), .. } = context.tetrio_client.fetch_leagueranks().await? else {
+            return Ok(Err(anyhow!("❌ Couldn't find user ranks data!")));
+        };
+
+        let day_ago = Self::cutoffs_by_rank(RankCutoffPDO::fetch_snapshots_before(context, 24).await?);
+        let week_ago = Self::cutoffs_by_rank(RankCutoffPDO::fetch_snapshots_before(context, 24 * 7).await?);
+
+        let rows = std::iter::once(
+            ["Rank", "TR", "24h", "7d", "Players", "Top %"]
+                .map(String::from)
+                .to_vec(),
+        )
+        .chain(RANKS.iter().filter_map(|rank| {
+            let league_rank = data.data.ranks.get(rank)?;
+            let name = rank.to_string();
+            Some(vec![
+                name.to_uppercase(),
+                format!("{:.0}", league_rank.tr),
+                Self::format_change(league_rank.tr, day_ago.get(&name).copied()),
+                Self::format_change(league_rank.tr, week_ago.get(&name).copied()),
+                league_rank.count.to_string(),
+                format!("{:.2}", league_rank.percentile * 100.0),
+            ])
+        }))
+        .collect::<Vec<_>>();
+
+        let rank: UserRank = model.rank.map(UserRank::from).unwrap_or(UserRank::X);
+        let days = model.days.unwrap_or(DEFAULT_HISTORY_DAYS);
+        let history = RankCutoffPDO::fetch_history(context, &rank.to_string(), days as i32).await?;
+        let points = history
+            .iter()
+            .map(|snapshot| (snapshot.taken_at, snapshot.tr))
+            .collect::<Vec<_>>();
+        let trend = Trend::fit(&points);
+        let now = UNIX_EPOCH.elapsed()?.as_secs() as i64;
+
+        let projection = match (trend, season_end()) {
+            (Some(trend), Some(end)) if end > now => {
+                let projected = trend.at(end);
+                let current = data.data.ranks.get(&rank).map(|league_rank| league_rank.tr).unwrap_or(projected);
+                format!(
+                    "**{projected:.0} TR** at the end of the season <t:{end}:R> ({} from now), {}{:.0} TR per day over the last {days} days",
+                    Self::format_change(projected, Some(current)),
+                    if trend.slope >= 0.0 { "+" } else { "" },
+                    trend.slope * 86400.0
+                )
+            }
+            (Some(trend), _) => format!(
+                "{}{:.0} TR per day over the last {days} days, the end of the season isn't known",
+                if trend.slope >= 0.0 { "+" } else { "" },
+                trend.slope * 86400.0
+            ),
+            (None, _) => "Not enough snapshots yet to project the cutoff".to_string(),
+        };
+
+        let embed = create_embed(None, context).await?
+            .title("RANK CUTOFFS")
+            .description(format!("```\n{}\n```", format_table(&rows)))
+            .field(EmbedField {
+                inline: false,
+                name: format!("{} cutoff projection", rank.to_string().to_uppercase()),
+                value: projection,
+            })
+            .build();
+
+        // a trend needs at least two snapshots, which is also enough for a chart
+        let Some(trend) = trend else {
+            context
+                .http_client
+                .interaction(context.application.id)
+                .update_response(&interaction.token)
+                .embeds(Some(&[embed]))?
+                .await?;
+
+            return Ok(Ok(()));
+        };
+
+        let low = points.iter().map(|(_, tr)| *tr).fold(f64::INFINITY, f64::min);
+        let high = points.iter().map(|(_, tr)| *tr).fold(f64::NEG_INFINITY, f64::max);
+        let min = (low / CHART_ROUNDING).floor() * CHART_ROUNDING;
+        let max = ((high / CHART_ROUNDING).ceil() * CHART_ROUNDING).max(min + CHART_ROUNDING);
+
+        let dark_mode = model.dark_mode.unwrap_or(false);
+        let colors = VsCommand::get_background_colors(dark_mode);
+        let chart = Chart {
+            kind: ChartKind::Line,
+            labels: history
+                .iter()
+                .map(|snapshot| Self::format_age(snapshot.taken_at, now))
+                .collect(),
+            datasets: vec![
+                ChartDataset {
+                    label: format!("{} cutoff", rank.to_string().to_uppercase()),
+                    data: points.iter().map(|(_, tr)| *tr).collect(),
+                    color: colors[0],
+                },
+                ChartDataset {
+                    label: "Trend".to_string(),
+                    data: points.iter().map(|(taken_at, _)| trend.at(*taken_at)).collect(),
+                    color: colors[1],
+                },
+            ],
+            min,
+            max,
+            dark_mode,
+        }
+        .render(context.chart_backend)
+        .await?;
+
+        chart.send_with_embeds("", &[embed], interaction, context).await?;
+
+        Ok(Ok(()))
+    }
+}
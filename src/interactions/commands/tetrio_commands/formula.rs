@@ -0,0 +1,208 @@
+use std::borrow::Cow;
+
+use anyhow::anyhow;
+use itertools::Itertools;
+use twilight_interactions::command::{CommandInputData, CommandModel, CreateCommand};
+use twilight_model::{
+    application::interaction::application_command::CommandData,
+    channel::message::embed::EmbedField,
+    gateway::payload::incoming::InteractionCreate,
+    guild::Permissions,
+};
+
+use crate::{
+    context::Context,
+    interactions::commands::subcommands::formula::{
+        add_sub_command::AddSubCommand, list_sub_command::ListSubCommand,
+        remove_sub_command::RemoveSubCommand, test_sub_command::TestSubCommand,
+        variables_sub_command::VariablesSubCommand,
+    },
+    services::stat_formula::StatFormulaPDO,
+    utils::{
+        box_commands::RunnableCommand,
+        create_embed::create_embed,
+        stat_formula::{resolve_formula, FormulaVariables, StatFormula, VARIABLES},
+        stats::calculate_stats,
+        timer::Timer,
+    },
+};
+
+use super::vs::VsCommand;
+
+#[derive(CreateCommand, CommandModel)]
+#[command(name = "formula", desc = "Manage the stat formulas of this server")]
+pub enum FormulaCommand {
+    #[command(name = "add")]
+    /// Save a formula for this server
+    Add(AddSubCommand),
+    #[command(name = "remove")]
+    /// Remove a formula from this server
+    Remove(RemoveSubCommand),
+    #[command(name = "list")]
+    /// List the formulas of this server
+    List(ListSubCommand),
+    #[command(name = "test")]
+    /// Evaluate a formula for a user
+    Test(TestSubCommand),
+    #[command(name = "variables")]
+    /// List the variables a formula can use
+    Variables(VariablesSubCommand),
+}
+
+impl FormulaCommand {
+    fn can_manage(interaction: &InteractionCreate) -> bool {
+        interaction
+            .member
+            .as_ref()
+            .and_then(|member| member.permissions)
+            .map(|permissions| permissions.contains(Permissions::MANAGE_GUILD))
+            .unwrap_or(false)
+    }
+}
+
+#[async_trait::async_trait]
+impl RunnableCommand for FormulaCommand {
+    async fn run(
+        _shard: u64,
+        interaction: &InteractionCreate,
+        data: Box<CommandData>,
+        context: &Context<'_>,
+    ) -> anyhow::Result<anyhow::Result<()>> {
+        log::info!("formula command");
+        let _command_timer = Timer::new("formula command");
+        context.defer_response(interaction).await?;
+        let model = Self::from_interaction(CommandInputData {
+            options: data.options,
+            resolved: data.resolved.map(Cow::Owned),
+        })?;
+
+        let Some(guild_id) = interaction.guild_id else {
+            return Ok(Err(anyhow!("❌ Formulas can only be used in a server")));
+        };
+
+        let content = match model {
+            FormulaCommand::Add(add) => {
+                if !Self::can_manage(interaction) {
+                    return Ok(Err(anyhow!("❌ You need the Manage Server permission to add formulas")));
+                }
+                let name = add.name.trim().to_lowercase();
+                if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                    return Ok(Err(anyhow!("❌ Formula names can only contain letters, numbers and underscores")));
+                }
+                if VARIABLES.iter().any(|(variable, _)| *variable == name) {
+                    return Ok(Err(anyhow!("❌ `{name}` is already a variable, choose another name")));
+                }
+                let formula = match StatFormula::parse(&add.expression) {
+                    Ok(formula) => formula,
+                    Err(err) => return Ok(Err(anyhow!("❌ Invalid formula: {err}"))),
+                };
+                let Some(author) = interaction.author_id() else {
+                    return Ok(Err(anyhow!("❌ Couldn't find the author of the command")));
+                };
+
+                StatFormulaPDO::upsert_formula(context, guild_id.get(), &name, formula.source(), author.get()).await?;
+
+                format!("✅ Saved formula `{name}` = `{}`", formula.source())
+            }
+            FormulaCommand::Remove(remove) => {
+                if !Self::can_manage(interaction) {
+                    return Ok(Err(anyhow!("❌ You need the Manage Server permission to remove formulas")));
+                }
+                let name = remove.name.trim().to_lowercase();
+                if !StatFormulaPDO::delete_formula(context, guild_id.get(), &name).await? {
+                    return Ok(Err(anyhow!("❌ Couldn't find formula `{name}`")));
+                }
+
+                format!("✅ Removed formula `{name}`")
+            }
+            FormulaCommand::List(_) => {
+                let formulas = StatFormulaPDO::fetch_formulas(context, guild_id.get()).await;
+                let formulas = if formulas.is_empty() {
+                    "No formulas have been saved yet.".to_string()
+                } else {
+                    formulas
+                        .iter()
+                        .map(|formula| format!("**{}**: `{}`", formula.name, formula.expression))
+                        .join("\n")
+                };
+
+                let embed = create_embed(None, context).await?
+                    .title("FORMULAS")
+                    .description(formulas)
+                    .field(EmbedField {
+                        inline: false,
+                        name: "Variables".to_string(),
+                        value: format!(
+                            "{}\nUse `/formula variables` for what they mean",
+                            VARIABLES.iter().map(|(name, _)| format!("`{name}`")).join(", ")
+                        ),
+                    })
+                    .field(EmbedField {
+                        inline: false,
+                        name: "Functions".to_string(),
+                        value: "`min(a, b, ...)`, `max(a, b, ...)`, `log(x)`, `log(x, base)`, `pow(x, y)`, `sqrt(x)`, `abs(x)`, `exp(x)`\nOperators: `+ - * / ^`".to_string(),
+                    })
+                    .build();
+
+                context
+                    .http_client
+                    .interaction(context.application.id)
+                    .update_response(&interaction.token)
+                    .embeds(Some(&[embed]))?
+                    .await?;
+
+                return Ok(Ok(()));
+            }
+            FormulaCommand::Variables(_) => {
+                let embed = create_embed(None, context).await?
+                    .title("FORMULA VARIABLES")
+                    .description(
+                        VARIABLES
+                            .iter()
+                            .map(|(name, description)| format!("`{name}`: {description}"))
+                            .join("\n"),
+                    )
+                    .build();
+
+                context
+                    .http_client
+                    .interaction(context.application.id)
+                    .update_response(&interaction.token)
+                    .embeds(Some(&[embed]))?
+                    .await?;
+
+                return Ok(Ok(()));
+            }
+            FormulaCommand::Test(test) => {
+                let formula = match resolve_formula(&test.formula, Some(guild_id), context).await? {
+                    Ok(formula) => formula,
+                    Err(err) => return Ok(Err(err)),
+                };
+                let player = match VsCommand::resolve_user(test.user, context).await? {
+                    Ok(player) => player,
+                    Err(err) => return Ok(Err(err)),
+                };
+                let name = player.name;
+                let variables = FormulaVariables::from_stats(&calculate_stats(player.stats));
+                let variables = match player.games {
+                    Some((won, played)) => variables.with_games(won, played),
+                    None => variables,
+                };
+
+                match formula.evaluate(&variables) {
+                    Some(value) => format!("`{}` for {name}: **{value:.4}**", formula.source()),
+                    None => format!("❌ `{}` can't be computed for {name}", formula.source()),
+                }
+            }
+        };
+
+        context
+            .http_client
+            .interaction(context.application.id)
+            .update_response(&interaction.token)
+            .content(Some(&content))?
+            .await?;
+
+        Ok(Ok(()))
+    }
+}
@@ -0,0 +1,132 @@
+use std::borrow::Cow;
+
+use anyhow::anyhow;
+use itertools::Itertools;
+use twilight_interactions::command::{CommandInputData, CommandModel, CreateCommand};
+use twilight_model::{
+    application::interaction::application_command::CommandData,
+    gateway::payload::incoming::InteractionCreate,
+};
+
+use crate::{
+    context::Context,
+    interactions::commands::options::{user_rank_option::UserRankOption, user_stat_options::UserStatOption},
+    utils::{
+        box_commands::RunnableCommand, guild_members::resolve_guild_accounts, stat_formula::resolve_formula,
+        timer::Timer,
+    },
+};
+
+use super::lb::LbCommand;
+
+const DEFAULT_PAGE_SIZE: i64 = 20;
+
+#[derive(CreateCommand, CommandModel)]
+#[command(name = "glb", desc = "Get the leaderboard of the members of this server")]
+pub struct GlbCommand {
+    /// The type of stat to use for the leaderboard
+    leaderboard_stat: UserStatOption,
+
+    /// Only players in this rank will be displayed
+    rank: Option<UserRankOption>,
+
+    /// A saved formula name or an expression to use instead of the leaderboard stat
+    formula: Option<String>,
+
+    #[command(min_value = 1)]
+    /// The page to display, defaults to the first one
+    page: Option<i64>,
+
+    #[command(min_value = 1, max_value = 50)]
+    /// How many places to display per page, defaults to 20
+    limit: Option<i64>,
+
+    /// Start from the lowest value instead of the highest
+    reverse: Option<bool>,
+}
+
+#[async_trait::async_trait]
+impl RunnableCommand for GlbCommand {
+    async fn run(
+        _shard: u64,
+        interaction: &InteractionCreate,
+        data: Box<CommandData>,
+        context: &Context<'_>,
+    ) -> anyhow::Result<anyhow::Result<()>> {
+        log::info!("glb command");
+        let _command_timer = Timer::new("glb command");
+        context.defer_response(interaction).await?;
+        let model = Self::from_interaction(CommandInputData {
+            options: data.options,
+            resolved: data.resolved.map(Cow::Owned),
+        })?;
+
+        let Some(guild_id) = interaction.guild_id else {
+            return Ok(Err(anyhow!("❌ The server leaderboard can only be used in a server")));
+        };
+
+        let members = resolve_guild_accounts(guild_id.get(), context).await?;
+        if members.accounts.is_empty() {
+            return Ok(Err(anyhow!("❌ No member of this server has a known tetrio account yet, they can use /link")));
+        }
+
+        let leaderboard = context.fetch_full_leaderboard(None::<&str>).await?;
+        let Some(data) = &leaderboard.data else {
+            return Ok(Err(anyhow!("❌ Couldn't fetch leaderboard data!")));
+        };
+
+        let iter = LbCommand::filter_rank(data, &model.rank)
+            .filter(|(_, user)| members.accounts.contains_key(&user.id.to_string()));
+
+        let stats = match &model.formula {
+            Some(formula) => {
+                let formula = match resolve_formula(formula, Some(guild_id), context).await? {
+                    Ok(formula) => formula,
+                    Err(err) => return Ok(Err(err)),
+                };
+                LbCommand::get_formula_stats(&formula, iter)
+            }
+            None => LbCommand::get_stats(&model.leaderboard_stat, iter),
+        };
+
+        let reverse = model.reverse.unwrap_or(false);
+        let stats = stats
+            .into_iter()
+            .sorted_by(|(_, _, a), (_, _, b)| if reverse { a.total_cmp(b) } else { b.total_cmp(a) })
+            .collect::<Vec<_>>();
+        if stats.is_empty() {
+            return Ok(Err(anyhow!("❌ No member of this server matches this leaderboard")));
+        }
+
+        let limit = model.limit.unwrap_or(DEFAULT_PAGE_SIZE) as usize;
+        let pages = stats.len().div_ceil(limit);
+        let page = (model.page.unwrap_or(1) as usize).min(pages);
+
+        let content = stats
+            .iter()
+            .enumerate()
+            .skip((page - 1) * limit)
+            .take(limit)
+            .map(|(index, (rank, username, value))| {
+                format!("#{}: {} (Rank: #{}): ({:.4}) ", index + 1, username, rank + 1, value)
+            })
+            .join("\n");
+
+        let pending = match members.pending {
+            0 => String::new(),
+            pending => format!(", {pending} members not looked up yet"),
+        };
+        let content = format!(
+            "```\n{content}\n```Page {page}/{pages}, {} ranked members{pending}",
+            stats.len()
+        );
+
+        let interaction_client = context.http_client.interaction(context.application.id);
+        match interaction_client.update_response(&interaction.token).content(Some(&content)) {
+            Ok(response) => response.await?,
+            Err(_) => return Ok(Err(anyhow!("❌ Message content was too long!"))),
+        };
+
+        Ok(Ok(()))
+    }
+}
@@ -2,7 +2,10 @@ use std::borrow::Cow;
 
 use anyhow::anyhow;
 use itertools::Itertools;
-use tetrio_api::models::{common::APIstring, users::user_leaderboard::LeaderboardUser};
+use tetrio_api::models::{
+    common::APIstring,
+    users::{user_leaderboard::LeaderboardUser, user_rank::UserRank},
+};
 use twilight_interactions::command::{CommandInputData, CommandModel, CreateCommand};
 use twilight_model::{
     application::interaction::application_command::CommandData,
@@ -12,13 +15,16 @@ use twilight_model::{
 use crate::{
     context::Context,
     utils::{
+        archetype::{Archetype, ArchetypeClassifier},
         box_commands::RunnableCommand,
+        leaderboard_browser::LeaderboardView,
+        stat_formula::{resolve_formula, FormulaVariables, StatFormula},
         stats::{calculate_stats, PlayerStats},
     },
 };
 
 use crate::interactions::commands::options::{
-    user_rank_option::UserRankOption, user_stat_options::UserStatOption,
+    archetype_option::ArchetypeOption, user_rank_option::UserRankOption, user_stat_options::UserStatOption,
 };
 
 use crate::utils::timer::Timer;
@@ -34,7 +40,7 @@ pub struct LbCommand {
     /// How many places to display
     limit: i64,
 
-    /// Where to start searching for players
+    /// Opens the page holding this position of the leaderboard
     position: Option<i64>,
 
     /// Only played in this rank will be displayed
@@ -42,6 +48,12 @@ pub struct LbCommand {
 
     /// country to limit the placements from
     country_code: Option<String>,
+
+    /// A saved formula name or an expression to use instead of the leaderboard stat
+    formula: Option<String>,
+
+    /// Only players with this playstyle archetype will be displayed
+    archetype: Option<ArchetypeOption>,
 }
 
 impl LbCommand {
@@ -79,6 +91,26 @@ impl LbCommand {
             })
     }
 
+    pub fn matches_archetype(
+        user: &LeaderboardUser,
+        archetype: Archetype,
+        classifier: &ArchetypeClassifier,
+    ) -> bool {
+        let (Some(pps), Some(apm), Some(vs)) =
+            (user.league.pps, user.league.apm, user.league.vs) else {
+                return false
+            };
+        classifier.classify_player(PlayerStats {
+            apm,
+            pps,
+            vs,
+            rd: Some(user.league.rd),
+            tr: Some(user.league.tr),
+            glicko: Some(user.league.glicko),
+            rank: user.league.rank.clone(),
+        }) == Some(archetype)
+    }
+
     pub fn get_stats<'a>(
         leaderboart_stat: &UserStatOption,
         iter: impl Iterator<
@@ -189,6 +221,58 @@ impl LbCommand {
                 .collect(),
         }
     }
+
+    /// Replies with a leaderboard that can be browsed with buttons and select menus.
+    pub async fn send_view(
+        mut view: LeaderboardView,
+        data: &[LeaderboardUser],
+        interaction: &InteractionCreate,
+        context: &Context<'_>,
+    ) -> anyhow::Result<anyhow::Result<()>> {
+        let stats = view.stats(data);
+        let (content, components) = view.render(&stats);
+
+        let interaction_client = context.http_client.interaction(context.application.id);
+        match interaction_client.update_response(&interaction.token).content(Some(&content)) {
+            Ok(response) => response.components(Some(&components))?.await?,
+            Err(_) => return Ok(Err(anyhow!("❌ Message content was too long!"))),
+        };
+
+        Ok(Ok(()))
+    }
+
+    pub fn get_formula_stats<'a>(
+        formula: &StatFormula,
+        iter: impl Iterator<
+            Item = (
+                usize,
+                &'a tetrio_api::models::users::user_leaderboard::LeaderboardUser,
+            ),
+        >,
+    ) -> Vec<(usize, APIstring, f64)> {
+        iter.filter_map(|(rank, user)| {
+            let (Some(pps), Some(apm), Some(vs)) =
+                (user.league.pps, user.league.apm, user.league.vs) else {
+                    return None
+                };
+            let stats = calculate_stats(PlayerStats {
+                apm,
+                pps,
+                vs,
+                rd: Some(user.league.rd),
+                tr: Some(user.league.tr),
+                glicko: Some(user.league.glicko),
+                rank: user.league.rank.clone(),
+            });
+            let variables = FormulaVariables::from_stats(&stats)
+                .with_games(user.league.gameswon as f64, user.league.gamesplayed as f64);
+
+            formula
+                .evaluate(&variables)
+                .map(|value| (rank, user.username.clone(), value))
+        })
+        .collect()
+    }
 }
 
 #[async_trait::async_trait]
@@ -208,23 +292,60 @@ impl RunnableCommand for LbCommand {
             resolved: data.resolved.map(Cow::Owned),
         })?;
 
-        let leaderboard = context
-            .fetch_full_leaderboard(model.country_code.as_deref())
-            .await?;
-
-        let Some(data) = &leaderboard.data else {
+        let Some(data) = context
+            .leaderboard_cache
+            .leaderboard(model.country_code.as_deref(), context)
+            .await?
+        else {
             return Ok(Err(anyhow!("❌ Couldn't fetch leaderboard data!")));
         };
+        let data = &*data;
+
+        // Formulas and archetypes don't fit in the components, these leaderboards can't be browsed
+        if model.formula.is_none() && model.archetype.is_none() {
+            let limit = model.limit.max(1) as usize;
+            let view = LeaderboardView {
+                stat: model.leaderboard_stat,
+                rank: model.rank.map(UserRank::from),
+                reverse: false,
+                page: model.position.map(|position| (position.max(1) as usize - 1) / limit + 1).unwrap_or(1),
+                limit,
+                country: model.country_code.map(|country| country.to_uppercase()),
+            };
+            return Self::send_view(view, data, interaction, context).await;
+        }
+
+        // Archetypes are always relative to the global leaderboard so that labels don't change with the country filter
+        let archetype = match model.archetype {
+            Some(archetype) => Some((Archetype::from(archetype), ArchetypeClassifier::fetch(context).await)),
+            None => None,
+        };
 
-        let iter = Self::filter_rank(data, &model.rank);
+        let iter = Self::filter_rank(data, &model.rank).filter(|(_, user)| match &archetype {
+            Some((archetype, classifier)) => Self::matches_archetype(user, *archetype, classifier),
+            None => true,
+        });
+
+        let stats = match &model.formula {
+            Some(formula) => {
+                let formula = match resolve_formula(formula, interaction.guild_id, context).await? {
+                    Ok(formula) => formula,
+                    Err(err) => return Ok(Err(err)),
+                };
+                Self::get_formula_stats(&formula, iter)
+            }
+            None => Self::get_stats(&model.leaderboard_stat, iter),
+        };
 
-        let v = Self::get_stats(&model.leaderboard_stat, iter)
+        let v = stats
             .into_iter()
             .sorted_by(|(_, _, a), (_, _, b)| b.total_cmp(a));
 
+        // same page as the browsable leaderboards
+        let limit = model.limit.max(1) as usize;
         let skip = model
             .position
-            .map(|position| (position as usize).saturating_sub(model.limit as usize))
+            .map(|position| (position.max(1) as usize - 1) / limit * limit)
             .unwrap_or(0);
 
         let content = v
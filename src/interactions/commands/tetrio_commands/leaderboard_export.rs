@@ -0,0 +1,198 @@
+use std::borrow::Cow;
+
+use anyhow::anyhow;
+use itertools::Itertools;
+use tetrio_api::models::users::user_rank::UserRank;
+use twilight_interactions::command::{CommandInputData, CommandModel, CreateCommand};
+use twilight_model::{
+    application::interaction::application_command::CommandData,
+    gateway::payload::incoming::InteractionCreate,
+    guild::Permissions,
+};
+
+use crate::{
+    context::Context,
+    interactions::commands::subcommands::leaderboard_export::{
+        file_sub_command::FileSubCommand, list_sub_command::ListSubCommand,
+        schedule_sub_command::ScheduleSubCommand, unschedule_sub_command::UnscheduleSubCommand,
+    },
+    models::leaderboard_export::LeaderboardExportData,
+    services::leaderboard_export::LeaderboardExportPDO,
+    utils::{box_commands::RunnableCommand, create_embed::create_embed, leaderboard_export::export_file, timer::Timer},
+};
+
+#[derive(CreateCommand, CommandModel)]
+#[command(name = "lbexport", desc = "Export the full leaderboard with every stat as a csv or json file")]
+pub enum LeaderboardExportCommand {
+    #[command(name = "file")]
+    /// Attach the full leaderboard with every stat as a file
+    File(FileSubCommand),
+    #[command(name = "schedule")]
+    /// Post an export of the leaderboard in a channel every day
+    Schedule(ScheduleSubCommand),
+    #[command(name = "unschedule")]
+    /// Stop posting the daily export in a channel
+    Unschedule(UnscheduleSubCommand),
+    #[command(name = "list")]
+    /// List the daily exports of this server
+    List(ListSubCommand),
+}
+
+impl LeaderboardExportCommand {
+    fn can_manage(interaction: &InteractionCreate) -> bool {
+        interaction
+            .member
+            .as_ref()
+            .and_then(|member| member.permissions)
+            .map(|permissions| permissions.contains(Permissions::MANAGE_GUILD))
+            .unwrap_or(false)
+    }
+
+    /// What an export holds, for the messages.
+    fn describe(format: &str, rank: Option<&str>, country: Option<&str>) -> String {
+        format!(
+            "{} of the {} leaderboard{}",
+            format.to_uppercase(),
+            country.unwrap_or("global"),
+            rank.map(|rank| format!(", {} only", rank.to_uppercase())).unwrap_or_default()
+        )
+    }
+
+    async fn file(
+        file: FileSubCommand,
+        interaction: &InteractionCreate,
+        context: &Context<'_>,
+    ) -> anyhow::Result<anyhow::Result<()>> {
+        let rank = file.rank.map(UserRank::from);
+        let country = file.country_code.map(|country| country.to_uppercase());
+
+        let (attachment, players) = match export_file(file.format, rank.as_ref(), country.as_deref(), context).await? {
+            Ok(export) => export,
+            Err(err) => return Ok(Err(err)),
+        };
+        if players == 0 {
+            return Ok(Err(anyhow!("❌ No player matches these filters")));
+        }
+
+        let content = format!(
+            "{}, {players} players",
+            Self::describe(
+                file.format.value(),
+                rank.map(|rank| rank.to_string()).as_deref(),
+                country.as_deref()
+            )
+        );
+        context
+            .http_client
+            .interaction(context.application.id)
+            .update_response(&interaction.token)
+            .content(Some(&content))?
+            .attachments(&[attachment])?
+            .await?;
+
+        Ok(Ok(()))
+    }
+}
+
+#[async_trait::async_trait]
+impl RunnableCommand for LeaderboardExportCommand {
+    async fn run(
+        _shard: u64,
+        interaction: &InteractionCreate,
+        data: Box<CommandData>,
+        context: &Context<'_>,
+    ) -> anyhow::Result<anyhow::Result<()>> {
+        log::info!("lbexport command");
+        let _command_timer = Timer::new("lbexport command");
+        context.defer_response(interaction).await?;
+        let model = Self::from_interaction(CommandInputData {
+            options: data.options,
+            resolved: data.resolved.map(Cow::Owned),
+        })?;
+
+        let model = match model {
+            LeaderboardExportCommand::File(file) => return Self::file(file, interaction, context).await,
+            model => model,
+        };
+
+        let Some(guild_id) = interaction.guild_id else {
+            return Ok(Err(anyhow!("❌ The daily exports can only be used in a server")));
+        };
+        if !matches!(model, LeaderboardExportCommand::List(_)) && !Self::can_manage(interaction) {
+            return Ok(Err(anyhow!("❌ You need the Manage Server permission to manage the daily exports")));
+        }
+
+        let content = match model {
+            LeaderboardExportCommand::Schedule(schedule) => {
+                let Some(channel_id) = schedule.channel.or(interaction.channel.as_ref().map(|channel| channel.id)) else {
+                    return Ok(Err(anyhow!("❌ Couldn't find the channel to post the exports in")));
+                };
+                let export = LeaderboardExportData {
+                    guild_id: guild_id.to_string(),
+                    channel_id: channel_id.to_string(),
+                    format: schedule.format.value().to_string(),
+                    rank: schedule.rank.map(|rank| UserRank::from(rank).to_string()),
+                    country: schedule.country_code.map(|country| country.to_uppercase()),
+                };
+                LeaderboardExportPDO::upsert_export(context, &export).await?;
+
+                format!(
+                    "✅ The {} will be posted in <#{channel_id}> every day",
+                    Self::describe(&export.format, export.rank.as_deref(), export.country.as_deref())
+                )
+            }
+            LeaderboardExportCommand::Unschedule(unschedule) => {
+                let Some(channel_id) = unschedule.channel.or(interaction.channel.as_ref().map(|channel| channel.id))
+                else {
+                    return Ok(Err(anyhow!("❌ Couldn't find the channel of the export")));
+                };
+                if !LeaderboardExportPDO::delete_export(context, guild_id.get(), channel_id.get()).await? {
+                    return Ok(Err(anyhow!("❌ No leaderboard export is posted in <#{channel_id}>")));
+                }
+
+                format!("✅ The leaderboard export won't be posted in <#{channel_id}> anymore")
+            }
+            LeaderboardExportCommand::List(_) => {
+                let exports = LeaderboardExportPDO::fetch_guild_exports(context, guild_id.get()).await?;
+                let description = if exports.is_empty() {
+                    "No daily export yet, use `/lbexport schedule`.".to_string()
+                } else {
+                    exports
+                        .iter()
+                        .map(|export| {
+                            format!(
+                                "<#{}>: {}",
+                                export.channel_id,
+                                Self::describe(&export.format, export.rank.as_deref(), export.country.as_deref())
+                            )
+                        })
+                        .join("\n")
+                };
+
+                let embed = create_embed(None, context).await?
+                    .title("DAILY LEADERBOARD EXPORTS")
+                    .description(description)
+                    .build();
+
+                context
+                    .http_client
+                    .interaction(context.application.id)
+                    .update_response(&interaction.token)
+                    .embeds(Some(&[embed]))?
+                    .await?;
+
+                return Ok(Ok(()));
+            }
+            LeaderboardExportCommand::File(_) => unreachable!("the file subcommand is handled above"),
+        };
+
+        context
+            .http_client
+            .interaction(context.application.id)
+            .update_response(&interaction.token)
+            .content(Some(&content))?
+            .await?;
+
+        Ok(Ok(()))
+    }
+}
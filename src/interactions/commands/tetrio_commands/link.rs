@@ -0,0 +1,127 @@
+use std::borrow::Cow;
+
+use anyhow::anyhow;
+use twilight_interactions::command::{CommandInputData, CommandModel, CreateCommand};
+use twilight_model::{
+    application::interaction::application_command::CommandData,
+    gateway::payload::incoming::InteractionCreate,
+};
+
+use crate::{
+    context::Context,
+    interactions::commands::subcommands::link::{
+        remove_sub_command::RemoveSubCommand, show_sub_command::ShowSubCommand,
+        start_sub_command::StartSubCommand, verify_sub_command::VerifySubCommand,
+    },
+    services::tetrio_link::TetrioLinkPDO,
+    utils::{account_link::generate_code, box_commands::RunnableCommand, timer::Timer},
+};
+
+#[derive(CreateCommand, CommandModel)]
+#[command(name = "link", desc = "Link your tetrio account to your discord account")]
+pub enum LinkCommand {
+    #[command(name = "start")]
+    /// Get a code to put in the bio of your tetrio account
+    Start(StartSubCommand),
+    #[command(name = "verify")]
+    /// Check the code in your tetrio bio and link the account
+    Verify(VerifySubCommand),
+    #[command(name = "remove")]
+    /// Unlink your tetrio account
+    Remove(RemoveSubCommand),
+    #[command(name = "show")]
+    /// Show the tetrio account linked to your discord account
+    Show(ShowSubCommand),
+}
+
+#[async_trait::async_trait]
+impl RunnableCommand for LinkCommand {
+    async fn run(
+        _shard: u64,
+        interaction: &InteractionCreate,
+        data: Box<CommandData>,
+        context: &Context<'_>,
+    ) -> anyhow::Result<anyhow::Result<()>> {
+        log::info!("link command");
+        let _command_timer = Timer::new("link command");
+        context.defer_response(interaction).await?;
+        let model = Self::from_interaction(CommandInputData {
+            options: data.options,
+            resolved: data.resolved.map(Cow::Owned),
+        })?;
+
+        let Some(discord_id) = interaction.author_id().map(|id| id.get()) else {
+            return Ok(Err(anyhow!("❌ Couldn't find the author of the command")));
+        };
+
+        let content = match model {
+            LinkCommand::Start(start) => {
+                let user = context
+                    .tetrio_client
+                    .fetch_user_info(&start.tetrio_user.to_lowercase())
+                    .await?;
+                let Some(data) = &user.data else {
+                    return Ok(Err(anyhow!("❌ Couldn't find tetrio user {}", start.tetrio_user)));
+                };
+
+                let code = generate_code();
+                TetrioLinkPDO::upsert_request(context, discord_id, &data.id.to_string(), &data.username, &code).await?;
+
+                format!(
+                    "Put `{code}` anywhere in the bio of **{}** on tetr.io, then use `/link verify` within the hour. You can remove it from the bio once linked.",
+                    data.username
+                )
+            }
+            LinkCommand::Verify(_) => {
+                let Some(request) = TetrioLinkPDO::fetch_request(context, discord_id).await? else {
+                    return Ok(Err(anyhow!("❌ No pending link, use `/link start` first")));
+                };
+
+                if !context.link_verifier.verify(&request.tetrio_id, &request.code).await? {
+                    return Ok(Err(anyhow!(
+                        "❌ Couldn't find `{}` in the bio of {}, it can take a minute to update on tetr.io",
+                        request.code,
+                        request.username
+                    )));
+                }
+
+                let linked = TetrioLinkPDO::upsert_link(context, discord_id, &request.tetrio_id, &request.username).await?;
+                TetrioLinkPDO::delete_request(context, discord_id).await?;
+                if !linked {
+                    return Ok(Err(anyhow!(
+                        "❌ {} is already linked to another discord account, it has to be unlinked first",
+                        request.username
+                    )));
+                }
+
+                format!(
+                    "✅ Linked to **{}**, commands now use this account when no user is given",
+                    request.username
+                )
+            }
+            LinkCommand::Remove(_) => {
+                if !TetrioLinkPDO::delete_link(context, discord_id).await? {
+                    return Ok(Err(anyhow!("❌ You don't have a linked tetrio account")));
+                }
+
+                "✅ Your tetrio account has been unlinked".to_string()
+            }
+            LinkCommand::Show(_) => match TetrioLinkPDO::fetch_link(context, discord_id).await? {
+                Some(link) => format!(
+                    "Your discord account is linked to **{}**: <https://ch.tetr.io/u/{}>",
+                    link.username, link.username
+                ),
+                None => "Your discord account isn't linked, use `/link start` to link it".to_string(),
+            },
+        };
+
+        context
+            .http_client
+            .interaction(context.application.id)
+            .update_response(&interaction.token)
+            .content(Some(&content))?
+            .await?;
+
+        Ok(Ok(()))
+    }
+}
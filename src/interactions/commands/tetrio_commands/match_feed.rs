@@ -0,0 +1,142 @@
+use std::borrow::Cow;
+
+use anyhow::anyhow;
+use itertools::Itertools;
+use twilight_interactions::command::{CommandInputData, CommandModel, CreateCommand};
+use twilight_model::{
+    application::interaction::application_command::CommandData,
+    gateway::payload::incoming::InteractionCreate,
+    guild::Permissions,
+};
+
+use crate::{
+    context::Context,
+    interactions::commands::subcommands::match_feed::{
+        list_sub_command::ListSubCommand, subscribe_sub_command::SubscribeSubCommand,
+        unsubscribe_sub_command::UnsubscribeSubCommand,
+    },
+    services::match_feed::MatchFeedPDO,
+    utils::{box_commands::RunnableCommand, create_embed::create_embed, match_feed::mark_recent_as_posted, timer::Timer},
+};
+
+#[derive(CreateCommand, CommandModel)]
+#[command(name = "matchfeed", desc = "Post the new tetra league matches of players in a channel")]
+pub enum MatchFeedCommand {
+    #[command(name = "subscribe")]
+    /// Post the new tetra league matches of a player
+    Subscribe(SubscribeSubCommand),
+    #[command(name = "unsubscribe")]
+    /// Stop posting the matches of a player in this server
+    Unsubscribe(UnsubscribeSubCommand),
+    #[command(name = "list")]
+    /// List the players whose matches are posted in this server
+    List(ListSubCommand),
+}
+
+impl MatchFeedCommand {
+    fn can_manage(interaction: &InteractionCreate) -> bool {
+        interaction
+            .member
+            .as_ref()
+            .and_then(|member| member.permissions)
+            .map(|permissions| permissions.contains(Permissions::MANAGE_GUILD))
+            .unwrap_or(false)
+    }
+}
+
+#[async_trait::async_trait]
+impl RunnableCommand for MatchFeedCommand {
+    async fn run(
+        _shard: u64,
+        interaction: &InteractionCreate,
+        data: Box<CommandData>,
+        context: &Context<'_>,
+    ) -> anyhow::Result<anyhow::Result<()>> {
+        log::info!("matchfeed command");
+        let _command_timer = Timer::new("matchfeed command");
+        context.defer_response(interaction).await?;
+        let model = Self::from_interaction(CommandInputData {
+            options: data.options,
+            resolved: data.resolved.map(Cow::Owned),
+        })?;
+
+        let Some(guild_id) = interaction.guild_id else {
+            return Ok(Err(anyhow!("❌ The match feed can only be used in a server")));
+        };
+        if !matches!(model, MatchFeedCommand::List(_)) && !Self::can_manage(interaction) {
+            return Ok(Err(anyhow!("❌ You need the Manage Server permission to manage the match feed")));
+        }
+
+        let content = match model {
+            MatchFeedCommand::Subscribe(subscribe) => {
+                let Some(channel_id) = subscribe.channel.or(interaction.channel.as_ref().map(|channel| channel.id)) else {
+                    return Ok(Err(anyhow!("❌ Couldn't find the channel to post the matches in")));
+                };
+                let user = context
+                    .tetrio_client
+                    .fetch_user_info(&subscribe.tetrio_user.to_lowercase())
+                    .await?;
+                let Some(data) = &user.data else {
+                    return Ok(Err(anyhow!("❌ Couldn't find tetrio user {}", subscribe.tetrio_user)));
+                };
+                let tetrio_id = data.id.to_string();
+
+                if let Err(err) = mark_recent_as_posted(channel_id.get(), &tetrio_id, context).await {
+                    log::warn!("couldn't fetch the recent matches of {}: {err:?}", data.username);
+                    return Ok(Err(anyhow!("❌ Couldn't fetch the recent matches of {}, try again later", data.username)));
+                }
+                MatchFeedPDO::upsert_subscription(context, guild_id.get(), channel_id.get(), &tetrio_id, &data.username).await?;
+
+                format!("✅ The new tetra league matches of **{}** will be posted in <#{channel_id}>", data.username)
+            }
+            MatchFeedCommand::Unsubscribe(unsubscribe) => {
+                if !MatchFeedPDO::delete_subscription(context, guild_id.get(), &unsubscribe.tetrio_user).await? {
+                    return Ok(Err(anyhow!("❌ The matches of {} are not posted in this server", unsubscribe.tetrio_user)));
+                }
+
+                format!("✅ The matches of {} won't be posted anymore", unsubscribe.tetrio_user)
+            }
+            MatchFeedCommand::List(_) => {
+                let subscriptions = MatchFeedPDO::fetch_guild_subscriptions(context, guild_id.get()).await?;
+                let description = if subscriptions.is_empty() {
+                    "No player is followed yet, use `/matchfeed subscribe`.".to_string()
+                } else {
+                    subscriptions
+                        .iter()
+                        .chunk_by(|subscription| subscription.channel_id.as_str())
+                        .into_iter()
+                        .map(|(channel_id, subscriptions)| {
+                            format!(
+                                "<#{channel_id}>: {}",
+                                subscriptions.map(|subscription| subscription.username.as_str()).join(", ")
+                            )
+                        })
+                        .join("\n")
+                };
+
+                let embed = create_embed(None, context).await?
+                    .title("MATCH FEED")
+                    .description(description)
+                    .build();
+
+                context
+                    .http_client
+                    .interaction(context.application.id)
+                    .update_response(&interaction.token)
+                    .embeds(Some(&[embed]))?
+                    .await?;
+
+                return Ok(Ok(()));
+            }
+        };
+
+        context
+            .http_client
+            .interaction(context.application.id)
+            .update_response(&interaction.token)
+            .content(Some(&content))?
+            .await?;
+
+        Ok(Ok(()))
+    }
+}
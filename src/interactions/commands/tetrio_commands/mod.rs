@@ -1,7 +1,29 @@
+pub mod alb;
+#[cfg(feature = "database")]
+pub mod announcements;
+#[cfg(feature = "database")]
+pub mod cutoffs;
+#[cfg(feature = "database")]
+pub mod formula;
+#[cfg(feature = "database")]
+pub mod glb;
 pub mod lb;
+#[cfg(feature = "database")]
+pub mod leaderboard_export;
+#[cfg(feature = "database")]
+pub mod link;
+#[cfg(feature = "database")]
+pub mod match_feed;
 pub mod psq;
+#[cfg(feature = "database")]
+pub mod rank_roles;
+pub mod replay;
+#[cfg(feature = "database")]
+pub mod replays;
 pub mod rlb;
+pub mod similar;
 pub mod sq;
+pub mod target;
 #[cfg(feature = "html_server_image_generation")]
 pub mod teto;
 #[cfg(feature = "html_server_image_generation")]
@@ -13,17 +35,39 @@ pub mod vst;
 
 pub fn get_descriptions() -> Box<[(Box<str>, Box<str>)]> {
     [
+        ("alb".into(), "get aggregate leaderboards of the countries by players, X rank players or any stat, and the stats of every rank side by side".into()),
+        #[cfg(feature = "database")]
+        ("announcements".into(), "announce the rank ups, TR and games played milestones and personal bests of tracked players and linked members".into()),
+        #[cfg(feature = "database")]
+        ("cutoffs".into(), "get the TR cutoff of every rank with its change over 24 hours and 7 days, a chart of its history and a projection to the end of the season".into()),
+        #[cfg(feature = "database")]
+        ("formula".into(), "save custom stat formulas for this server, usable in lb, rlb and ts".into()),
+        #[cfg(feature = "database")]
+        ("glb".into(), "get a leaderboard of stats of the members of this server with a linked or discoverable tetrio account".into()),
         ("lb".into(), "get a leaderboard of stats".into()),
+        #[cfg(feature = "database")]
+        ("lbexport".into(), "export the full leaderboard with every stat as a csv or json file, or post it in a channel every day".into()),
+        #[cfg(feature = "database")]
+        ("link".into(), "link your tetrio account, used by every command when no user is given".into()),
+        #[cfg(feature = "database")]
+        ("matchfeed".into(), "post the new tetra league matches of players in a channel, with the score, the TR change and the stats of every round".into()),
         ("rlb".into(), "get a leaderboard of stats in the reverse order".into()),
-        ("vst".into(), "compare the stats of two users".into()),
+        ("vst".into(), "compare the stats of two users, or up to 6 with the list subcommand".into()),
         ("vs".into(), "get a graph from tetrio stats, from a user, from the average of a rank or from the stats of a recent tetra league game".into()),
         ("vsr".into(), "get a graph from tetrio stats, from a user, from the average of a rank or from the stats of a recent tetra league game relative to the highest stat".into()),
         ("psq".into(), "get a graph representing the playstyle from tetrio stats, from a user, from the average of a rank or from the stats of a recent tetra league game".into()),
+        #[cfg(feature = "database")]
+        ("rankroles".into(), "give discord roles from the tetra league rank, TR or country of linked members, kept up to date automatically".into()),
+        ("replay".into(), "analyze a ttrm replay round by round, compare both players of a match, rebuild the board of a player at a key moment, or analyze a 40 lines or blitz ttr replay".into()),
+        #[cfg(feature = "database")]
+        ("replays".into(), "search the uploaded replays by player, opponent or date, to run them again by id".into()),
+        ("similar".into(), "find the players of the leaderboard with the most similar playstyle".into()),
         ("sq".into(), "get a graph representing the main characteristics from tetrio stats, from a user, from the average of a rank or from the stats of a recent tetra league game".into()),
         #[cfg(feature = "html_server_image_generation")]
         ("teto".into(), "get the tetrio profile of a user".into()),
         #[cfg(feature = "html_server_image_generation")]
         ("tetra".into(), "get a recent tetra league game of a user".into()),
+        ("target".into(), "find the apm, pps or vs needed to reach a TR or a rank".into()),
         ("ts".into(), "get the stats from tetrio stats, from a user, from the average of a rank or from the stats of a recent tetra league game formatted in a nice way.".into())
     ].into()
 }
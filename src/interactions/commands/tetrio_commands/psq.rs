@@ -1,9 +1,7 @@
 use std::borrow::Cow;
 
 
-use anyhow::anyhow;
 use async_trait::async_trait;
-use serde_json::json;
 use twilight_interactions::command::{CommandInputData, CommandModel, CreateCommand};
 
 use twilight_model::application::interaction::application_command::CommandData;
@@ -12,11 +10,14 @@ use twilight_model::gateway::payload::incoming::InteractionCreate;
 use crate::context::Context;
 
 use super::sq::SqCommand;
+use crate::utils::archetype::ArchetypeClassifier;
 use crate::utils::box_commands::{CommandBox, RunnableCommand};
+use crate::utils::charts::{Chart, ChartBackend, ChartDataset, ChartKind, RenderedChart};
 use crate::utils::stats::{calculate_stats, PlayerStats};
 
 use crate::interactions::commands::models::graph_user_model::{
-    AverageSubCommand, DiscordUserSubCommand, GraphUser, StatsSubCommand, TetrioUserSubCommand,
+    AverageSubCommand, DiscordUserSubCommand, GraphUser, SourceSubCommand, StatsSubCommand,
+    TetrioUserSubCommand,
 };
 
 use crate::utils::timer::Timer;
@@ -38,6 +39,9 @@ pub enum PsqCommand {
     #[command(name = "average")]
     /// Use average stats
     Average(AverageSubCommand),
+    #[command(name = "source")]
+    /// Use any player source
+    Source(SourceSubCommand),
 }
 
 impl PsqCommand {
@@ -45,57 +49,28 @@ impl PsqCommand {
         username: &str,
         dark_mode: bool,
         stats: PlayerStats,
-    ) -> anyhow::Result<String> {
+        backend: ChartBackend,
+    ) -> anyhow::Result<RenderedChart> {
         let stats = calculate_stats(stats);
         let infds = stats.infds;
         let stride = stats.stride;
         let plonk = stats.plonk;
         let opener = stats.opener;
 
-        let json = json!({
-            "type": "radar",
-            "data": {
-                "labels": ["OPENER", "STRIDE", "INF DS", "PLONK"],
-                "datasets": [{
-                    "label": username,
-                    "data": [
-                        opener, stride, infds, plonk
-                    ],
-                    "backgroundColor": SqCommand::get_background_colors(dark_mode),
-                    "borderColor": SqCommand::get_background_colors(dark_mode),
-                    "borderWidth": 0,
-                    "pointRadius": 0
-                }]
-            },
-            "options":{"legend": { "labels": { "fontColor": SqCommand::get_font_color(dark_mode), "fontSize": 16}}, "scale":{"pointLabels":{"fontColor":SqCommand::get_font_color(dark_mode), "fontSize": 16},"rAxis":{"ticks":{"display":false}},"ticks":{"min":0,"max":1.2,"stepSize":"0.2","fontColor":"blue","display":false},"gridLines":{"color":"gray"},"angleLines":{"color":"gray"}}}
-        });
-
-        let json = json!({
-            "width": 500,
-            "height": 300,
-            "format": "webp",
-            "background": "transparent",
-            "version": 2,
-            "chart": json
-        });
-
-        log::debug!("{json}");
-
-        let response = reqwest::Client::builder()
-            .build()?
-            .post("https://quickchart.io/chart/create")
-            .header("Content-Type", "application/json")
-            .body(json.to_string())
-            .send()
-            .await?
-            .json::<serde_json::Value>()
-            .await?;
-        Ok(response
-            .get("url")
-            .ok_or(anyhow!("Expected a string for the url of the chart"))?
-            .as_str()
-            .ok_or(anyhow!("Expected a string"))?
-            .to_string())
+        Chart {
+            kind: ChartKind::Radar,
+            labels: ["OPENER", "STRIDE", "INF DS", "PLONK"].map(String::from).to_vec(),
+            datasets: vec![ChartDataset {
+                label: username.to_string(),
+                data: vec![opener, stride, infds, plonk],
+                color: SqCommand::get_background_colors(dark_mode),
+            }],
+            min: 0.0,
+            max: 1.2,
+            dark_mode,
+        }
+        .render(backend)
+        .await
     }
 }
 
@@ -117,7 +92,7 @@ impl RunnableCommand for PsqCommand {
             resolved: data.resolved.map(Cow::Owned),
         })?;
 
-        let data = model.get_data(&context).await?;
+        let data = model.get_data(interaction, &context).await?;
 
         let data = match data {
             Ok(data) => data,
@@ -136,15 +111,19 @@ impl RunnableCommand for PsqCommand {
             String::new()
         };
 
-        let url = Self::graph_with_stats(&data.name, data.dark_mode, data.stats).await?;
+        let archetype_str = match ArchetypeClassifier::fetch(context).await.classify_player(data.stats.clone()) {
+            Some(archetype) => format!(
+                "Archetype: **{archetype}**\n➕ {}\n➖ {}",
+                archetype.strengths(),
+                archetype.weaknesses()
+            ),
+            None => String::new(),
+        };
+
+        let chart = Self::graph_with_stats(&data.name, data.dark_mode, data.stats, context.chart_backend).await?;
 
-        let content = format!("{replay_str}\n{url}");
-        context
-            .http_client
-            .interaction(context.application.id)
-            .update_response(&interaction.token)
-            .content(Some(&content))?
-            .await?;
+        let content = format!("{replay_str}\n{archetype_str}");
+        chart.send(content.trim(), interaction, context).await?;
 
         Ok(Ok(()))
     }
@@ -0,0 +1,226 @@
+use std::borrow::Cow;
+
+use anyhow::anyhow;
+use itertools::Itertools;
+use tetrio_api::models::users::user_rank::UserRank;
+use twilight_interactions::command::{CommandInputData, CommandModel, CreateCommand};
+use twilight_model::{
+    application::interaction::application_command::CommandData,
+    gateway::payload::incoming::InteractionCreate,
+    guild::Permissions,
+};
+
+use crate::{
+    context::Context,
+    interactions::commands::subcommands::rank_roles::{
+        audit_sub_command::AuditSubCommand, list_sub_command::ListSubCommand,
+        preview_sub_command::PreviewSubCommand, remove_sub_command::RemoveSubCommand,
+        set_sub_command::SetSubCommand, sync_sub_command::SyncSubCommand,
+    },
+    models::rank_role::RankRoleData,
+    services::rank_role::RankRolePDO,
+    utils::{
+        box_commands::RunnableCommand,
+        create_embed::create_embed,
+        rank_roles::{sync_guild, RoleChange, StandingCache, SyncReport},
+        timer::Timer,
+    },
+};
+
+/// Changes listed in a preview or a sync report, the rest is counted
+const MAX_LISTED_CHANGES: usize = 20;
+
+#[derive(CreateCommand, CommandModel)]
+#[command(name = "rankroles", desc = "Give discord roles from the tetra league rank of linked members")]
+pub enum RankRolesCommand {
+    #[command(name = "set")]
+    /// Give a role to the linked members matching a rank, a TR band or a country
+    Set(SetSubCommand),
+    #[command(name = "remove")]
+    /// Stop managing a role
+    Remove(RemoveSubCommand),
+    #[command(name = "list")]
+    /// List the rank roles of this server
+    List(ListSubCommand),
+    #[command(name = "preview")]
+    /// Show the roles a sync would add and remove
+    Preview(PreviewSubCommand),
+    #[command(name = "sync")]
+    /// Update the rank roles of the linked members now
+    Sync(SyncSubCommand),
+    #[command(name = "audit")]
+    /// Show the last rank roles given and removed
+    Audit(AuditSubCommand),
+}
+
+impl RankRolesCommand {
+    fn can_manage(interaction: &InteractionCreate) -> bool {
+        interaction
+            .member
+            .as_ref()
+            .and_then(|member| member.permissions)
+            .map(|permissions| permissions.contains(Permissions::MANAGE_ROLES))
+            .unwrap_or(false)
+    }
+
+    fn format_change(change: &RoleChange) -> String {
+        format!(
+            "{} <@&{}> {} <@{}>: {}",
+            if change.added { "➕" } else { "➖" },
+            change.role_id,
+            if change.added { "to" } else { "from" },
+            change.discord_id,
+            change.reason
+        )
+    }
+
+    fn format_report(report: &SyncReport) -> String {
+        let mut lines = report
+            .changes
+            .iter()
+            .take(MAX_LISTED_CHANGES)
+            .map(Self::format_change)
+            .collect_vec();
+        if report.changes.len() > MAX_LISTED_CHANGES {
+            lines.push(format!("and {} more", report.changes.len() - MAX_LISTED_CHANGES));
+        }
+        if lines.is_empty() {
+            lines.push("Every linked member already has the right roles.".to_string());
+        }
+
+        let added = report.changes.iter().filter(|change| change.added).count();
+        lines.push(format!(
+            "\n{} linked members, {added} roles added, {} removed",
+            report.members,
+            report.changes.len() - added
+        ));
+        if report.errors > 0 {
+            lines.push(format!("⚠️ {} members or roles couldn't be updated, check the role order and the bot permissions", report.errors));
+        }
+
+        lines.join("\n")
+    }
+}
+
+#[async_trait::async_trait]
+impl RunnableCommand for RankRolesCommand {
+    async fn run(
+        _shard: u64,
+        interaction: &InteractionCreate,
+        data: Box<CommandData>,
+        context: &Context<'_>,
+    ) -> anyhow::Result<anyhow::Result<()>> {
+        log::info!("rankroles command");
+        let _command_timer = Timer::new("rankroles command");
+        context.defer_response(interaction).await?;
+        let model = Self::from_interaction(CommandInputData {
+            options: data.options,
+            resolved: data.resolved.map(Cow::Owned),
+        })?;
+
+        let Some(guild_id) = interaction.guild_id else {
+            return Ok(Err(anyhow!("❌ Rank roles can only be used in a server")));
+        };
+        let needs_permission = !matches!(model, RankRolesCommand::List(_) | RankRolesCommand::Audit(_));
+        if needs_permission && !Self::can_manage(interaction) {
+            return Ok(Err(anyhow!("❌ You need the Manage Roles permission to manage rank roles")));
+        }
+
+        let (title, description) = match model {
+            RankRolesCommand::Set(set) => {
+                let country = set.country_code.map(|country| country.trim().to_uppercase());
+                if set.rank.is_none() && set.min_tr.is_none() && set.max_tr.is_none() && country.is_none() {
+                    return Ok(Err(anyhow!("❌ Give a rank, a TR band or a country for the role")));
+                }
+                if let (Some(min), Some(max)) = (set.min_tr, set.max_tr) {
+                    if min >= max {
+                        return Ok(Err(anyhow!("❌ The minimum TR has to be lower than the maximum TR")));
+                    }
+                }
+
+                let rule = RankRoleData {
+                    guild_id: guild_id.to_string(),
+                    role_id: set.role.to_string(),
+                    rank: set.rank.map(|rank| UserRank::from(rank).to_string()),
+                    min_tr: set.min_tr,
+                    max_tr: set.max_tr,
+                    country,
+                };
+                RankRolePDO::upsert_rank_role(context, &rule).await?;
+
+                (
+                    "RANK ROLES".to_string(),
+                    format!("✅ <@&{}> is now given for {}, it will be applied on the next sync", set.role, rule.describe()),
+                )
+            }
+            RankRolesCommand::Remove(remove) => {
+                if !RankRolePDO::delete_rank_role(context, guild_id.get(), remove.role.get()).await? {
+                    return Ok(Err(anyhow!("❌ <@&{}> is not a rank role", remove.role)));
+                }
+
+                (
+                    "RANK ROLES".to_string(),
+                    format!("✅ <@&{}> is not managed anymore, members keep it until it is removed by hand", remove.role),
+                )
+            }
+            RankRolesCommand::List(_) => {
+                let rules = RankRolePDO::fetch_rank_roles(context, guild_id.get()).await?;
+                let description = if rules.is_empty() {
+                    "No rank roles have been set yet, use `/rankroles set`.".to_string()
+                } else {
+                    rules
+                        .iter()
+                        .map(|rule| format!("<@&{}>: {}", rule.role_id, rule.describe()))
+                        .join("\n")
+                };
+
+                ("RANK ROLES".to_string(), description)
+            }
+            RankRolesCommand::Preview(_) => {
+                let report = sync_guild(guild_id.get(), false, &mut StandingCache::default(), context).await?;
+                ("RANK ROLES PREVIEW".to_string(), Self::format_report(&report))
+            }
+            RankRolesCommand::Sync(_) => {
+                let report = sync_guild(guild_id.get(), true, &mut StandingCache::default(), context).await?;
+                ("RANK ROLES SYNC".to_string(), Self::format_report(&report))
+            }
+            RankRolesCommand::Audit(audit) => {
+                let entries = RankRolePDO::fetch_audit(context, guild_id.get(), audit.limit.unwrap_or(15)).await?;
+                let description = if entries.is_empty() {
+                    "No rank role has been changed yet.".to_string()
+                } else {
+                    entries
+                        .iter()
+                        .map(|entry| {
+                            format!(
+                                "`{}` {} <@&{}> {} <@{}>: {}",
+                                entry.created_at,
+                                if entry.added { "➕" } else { "➖" },
+                                entry.role_id,
+                                if entry.added { "to" } else { "from" },
+                                entry.discord_id,
+                                entry.reason
+                            )
+                        })
+                        .join("\n")
+                };
+
+                ("RANK ROLES AUDIT".to_string(), description)
+            }
+        };
+
+        let embed = create_embed(None, context).await?
+            .title(title)
+            .description(description)
+            .build();
+
+        context
+            .http_client
+            .interaction(context.application.id)
+            .update_response(&interaction.token)
+            .embeds(Some(&[embed]))?
+            .await?;
+
+        Ok(Ok(()))
+    }
+}
@@ -0,0 +1,676 @@
+use std::borrow::Cow;
+
+use anyhow::anyhow;
+use twilight_interactions::command::{CommandInputData, CommandModel, CreateCommand};
+use twilight_model::{
+    application::interaction::application_command::CommandData,
+    channel::message::{embed::EmbedField, Embed},
+    gateway::payload::incoming::InteractionCreate,
+};
+
+use crate::{
+    context::Context,
+    interactions::commands::{
+        options::board_moment_option::BoardMomentOption,
+        subcommands::replay::{
+            analyze_sub_command::AnalyzeSubCommand, board_sub_command::BoardSubCommand,
+            solo_sub_command::SoloSubCommand, versus_sub_command::VersusSubCommand,
+        },
+    },
+    utils::{
+        box_commands::RunnableCommand,
+        charts::{Chart, ChartDataset, ChartKind, RenderedChart},
+        create_embed::create_embed,
+        replay_analysis::{PlayerAnalysis, ReplayAnalysis, RoundStats},
+        replay_archive::{fetch_replay, ReplayInput, ReplayMetadata, UploadedReplay},
+        replay_board::{format_time, Reconstruction},
+        solo_replay::{format_duration, SoloReplay, SoloStats},
+        stats::{calculate_stats, calculate_win_chance, stringify_stats, Stats},
+        table::format_table,
+        timer::Timer,
+    },
+};
+
+use super::vs::{VsCommand, MAX_COMPARED_USERS};
+
+#[derive(CreateCommand, CommandModel)]
+#[command(name = "replay", desc = "Analyze ttrm replays")]
+pub enum ReplayCommand {
+    #[command(name = "analyze")]
+    /// Break down a ttrm replay round by round
+    Analyze(AnalyzeSubCommand),
+    #[command(name = "board")]
+    /// Rebuild the board of a player at a key moment of a ttrm replay
+    Board(BoardSubCommand),
+    #[command(name = "versus")]
+    /// Compare both players of a ttrm replay round by round
+    Versus(VersusSubCommand),
+    #[command(name = "solo")]
+    /// Analyze a 40 lines or blitz ttr replay
+    Solo(SoloSubCommand),
+}
+
+/// Rating deviation used for the estimated glicko of a round, same as unranked players in `/vst`
+const ROUND_RD: f64 = 60.9;
+/// Longest rounds table of `/replay versus`, the embeds of a message share 6000 characters
+const MAX_ROUNDS_TABLE_LENGTH: usize = 2200;
+/// Longest timeline table of `/replay versus`
+const MAX_TIMELINE_TABLE_LENGTH: usize = 1300;
+/// Length shared by the round tables of `/replay analyze`, the rest of each player embed takes about 300 characters
+const MAX_PLAYER_TABLES_LENGTH: usize = 3600;
+
+impl ReplayCommand {
+    fn round_row(label: String, round: &RoundStats) -> Vec<String> {
+        vec![
+            label,
+            format!("{:.0}", round.pieces),
+            format!("{:.0}", round.lines),
+            format!("{:.0}", round.attack),
+            format!("{:.0}", round.garbage_received),
+            format!("{:.1}s", round.time),
+            format!("{:.2}", round.apm),
+            format!("{:.2}", round.pps),
+            format!("{:.2}", round.vs),
+        ]
+    }
+
+    /// Round by round table of a player in at most `max_table_length` characters, followed by the totals and the
+    /// derived stats of the average.
+    async fn player_embed(player: &PlayerAnalysis, max_table_length: usize, context: &Context<'_>) -> anyhow::Result<Embed> {
+        let header = ["Round", "Pieces", "Lines", "Atk", "Recv", "Time", "APM", "PPS", "VS"]
+            .map(String::from)
+            .to_vec();
+        let rows = std::iter::once(header)
+            .chain(player.rounds.iter().map(|round| Self::round_row(round.round.to_string(), round)))
+            .collect::<Vec<_>>();
+        let total = [Self::round_row("Total".to_string(), &player.total())];
+        let (table, hidden) = Self::fit_table(&rows, &total, 1, max_table_length);
+        let hidden = match hidden {
+            0 => String::new(),
+            hidden => format!(" {hidden} more rounds are left out, the total counts them."),
+        };
+
+        let stats = stringify_stats(player.derived(None)?);
+        let field = |name: &str, value: String| EmbedField {
+            inline: true,
+            name: name.to_string(),
+            value,
+        };
+
+        Ok(create_embed(None, context).await?
+            .title(player.username.to_uppercase())
+            .description(format!("```\n{table}\n```\nTotal rates are the average of every round.{hidden}"))
+            .field(field("APP", stats.app))
+            .field(field("DS/Piece", stats.dspiece))
+            .field(field("APP+DS/Piece", stats.dsapppiece))
+            .field(field("VS/APM", stats.vsapm))
+            .field(field("Cheese Index", stats.cheese))
+            .field(field("Garbage Effi.", stats.garbage_effi))
+            .field(field("Area", stats.area))
+            .field(field("Est. of TR", stats.esttr))
+            .field(field("Weighted APP", stats.weighted_app))
+            .build())
+    }
+
+    /// Bar chart of the VS of each player per round.
+    async fn vs_chart(
+        players: &[&PlayerAnalysis],
+        rounds: usize,
+        dark_mode: bool,
+        context: &Context<'_>,
+    ) -> anyhow::Result<RenderedChart> {
+        let colors = VsCommand::get_background_colors(dark_mode);
+        let datasets = players
+            .iter()
+            .enumerate()
+            .map(|(i, player)| ChartDataset {
+                label: format!("{} VS", player.username),
+                data: (1..=rounds)
+                    .map(|round| {
+                        player
+                            .rounds
+                            .iter()
+                            .find(|stats| stats.round == round)
+                            .map(|stats| stats.vs)
+                            .unwrap_or(0.0)
+                    })
+                    .collect(),
+                color: colors[i % MAX_COMPARED_USERS],
+            })
+            .collect::<Vec<_>>();
+        let max = datasets
+            .iter()
+            .flat_map(|dataset| dataset.data.iter().copied())
+            .fold(0.0, f64::max);
+
+        Chart {
+            kind: ChartKind::Bar,
+            labels: (1..=rounds).map(|round| format!("R{round}")).collect(),
+            datasets,
+            min: 0.0,
+            // rounded up to a multiple of 50 to keep readable steps
+            max: ((max / 50.0).ceil() * 50.0).max(50.0),
+            dark_mode,
+        }
+        .render(context.chart_backend)
+        .await
+    }
+
+    async fn analyze(
+        analyze: AnalyzeSubCommand,
+        interaction: &InteractionCreate,
+        context: &Context<'_>,
+    ) -> anyhow::Result<anyhow::Result<()>> {
+        let input = ReplayInput {
+            attachment: analyze.replay.as_ref(),
+            tetrio: analyze.tetrio_replay.as_deref(),
+            archive_id: analyze.replay_id,
+        };
+        let uploaded = match fetch_replay(input, interaction, context).await? {
+            Ok(uploaded) => uploaded,
+            Err(err) => return Ok(Err(err)),
+        };
+        if uploaded.filename.ends_with(".ttr") {
+            return Self::solo_report(uploaded, false, interaction, context).await;
+        }
+        // check that extension is ttrm
+        if !uploaded.filename.ends_with("ttrm") {
+            return Ok(Err(anyhow!("❌ File type not supported: {:?}", uploaded.filename)));
+        };
+
+        let analysis = match ReplayAnalysis::parse(&uploaded.bytes) {
+            Ok(analysis) => analysis,
+            Err(err) => return Ok(Err(err)),
+        };
+        uploaded
+            .archive(&ReplayMetadata::from_analysis(&uploaded.filename, &analysis), interaction, context)
+            .await;
+
+        let players = match &analyze.user {
+            Some(user) => match analysis.player(user) {
+                Ok(player) => vec![player],
+                Err(err) => return Ok(Err(err)),
+            },
+            None => analysis.players.iter().take(MAX_COMPARED_USERS).collect(),
+        };
+
+        if players.is_empty() {
+            return Ok(Err(anyhow!("❌ Couldn't find any player in the replay")));
+        }
+
+        // the embeds of a message share 6000 characters, the tables are shortened to fit every player
+        let max_table_length = MAX_PLAYER_TABLES_LENGTH / players.len();
+        let mut embeds = vec![];
+        for player in &players {
+            embeds.push(Self::player_embed(player, max_table_length, context).await?);
+        }
+
+        let chart = Self::vs_chart(&players, analysis.rounds, analyze.dark_mode.unwrap_or(false), context).await?;
+        chart
+            .send_with_embeds(
+                &format!("Replay with {} rounds, VS per round:", analysis.rounds),
+                &embeds,
+                interaction,
+                context,
+            )
+            .await?;
+
+        Ok(Ok(()))
+    }
+
+    async fn board(
+        board: BoardSubCommand,
+        interaction: &InteractionCreate,
+        context: &Context<'_>,
+    ) -> anyhow::Result<anyhow::Result<()>> {
+        let input = ReplayInput {
+            attachment: board.replay.as_ref(),
+            tetrio: board.tetrio_replay.as_deref(),
+            archive_id: board.replay_id,
+        };
+        let uploaded = match fetch_replay(input, interaction, context).await? {
+            Ok(uploaded) => uploaded,
+            Err(err) => return Ok(Err(err)),
+        };
+        // check that extension is ttrm
+        if !uploaded.filename.ends_with("ttrm") {
+            return Ok(Err(anyhow!("❌ File type not supported: {:?}", uploaded.filename)));
+        };
+
+        let round = board.round.unwrap_or(1).max(1) as usize;
+        let (uploaded, reconstruction, analysis) = {
+            let _timer = Timer::new("replay board reconstruction");
+            let user = board.user.clone();
+            let reconstruction = tokio::task::spawn_blocking(move || {
+                let replay: serde_json::Value = serde_json::from_slice(&uploaded.bytes)
+                    .map_err(|err| anyhow!("❌ Couldn't parse ttrm replay: {:?}", err))?;
+                let reconstruction = Reconstruction::parse(&replay, &user, round)?;
+                // the analysis is only needed to archive the replay, the board is shown without it
+                anyhow::Ok((uploaded, reconstruction, ReplayAnalysis::from_raw(replay).ok()))
+            });
+            match reconstruction.await? {
+                Ok(reconstruction) => reconstruction,
+                Err(err) => return Ok(Err(err)),
+            }
+        };
+        if let Some(analysis) = &analysis {
+            uploaded
+                .archive(&ReplayMetadata::from_analysis(&uploaded.filename, analysis), interaction, context)
+                .await;
+        }
+
+        let moment = board.moment.unwrap_or(BoardMomentOption::Final);
+        let description = match moment {
+            BoardMomentOption::Final => "final board".to_string(),
+            BoardMomentOption::TopOut => "top-out".to_string(),
+            BoardMomentOption::Animation => "last pieces of the round".to_string(),
+            BoardMomentOption::BiggestSpike => match reconstruction.biggest_spike() {
+                Some(spike) => format!("biggest spike, {} lines sent from {}", spike.attack, format_time(spike.start_frame)),
+                None => return Ok(Err(anyhow!("❌ {} didn't send any attack in this round", reconstruction.username))),
+            },
+        };
+        let warning = match reconstruction.matches_replay {
+            Some(false) => "\n⚠️ The rebuilt board doesn't match the end of the replay, it might be inaccurate.",
+            _ => "",
+        };
+
+        #[cfg(feature = "local_image_generation")]
+        {
+            use crate::utils::replay_board::{
+                render::{render_gif, render_png, ANIMATION_FILENAME, BOARD_FILENAME},
+                Moment,
+            };
+            use twilight_model::http::attachment::Attachment;
+
+            let (filename, image, frame) = match moment {
+                BoardMomentOption::Animation => (
+                    ANIMATION_FILENAME,
+                    render_gif(&reconstruction.game.placements)?,
+                    reconstruction.game.frame,
+                ),
+                moment => {
+                    let moment = match moment {
+                        BoardMomentOption::TopOut => Moment::TopOut,
+                        BoardMomentOption::BiggestSpike => Moment::BiggestSpike,
+                        _ => Moment::Final,
+                    };
+                    let (board, frame, incoming) = match reconstruction.moment(moment) {
+                        Ok(moment) => moment,
+                        Err(err) => return Ok(Err(err)),
+                    };
+                    (BOARD_FILENAME, render_png(&board, incoming)?, frame)
+                }
+            };
+
+            context
+                .http_client
+                .interaction(context.application.id)
+                .update_response(&interaction.token)
+                .content(Some(&format!(
+                    "{}, round {round}: {description} ({}){warning}",
+                    reconstruction.username,
+                    format_time(frame)
+                )))?
+                .attachments(&[Attachment::from_bytes(filename.to_string(), image, 1)])?
+                .await?;
+
+            Ok(Ok(()))
+        }
+        #[cfg(not(feature = "local_image_generation"))]
+        {
+            let _ = (interaction, context, description, warning);
+            Ok(Err(anyhow!("❌ Board rendering is not available on this bot")))
+        }
+    }
+
+    /// Chance of the left round to win against the right one, from the estimated glicko of both.
+    fn round_win_chance(left: &RoundStats, right: &RoundStats) -> f64 {
+        calculate_win_chance(
+            calculate_stats(left.player_stats()).estglicko,
+            calculate_stats(right.player_stats()).estglicko,
+            ROUND_RD,
+            ROUND_RD,
+        )
+    }
+
+    /// Formats the header and as many groups of `group_size` rows as fit in `max_length` characters,
+    /// then the `footer` rows that are always kept, with the number of groups left out.
+    fn fit_table(rows: &[Vec<String>], footer: &[Vec<String>], group_size: usize, max_length: usize) -> (String, usize) {
+        let groups = rows.len().saturating_sub(1) / group_size;
+        let table = |kept: usize| format_table(&[&rows[..1 + kept * group_size], footer].concat());
+        (0..=groups)
+            .rev()
+            .map(|kept| (table(kept), groups - kept))
+            .find(|(table, _)| table.chars().count() <= max_length)
+            .unwrap_or_else(|| (table(0), groups))
+    }
+
+    fn delta_row(label: &str, left: &Stats, right: &Stats, stat: impl Fn(&Stats) -> f64) -> Vec<String> {
+        let (left, right) = (stat(left), stat(right));
+        vec![
+            label.to_string(),
+            format!("{left:.4}"),
+            format!("{right:.4}"),
+            format!("{:+.4}", left - right),
+        ]
+    }
+
+    async fn versus(
+        versus: VersusSubCommand,
+        interaction: &InteractionCreate,
+        context: &Context<'_>,
+    ) -> anyhow::Result<anyhow::Result<()>> {
+        let input = ReplayInput {
+            attachment: versus.replay.as_ref(),
+            tetrio: versus.tetrio_replay.as_deref(),
+            archive_id: versus.replay_id,
+        };
+        let uploaded = match fetch_replay(input, interaction, context).await? {
+            Ok(uploaded) => uploaded,
+            Err(err) => return Ok(Err(err)),
+        };
+        // check that extension is ttrm
+        if !uploaded.filename.ends_with("ttrm") {
+            return Ok(Err(anyhow!("❌ File type not supported: {:?}", uploaded.filename)));
+        };
+
+        let analysis = match ReplayAnalysis::parse(&uploaded.bytes) {
+            Ok(analysis) => analysis,
+            Err(err) => return Ok(Err(err)),
+        };
+        uploaded
+            .archive(&ReplayMetadata::from_analysis(&uploaded.filename, &analysis), interaction, context)
+            .await;
+
+        let [left, right] = analysis.players.as_slice() else {
+            return Ok(Err(anyhow!(
+                "❌ A versus comparison needs a replay with 2 players, this one has {}",
+                analysis.players.len()
+            )));
+        };
+
+        let mut rounds = vec![["Round", "Player", "APM", "PPS", "VS", "Sent", "Recv", "Win%"]
+            .map(String::from)
+            .to_vec()];
+        let mut timeline = vec![["Round", "Winner", "Score", "Favored"].map(String::from).to_vec()];
+        let mut score = (0, 0);
+
+        for round in 1..=analysis.rounds {
+            let (Some(left_round), Some(right_round)) = (
+                left.rounds.iter().find(|stats| stats.round == round),
+                right.rounds.iter().find(|stats| stats.round == round),
+            ) else {
+                continue;
+            };
+
+            let chance = Self::round_win_chance(left_round, right_round);
+            for (player, stats, chance) in [(left, left_round, chance), (right, right_round, 1.0 - chance)] {
+                rounds.push(vec![
+                    round.to_string(),
+                    player.username.to_uppercase(),
+                    format!("{:.2}", stats.apm),
+                    format!("{:.2}", stats.pps),
+                    format!("{:.2}", stats.vs),
+                    format!("{:.0}", stats.attack),
+                    format!("{:.0}", stats.garbage_received),
+                    format!("{:.0}%", chance * 100.0),
+                ]);
+            }
+
+            let winner = analysis.winner(round);
+            match winner {
+                Some(winner) if winner.eq_ignore_ascii_case(&left.username) => score.0 += 1,
+                Some(winner) if winner.eq_ignore_ascii_case(&right.username) => score.1 += 1,
+                _ => {}
+            }
+            let favored = if chance >= 0.5 { left } else { right };
+            timeline.push(vec![
+                round.to_string(),
+                winner.map(str::to_uppercase).unwrap_or_else(|| "?".to_string()),
+                format!("{}-{}", score.0, score.1),
+                format!("{} ({:.0}%)", favored.username.to_uppercase(), chance.max(1.0 - chance) * 100.0),
+            ]);
+        }
+
+        let (left_stats, right_stats) = match (left.derived(None), right.derived(None)) {
+            (Ok(left_stats), Ok(right_stats)) => (left_stats, right_stats),
+            (Err(err), _) | (_, Err(err)) => return Ok(Err(err)),
+        };
+        let deltas = vec![
+            vec![
+                "Stat".to_string(),
+                left.username.to_uppercase(),
+                right.username.to_uppercase(),
+                "Delta".to_string(),
+            ],
+            Self::delta_row("APM", &left_stats, &right_stats, |stats| stats.apm),
+            Self::delta_row("PPS", &left_stats, &right_stats, |stats| stats.pps),
+            Self::delta_row("VS", &left_stats, &right_stats, |stats| stats.vs),
+            Self::delta_row("APP", &left_stats, &right_stats, |stats| stats.app),
+            Self::delta_row("DS/Piece", &left_stats, &right_stats, |stats| stats.dspiece),
+            Self::delta_row("APP+DS/Piece", &left_stats, &right_stats, |stats| stats.dsapppiece),
+            Self::delta_row("VS/APM", &left_stats, &right_stats, |stats| stats.vsapm),
+            Self::delta_row("Cheese Index", &left_stats, &right_stats, |stats| stats.cheese),
+            Self::delta_row("Garbage Effi.", &left_stats, &right_stats, |stats| stats.garbage_effi),
+            Self::delta_row("Area", &left_stats, &right_stats, |stats| stats.area),
+            Self::delta_row("Est. of TR", &left_stats, &right_stats, |stats| stats.esttr),
+        ];
+        let overall = calculate_win_chance(left_stats.estglicko, right_stats.estglicko, ROUND_RD, ROUND_RD);
+        let overall_favored = if overall >= 0.5 { left } else { right };
+
+        let more = |hidden: usize| match hidden {
+            0 => String::new(),
+            hidden => format!("\n{hidden} more rounds are left out, the chart shows every round."),
+        };
+        let (rounds, hidden_rounds) = Self::fit_table(&rounds, &[], 2, MAX_ROUNDS_TABLE_LENGTH);
+        let (timeline, hidden_timeline) = Self::fit_table(&timeline, &[], 1, MAX_TIMELINE_TABLE_LENGTH);
+
+        let embeds = vec![
+            create_embed(None, context).await?
+                .title(format!("{} vs {}", left.username.to_uppercase(), right.username.to_uppercase()))
+                .description(format!(
+                    "```\n{rounds}\n```\nWin% is the chance to win the round from the stats of both players.{}",
+                    more(hidden_rounds)
+                ))
+                .build(),
+            create_embed(None, context).await?
+                .title("Timeline")
+                .description(format!("```\n{timeline}\n```{}", more(hidden_timeline)))
+                .build(),
+            create_embed(None, context).await?
+                .title("Derived stats")
+                .description(format!(
+                    "```\n{}\n```\nFrom the average of every round, {} was favored with {:.2}% win chance.",
+                    format_table(&deltas),
+                    overall_favored.username.to_uppercase(),
+                    overall.max(1.0 - overall) * 100.0
+                ))
+                .build(),
+        ];
+
+        let chart = Self::vs_chart(&[left, right], analysis.rounds, versus.dark_mode.unwrap_or(false), context).await?;
+
+        chart
+            .send_with_embeds(
+                &format!(
+                    "**{}** {} - {} **{}**",
+                    left.username.to_uppercase(),
+                    score.0,
+                    score.1,
+                    right.username.to_uppercase()
+                ),
+                &embeds,
+                interaction,
+                context,
+            )
+            .await?;
+
+        Ok(Ok(()))
+    }
+
+    fn solo_row(label: &str, replay: &SoloStats, best: &SoloStats, stat: impl Fn(&SoloStats) -> f64, precision: usize) -> Vec<String> {
+        let (replay, best) = (stat(replay), stat(best));
+        vec![
+            label.to_string(),
+            format!("{replay:.precision$}"),
+            format!("{best:.precision$}"),
+            format!("{:+.precision$}", replay - best),
+        ]
+    }
+
+    async fn solo(
+        solo: SoloSubCommand,
+        interaction: &InteractionCreate,
+        context: &Context<'_>,
+    ) -> anyhow::Result<anyhow::Result<()>> {
+        let input = ReplayInput {
+            attachment: solo.replay.as_ref(),
+            tetrio: solo.tetrio_replay.as_deref(),
+            archive_id: solo.replay_id,
+        };
+        let uploaded = match fetch_replay(input, interaction, context).await? {
+            Ok(uploaded) => uploaded,
+            Err(err) => return Ok(Err(err)),
+        };
+
+        Self::solo_report(uploaded, solo.compare_pb.unwrap_or(false), interaction, context).await
+    }
+
+    async fn solo_report(
+        uploaded: UploadedReplay,
+        compare_pb: bool,
+        interaction: &InteractionCreate,
+        context: &Context<'_>,
+    ) -> anyhow::Result<anyhow::Result<()>> {
+        // check that extension is ttr
+        if !uploaded.filename.ends_with(".ttr") {
+            return Ok(Err(anyhow!("❌ File type not supported: {:?}, expected a ttr replay", uploaded.filename)));
+        };
+
+        let replay = match SoloReplay::parse(&uploaded.bytes) {
+            Ok(replay) => replay,
+            Err(err) => return Ok(Err(err)),
+        };
+        uploaded
+            .archive(&ReplayMetadata::from_solo(&uploaded.filename, &replay), interaction, context)
+            .await;
+        let stats = &replay.stats;
+        let clears = &stats.clears;
+
+        let field = |name: &str, value: String| EmbedField {
+            inline: true,
+            name: name.to_string(),
+            value,
+        };
+        let breakdown = [
+            ("Singles", clears.singles),
+            ("Doubles", clears.doubles),
+            ("Triples", clears.triples),
+            ("Quads", clears.quads),
+            ("T-Spin Minis", clears.tspin_minis),
+            ("T-Spin Mini Singles", clears.tspin_mini_singles),
+            ("T-Spin Mini Doubles", clears.tspin_mini_doubles),
+            ("T-Spin Singles", clears.tspin_singles),
+            ("T-Spin Doubles", clears.tspin_doubles),
+            ("T-Spin Triples", clears.tspin_triples),
+            ("T-Spin Quads", clears.tspin_quads),
+            ("All Clears", clears.all_clears),
+        ]
+        .into_iter()
+        .filter(|(_, count)| *count > 0)
+        .map(|(label, count)| vec![label.to_string(), count.to_string()])
+        .collect::<Vec<_>>();
+
+        let mut embeds = vec![create_embed(None, context).await?
+            .title(format!("{} - {}", replay.username.to_uppercase(), replay.mode))
+            .field(field("Time", format_duration(stats.final_time)))
+            .field(field("Score", stats.score.to_string()))
+            .field(field("Lines", stats.lines.to_string()))
+            .field(field("Pieces", stats.pieces.to_string()))
+            .field(field("PPS", format!("{:.2}", stats.pps())))
+            .field(field("KPP", format!("{:.2}", stats.kpp())))
+            .field(field(
+                "Finesse",
+                format!("{} faults, {:.2}%", stats.finesse_faults, stats.finesse() * 100.0),
+            ))
+            .field(field("Max Combo", stats.max_combo.to_string()))
+            .field(field("Max B2B", stats.max_b2b.to_string()))
+            .field(EmbedField {
+                inline: false,
+                name: "Line Clears".to_string(),
+                value: if breakdown.is_empty() {
+                    "No line clear".to_string()
+                } else {
+                    format!("```\n{}\n```", format_table(&breakdown))
+                },
+            })
+            .build()];
+
+        if compare_pb {
+            let best = match replay.personal_best().await? {
+                Ok(best) => best,
+                Err(err) => return Ok(Err(err)),
+            };
+            let rows = vec![
+                ["Stat", "Replay", "PB", "Delta"].map(String::from).to_vec(),
+                Self::solo_row("Time (s)", stats, &best.stats, |stats| stats.final_time, 3),
+                Self::solo_row("Score", stats, &best.stats, |stats| stats.score as f64, 0),
+                Self::solo_row("PPS", stats, &best.stats, SoloStats::pps, 2),
+                Self::solo_row("KPP", stats, &best.stats, SoloStats::kpp, 2),
+                Self::solo_row("Finesse faults", stats, &best.stats, |stats| stats.finesse_faults as f64, 0),
+                Self::solo_row("Max Combo", stats, &best.stats, |stats| stats.max_combo as f64, 0),
+                Self::solo_row("Max B2B", stats, &best.stats, |stats| stats.max_b2b as f64, 0),
+                Self::solo_row("Quads", stats, &best.stats, |stats| stats.clears.quads as f64, 0),
+                Self::solo_row("T-Spins", stats, &best.stats, |stats| {
+                    let clears = &stats.clears;
+                    (clears.tspin_singles + clears.tspin_doubles + clears.tspin_triples + clears.tspin_quads) as f64
+                }, 0),
+            ];
+            let replay_link = best
+                .replay_id
+                .map(|id| format!("\n[Personal best replay](https://tetr.io/#r:{id})"))
+                .unwrap_or_default();
+
+            embeds.push(
+                create_embed(None, context).await?
+                    .title(format!("Compared to the personal best in {}", replay.mode))
+                    .description(format!("```\n{}\n```{replay_link}", format_table(&rows)))
+                    .build(),
+            );
+        }
+
+        context
+            .http_client
+            .interaction(context.application.id)
+            .update_response(&interaction.token)
+            .embeds(Some(&embeds))?
+            .await?;
+
+        Ok(Ok(()))
+    }
+}
+
+#[async_trait::async_trait]
+impl RunnableCommand for ReplayCommand {
+    async fn run(
+        _shard: u64,
+        interaction: &InteractionCreate,
+        data: Box<CommandData>,
+        context: &Context<'_>,
+    ) -> anyhow::Result<anyhow::Result<()>> {
+        log::info!("replay command");
+        let _command_timer = Timer::new("replay command");
+        context.defer_response(interaction).await?;
+        let model = Self::from_interaction(CommandInputData {
+            options: data.options,
+            resolved: data.resolved.map(Cow::Owned),
+        })?;
+
+        match model {
+            ReplayCommand::Analyze(analyze) => Self::analyze(analyze, interaction, context).await,
+            ReplayCommand::Board(board) => Self::board(board, interaction, context).await,
+            ReplayCommand::Versus(versus) => Self::versus(versus, interaction, context).await,
+            ReplayCommand::Solo(solo) => Self::solo(solo, interaction, context).await,
+        }
+    }
+}
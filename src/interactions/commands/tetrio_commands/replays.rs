@@ -0,0 +1,101 @@
+use std::borrow::Cow;
+
+use anyhow::anyhow;
+use chrono::NaiveDate;
+use twilight_interactions::command::{CommandInputData, CommandModel, CreateCommand};
+use twilight_model::{
+    application::interaction::application_command::CommandData,
+    gateway::payload::incoming::InteractionCreate,
+};
+
+use crate::{
+    context::Context,
+    services::replay_archive::{ReplayArchivePDO, MAX_SEARCH_RESULTS},
+    utils::{box_commands::RunnableCommand, create_embed::create_embed, table::format_table, timer::Timer},
+};
+
+#[derive(CreateCommand, CommandModel)]
+#[command(name = "replays", desc = "Search the replays uploaded in this server")]
+pub struct ReplaysCommand {
+    /// A player of the replay
+    player: Option<String>,
+
+    /// Another player of the replay
+    opponent: Option<String>,
+
+    /// Day the game was played, as YYYY-MM-DD
+    date: Option<String>,
+}
+
+#[async_trait::async_trait]
+impl RunnableCommand for ReplaysCommand {
+    async fn run(
+        _shard: u64,
+        interaction: &InteractionCreate,
+        data: Box<CommandData>,
+        context: &Context<'_>,
+    ) -> anyhow::Result<anyhow::Result<()>> {
+        log::info!("replays command");
+        let _command_timer = Timer::new("replays command");
+        context.defer_response(interaction).await?;
+        let model = Self::from_interaction(CommandInputData {
+            options: data.options,
+            resolved: data.resolved.map(Cow::Owned),
+        })?;
+
+        let Some(guild_id) = interaction.guild_id else {
+            return Ok(Err(anyhow!("❌ The replay archive is only available in servers")));
+        };
+
+        // the date is checked here, postgres would fail the whole search on a day that doesn't exist
+        let date = match model.date.as_deref().map(|date| (date, NaiveDate::parse_from_str(date, "%Y-%m-%d"))) {
+            Some((_, Ok(date))) => Some(date.format("%Y-%m-%d").to_string()),
+            Some((date, Err(_))) => {
+                return Ok(Err(anyhow!("❌ `{date}` is not a date, use the YYYY-MM-DD format")));
+            }
+            None => None,
+        };
+
+        let replays = ReplayArchivePDO::search_replays(
+            context,
+            guild_id.get(),
+            model.player.as_deref(),
+            model.opponent.as_deref(),
+            date.as_deref(),
+        )
+        .await?;
+
+        if replays.is_empty() {
+            return Ok(Err(anyhow!("❌ No archived replay matches this search")));
+        }
+
+        let rows = std::iter::once(["Id", "Date", "Players", "Rounds", "Result"].map(String::from).to_vec())
+            .chain(replays.iter().map(|replay| {
+                vec![
+                    replay.id_replay.to_string(),
+                    replay.ts.clone().unwrap_or_else(|| "?".to_string()),
+                    replay.players.join(" vs "),
+                    replay.rounds.to_string(),
+                    replay.result.clone().unwrap_or_default(),
+                ]
+            }))
+            .collect::<Vec<_>>();
+
+        let embed = create_embed(None, context).await?
+            .title("Archived replays")
+            .description(format!(
+                "```\n{}\n```\nShowing the {MAX_SEARCH_RESULTS} latest matches at most, use `replay_id` in any replay command to run it again.",
+                format_table(&rows)
+            ))
+            .build();
+
+        context
+            .http_client
+            .interaction(context.application.id)
+            .update_response(&interaction.token)
+            .embeds(Some(&[embed]))?
+            .await?;
+
+        Ok(Ok(()))
+    }
+}
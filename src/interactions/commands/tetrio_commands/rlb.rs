@@ -2,6 +2,7 @@ use std::borrow::Cow;
 
 use anyhow::anyhow;
 use itertools::Itertools;
+use tetrio_api::models::users::user_rank::UserRank;
 
 use twilight_interactions::command::{CommandInputData, CommandModel, CreateCommand};
 use twilight_model::{
@@ -14,7 +15,7 @@ use crate::{
     interactions::commands::options::{
         user_rank_option::UserRankOption, user_stat_options::UserStatOption,
     },
-    utils::box_commands::RunnableCommand,
+    utils::{box_commands::RunnableCommand, leaderboard_browser::LeaderboardView, stat_formula::resolve_formula},
 };
 
 use super::lb::LbCommand;
@@ -30,7 +31,7 @@ pub struct RLbCommand {
     /// How many places to display
     limit: i64,
 
-    /// Where to start searching for players
+    /// Opens the page holding this position of the leaderboard
     position: Option<i64>,
 
     /// Only played in this rank will be displayed
@@ -38,6 +39,9 @@ pub struct RLbCommand {
 
     /// country to limit the placements from
     country_code: Option<String>,
+
+    /// A saved formula name or an expression to use instead of the leaderboard stat
+    formula: Option<String>,
 }
 
 #[async_trait::async_trait]
@@ -56,23 +60,46 @@ impl RunnableCommand for RLbCommand {
             resolved: data.resolved.map(Cow::Owned),
         })?;
 
-        let leaderboard = context
-            .fetch_full_leaderboard(model.country_code.as_deref())
-            .await?;
-
-        let Some(data) = &leaderboard.data else {
+        let Some(data) = context
+            .leaderboard_cache
+            .leaderboard(model.country_code.as_deref(), context)
+            .await?
+        else {
             return Ok(Err(anyhow!("❌ Couldn't fetch leaderboard data!")));
         };
+        let data = &*data;
+
+        // Formulas don't fit in the components, these leaderboards can't be browsed
+        let Some(formula) = &model.formula else {
+            let limit = model.limit.max(1) as usize;
+            let view = LeaderboardView {
+                stat: model.leaderboard_stat,
+                rank: model.rank.map(UserRank::from),
+                reverse: true,
+                page: model.position.map(|position| (position.max(1) as usize - 1) / limit + 1).unwrap_or(1),
+                limit,
+                country: model.country_code.map(|country| country.to_uppercase()),
+            };
+            return LbCommand::send_view(view, data, interaction, context).await;
+        };
 
         let iter = LbCommand::filter_rank(data, &model.rank);
 
-        let v = LbCommand::get_stats(&model.leaderboard_stat, iter)
+        let formula = match resolve_formula(formula, interaction.guild_id, context).await? {
+            Ok(formula) => formula,
+            Err(err) => return Ok(Err(err)),
+        };
+        let stats = LbCommand::get_formula_stats(&formula, iter);
+
+        let v = stats
             .into_iter()
             .sorted_by(|(_, _, a), (_, _, b)| a.total_cmp(b));
 
+        // same page as the browsable leaderboards
+        let limit = model.limit.max(1) as usize;
         let skip = model
             .position
-            .map(|position| (position as usize).saturating_sub(model.limit as usize))
+            .map(|position| (position.max(1) as usize - 1) / limit * limit)
             .unwrap_or(0);
 
         let content = v
@@ -0,0 +1,145 @@
+use std::borrow::Cow;
+
+use anyhow::anyhow;
+use itertools::Itertools;
+use twilight_interactions::command::{CommandInputData, CommandModel, CreateCommand};
+use twilight_model::{
+    application::interaction::application_command::CommandData,
+    gateway::payload::incoming::InteractionCreate,
+};
+
+use crate::{
+    context::Context,
+    interactions::commands::options::user_rank_option::UserRankOption,
+    utils::{
+        box_commands::RunnableCommand,
+        player_source::source_or_caller,
+        similarity::{playstyle_vector, PlaystyleSpace},
+        stats::{calculate_stats, PlayerStats},
+        timer::Timer,
+    },
+};
+
+use super::{lb::LbCommand, vs::VsCommand};
+
+#[derive(CreateCommand, CommandModel)]
+#[command(name = "similar", desc = "Find the players with the most similar playstyle")]
+pub struct SimilarCommand {
+    /// A tetrio user, (pps, apm, vs), discord ping, $avgX where X is a rank, e.g S+ or $avgX:COUNTRY_CODE, defaults to you
+    user: Option<String>,
+
+    #[command(min_value = 1, max_value = 25)]
+    /// How many players to display, defaults to 10
+    count: Option<i64>,
+
+    /// Only players in this rank will be compared
+    rank: Option<UserRankOption>,
+
+    /// country to limit the players from
+    country_code: Option<String>,
+
+    /// Get a dark mode chart
+    dark_mode: Option<bool>,
+}
+
+#[async_trait::async_trait]
+impl RunnableCommand for SimilarCommand {
+    async fn run(
+        _shard: u64,
+        interaction: &InteractionCreate,
+        data: Box<CommandData>,
+        context: &Context<'_>,
+    ) -> anyhow::Result<anyhow::Result<()>> {
+        log::info!("similar command");
+        let _command_timer = Timer::new("similar command");
+        context.defer_response(interaction).await?;
+        let model = Self::from_interaction(CommandInputData {
+            options: data.options,
+            resolved: data.resolved.map(Cow::Owned),
+        })?;
+
+        let user = match source_or_caller(model.user, interaction) {
+            Ok(user) => user,
+            Err(err) => return Ok(Err(err)),
+        };
+        let (name, stats) = match VsCommand::parse_user(user, context).await? {
+            Ok(user) => user,
+            Err(err) => return Ok(Err(err)),
+        };
+
+        let Some(target) = playstyle_vector(&calculate_stats(stats.clone())) else {
+            return Ok(Err(anyhow!("❌ Couldn't compute the playstyle of {name}")));
+        };
+
+        let leaderboard = context
+            .fetch_full_leaderboard(model.country_code.as_deref())
+            .await?;
+
+        let Some(data) = &leaderboard.data else {
+            return Ok(Err(anyhow!("❌ Couldn't fetch leaderboard data!")));
+        };
+
+        let players = {
+            let _timer = Timer::new("similar embedding players");
+            LbCommand::filter_rank(data, &model.rank)
+                .filter_map(|(position, user)| {
+                    let (Some(pps), Some(apm), Some(vs)) =
+                        (user.league.pps, user.league.apm, user.league.vs) else {
+                            return None
+                        };
+                    let stats = PlayerStats {
+                        apm,
+                        pps,
+                        vs,
+                        rd: Some(user.league.rd),
+                        tr: Some(user.league.tr),
+                        glicko: Some(user.league.glicko),
+                        rank: user.league.rank.clone(),
+                    };
+                    let vector = playstyle_vector(&calculate_stats(stats.clone()))?;
+                    Some((position, user.username.to_string(), stats, vector))
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let space = PlaystyleSpace::from_vectors(players.iter().map(|(_, _, _, vector)| vector));
+
+        let similar = players
+            .into_iter()
+            .filter(|(_, username, _, _)| !username.eq_ignore_ascii_case(&name))
+            .map(|(position, username, stats, vector)| {
+                (position, username, stats, space.similarity(&target, &vector))
+            })
+            .sorted_by(|(_, _, _, a), (_, _, _, b)| b.total_cmp(a))
+            .take(model.count.unwrap_or(10) as usize)
+            .collect::<Vec<_>>();
+
+        let Some((_, closest_name, closest_stats, _)) = similar.first().cloned() else {
+            return Ok(Err(anyhow!("❌ Couldn't find any player to compare with")));
+        };
+
+        let list = similar
+            .iter()
+            .enumerate()
+            .map(|(index, (position, username, _, similarity))| {
+                format!("#{}: {} (Rank: #{}): {:.2}%", index + 1, username, position + 1, similarity)
+            })
+            .join("\n");
+
+        let chart = VsCommand::graph_with_stats(
+            vec![(name.clone(), stats), (closest_name, closest_stats)],
+            model.dark_mode.unwrap_or(false),
+            context.chart_backend,
+        )
+        .await?;
+
+        let content = format!("Players that play like {name}:\n```\n{list}\n```");
+        if !chart.fits(&content) {
+            return Ok(Err(anyhow!("❌ Message content was too long!")));
+        }
+
+        chart.send(&content, interaction, context).await?;
+
+        Ok(Ok(()))
+    }
+}
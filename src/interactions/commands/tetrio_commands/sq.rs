@@ -1,9 +1,7 @@
 use std::borrow::Cow;
 
 
-use anyhow::anyhow;
 use async_trait::async_trait;
-use serde_json::json;
 use twilight_interactions::command::{CommandInputData, CommandModel, CreateCommand};
 
 use twilight_model::application::interaction::application_command::CommandData;
@@ -13,10 +11,12 @@ use crate::context::Context;
 
 use crate::utils::timer::Timer;
 use crate::utils::box_commands::{CommandBox, RunnableCommand};
+use crate::utils::charts::{Chart, ChartBackend, ChartDataset, ChartKind, RenderedChart};
 use crate::utils::stats::{calculate_stats, PlayerStats};
 
 use crate::interactions::commands::models::graph_user_model::{
-    AverageSubCommand, DiscordUserSubCommand, GraphUser, StatsSubCommand, TetrioUserSubCommand,
+    AverageSubCommand, DiscordUserSubCommand, GraphUser, SourceSubCommand, StatsSubCommand,
+    TetrioUserSubCommand,
 };
 
 #[derive(CreateCommand)]
@@ -35,6 +35,9 @@ pub enum SqCommand {
     #[command(name = "average")]
     /// Use average stats
     Average(AverageSubCommand),
+    #[command(name = "source")]
+    /// Use any player source
+    Source(SourceSubCommand),
 }
 
 impl SqCommand {
@@ -46,19 +49,12 @@ impl SqCommand {
         }
     }
 
-    pub fn get_font_color(dark_mode: bool) -> &'static str {
-        if dark_mode {
-            "#F5F5F5"
-        } else {
-            "#000000"
-        }
-    }
-
     async fn graph_with_stats(
         username: &str,
         dark_mode: bool,
         stats: PlayerStats,
-    ) -> anyhow::Result<String> {
+        backend: ChartBackend,
+    ) -> anyhow::Result<RenderedChart> {
         let stats = calculate_stats(stats);
         let attack = (stats.apm / 60.0) * 0.4;
         let speed = stats.pps / 3.75;
@@ -67,51 +63,20 @@ impl SqCommand {
 
         log::debug!("{attack} {speed} {defense} {cheese}");
 
-        let json = json!({
-            "type": "radar",
-            "data": {
-                "labels": ["ATTACK", "SPEED", "DEFENSE", "CHEESE"],
-                "datasets": [{
-                    "label": username,
-                    "data": [
-                        attack, speed, defense, cheese
-                    ],
-                    "backgroundColor": Self::get_background_colors(dark_mode),
-                    "borderColor": Self::get_background_colors(dark_mode),
-                    "borderWidth": 0,
-                    "pointRadius": 0
-                }]
-            },
-            "options":{"legend": { "labels": { "fontColor": Self::get_font_color(dark_mode), "fontSize": 16}}, "scale":{"pointLabels":{"fontColor":Self::get_font_color(dark_mode), "fontSize": 16},"rAxis":{"ticks":{"display":false}},"ticks":{"min":0,"max":1.2,"stepSize":"0.2","fontColor":"blue","display":false},"gridLines":{"color":"gray"},"angleLines":{"color":"gray"}}}
-        });
-
-        let json = json!({
-            "width": 500,
-            "height": 300,
-            "format": "webp",
-            "background": "transparent",
-            "version": 2,
-            "chart": json
-        });
-
-        log::debug!("{json}");
-
-        let response = reqwest::Client::builder()
-            .build()?
-            .post("https://quickchart.io/chart/create")
-            .header("Content-Type", "application/json")
-            .body(json.to_string())
-            .send()
-            .await?
-            .json::<serde_json::Value>()
-            .await?;
-
-        Ok(response
-            .get("url")
-            .ok_or(anyhow!("Expected a string for the url of the chart"))?
-            .as_str()
-            .ok_or(anyhow!("Expected a string"))?
-            .to_string())
+        Chart {
+            kind: ChartKind::Radar,
+            labels: ["ATTACK", "SPEED", "DEFENSE", "CHEESE"].map(String::from).to_vec(),
+            datasets: vec![ChartDataset {
+                label: username.to_string(),
+                data: vec![attack, speed, defense, cheese],
+                color: Self::get_background_colors(dark_mode),
+            }],
+            min: 0.0,
+            max: 1.2,
+            dark_mode,
+        }
+        .render(backend)
+        .await
     }
 }
 
@@ -132,7 +97,7 @@ impl RunnableCommand for SqCommand {
             resolved: data.resolved.map(Cow::Owned),
         })?;
 
-        let data = model.get_data(&context).await?;
+        let data = model.get_data(interaction, &context).await?;
 
         let data = match data {
             Ok(data) => data,
@@ -151,17 +116,9 @@ impl RunnableCommand for SqCommand {
             String::new()
         };
 
-        let url = Self::graph_with_stats(&data.name, data.dark_mode, data.stats).await?;
-
-        let content = format!("{replay_str}\n{url}");
-
+        let chart = Self::graph_with_stats(&data.name, data.dark_mode, data.stats, context.chart_backend).await?;
 
-        context
-            .http_client
-            .interaction(context.application.id)
-            .update_response(&interaction.token)
-            .content(Some(&content))?
-            .await?;
+        chart.send(&replay_str, interaction, context).await?;
 
         Ok(Ok(()))
     }
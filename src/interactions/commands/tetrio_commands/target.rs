@@ -0,0 +1,197 @@
+use std::borrow::Cow;
+
+use anyhow::anyhow;
+use tetrio_api::models::{packet::Packet, users::user_rank::UserRank};
+use twilight_interactions::command::{CommandInputData, CommandModel, CreateCommand};
+use twilight_model::{
+    application::interaction::application_command::CommandData,
+    channel::message::embed::EmbedField,
+    gateway::payload::incoming::InteractionCreate,
+};
+
+use crate::{
+    context::Context,
+    interactions::commands::options::user_rank_option::UserRankOption,
+    utils::{
+        box_commands::RunnableCommand,
+        create_embed::create_embed,
+        stat_solver::{estimated_tr, nearest_paths, solve_missing_stat, SolvedStat, TargetStats},
+        timer::Timer,
+    },
+};
+
+use super::vs::VsCommand;
+
+#[derive(CreateCommand, CommandModel)]
+#[command(name = "target", desc = "Find the stats needed to reach a TR or a rank, solving for one of APM, PPS and VS at most")]
+pub struct TargetCommand {
+    /// The rank to reach
+    rank: Option<UserRankOption>,
+    /// The TR to reach
+    #[command(min_value = 0.0, max_value = 25000.0)]
+    tr: Option<f64>,
+    /// A tetrio user, (pps, apm, vs), discord ping, $avgX where X is a rank, e.g S+ or $avgX:COUNTRY_CODE
+    user: Option<String>,
+    /// attacks per minute, leave it empty to solve for it from the two others
+    apm: Option<f64>,
+    /// pieces per second, leave it empty to solve for it from the two others
+    pps: Option<f64>,
+    /// vs score, leave it empty to solve for it from the two others
+    vs: Option<f64>,
+    /// rd, defaults to 60.9
+    rd: Option<f64>,
+}
+
+impl TargetCommand {
+    async fn target_tr(
+        rank: Option<UserRankOption>,
+        tr: Option<f64>,
+        context: &Context<'_>,
+    ) -> anyhow::Result<anyhow::Result<(String, f64)>> {
+        match (rank, tr) {
+            (Some(rank), _) => {
+                let rank: UserRank = rank.into();
+                let Packet { data: Some(data), .. } = context.tetrio_client.fetch_leagueranks().await? else {
+                    return Ok(Err(anyhow!("❌ Couldn't find user ranks data!")));
+                };
+
+                let Some(league_rank) = data.data.ranks.get(&rank) else {
+                    return Ok(Err(anyhow!("❌ Couldn't find stats for rank {}", rank)));
+                };
+
+                Ok(Ok((format!("RANK {}", rank.to_string().to_uppercase()), league_rank.tr)))
+            }
+            (None, Some(tr)) => Ok(Ok((format!("{tr:.1} TR"), tr))),
+            (None, None) => Ok(Err(anyhow!("❌ You need to give either a rank or a TR to reach"))),
+        }
+    }
+
+    fn format_change(name: &str, from: Option<f64>, to: f64) -> String {
+        match from {
+            Some(from) => {
+                let difference = to - from;
+                format!(
+                    "{name}: {from:.2} → **{to:.2}** ({}{difference:.2})",
+                    if difference >= 0.0 { "+" } else { "" }
+                )
+            }
+            None => format!("{name}: **{to:.2}**"),
+        }
+    }
+
+    fn format_path(current: (Option<f64>, Option<f64>, Option<f64>), stats: &TargetStats) -> String {
+        [
+            Self::format_change("APM", current.0, stats.apm),
+            Self::format_change("PPS", current.1, stats.pps),
+            Self::format_change("VS", current.2, stats.vs),
+        ]
+        .join("\n")
+    }
+}
+
+#[async_trait::async_trait]
+impl RunnableCommand for TargetCommand {
+    async fn run(
+        _shard: u64,
+        interaction: &InteractionCreate,
+        data: Box<CommandData>,
+        context: &Context<'_>,
+    ) -> anyhow::Result<anyhow::Result<()>> {
+        log::info!("target command");
+        let _command_timer = Timer::new("target command");
+        context.defer_response(interaction).await?;
+        let model = Self::from_interaction(CommandInputData {
+            options: data.options,
+            resolved: data.resolved.map(Cow::Owned),
+        })?;
+
+        let (target_name, target_tr) = match Self::target_tr(model.rank, model.tr, context).await? {
+            Ok(target) => target,
+            Err(err) => return Ok(Err(err)),
+        };
+
+        let (name, apm, pps, vs, rd) = match model.user {
+            Some(user) => {
+                let (name, stats) = match VsCommand::parse_user(user, context).await? {
+                    Ok(user) => user,
+                    Err(err) => return Ok(Err(err)),
+                };
+                (
+                    Some(name),
+                    model.apm.or(Some(stats.apm)),
+                    model.pps.or(Some(stats.pps)),
+                    model.vs.or(Some(stats.vs)),
+                    model.rd.or(stats.rd),
+                )
+            }
+            None => (None, model.apm, model.pps, model.vs, model.rd),
+        };
+
+        let builder = create_embed(None, context).await?
+            .title(format!("STATS NEEDED TO REACH {target_name}"))
+            .description(format!(
+                "Target: **{target_tr:.1} TR**{}",
+                name.map(|name| format!("\nFrom: {name}")).unwrap_or_default()
+            ));
+
+        let missing = [
+            (SolvedStat::Apm, apm),
+            (SolvedStat::Pps, pps),
+            (SolvedStat::Vs, vs),
+        ]
+        .into_iter()
+        .filter(|(_, value)| value.is_none())
+        .map(|(stat, _)| stat)
+        .collect::<Vec<_>>();
+
+        let builder = match (missing.as_slice(), apm, pps, vs) {
+            ([], Some(apm), Some(pps), Some(vs)) => {
+                let builder = builder.field(EmbedField {
+                    inline: false,
+                    name: "Current Est. of TR".to_string(),
+                    value: format!("{:.1}", estimated_tr(apm, pps, vs, rd)),
+                });
+
+                nearest_paths(apm, pps, vs, rd, target_tr)
+                    .into_iter()
+                    .fold(builder, |builder, path| {
+                        builder.field(EmbedField {
+                            inline: true,
+                            name: format!("Improving {}", path.name),
+                            value: path
+                                .stats
+                                .map(|stats| Self::format_path((Some(apm), Some(pps), Some(vs)), &stats))
+                                .unwrap_or("Unreachable".to_string()),
+                        })
+                    })
+            }
+            ([stat], _, _, _) => {
+                let value = solve_missing_stat(*stat, apm, pps, vs, rd, target_tr)
+                    .map(|stats| Self::format_path((apm, pps, vs), &stats))
+                    .unwrap_or("Unreachable".to_string());
+
+                builder.field(EmbedField {
+                    inline: false,
+                    name: format!("{} needed", stat.name()),
+                    value,
+                })
+            }
+            _ => {
+                return Ok(Err(anyhow!(
+                    "❌ At least two of APM, PPS and VS are needed to find the missing one"
+                )))
+            }
+        };
+
+        let embed = builder.build();
+
+        context
+            .http_client
+            .interaction(context.application.id)
+            .update_response(&interaction.token)
+            .embeds(Some(&[embed]))?
+            .await?;
+
+        Ok(Ok(()))
+    }
+}
@@ -1,7 +1,6 @@
 use std::borrow::Cow;
 
 use anyhow::anyhow;
-use tetrio_api::models::packet::Packet;
 use twilight_interactions::command::{CommandInputData, CommandModel, CreateCommand};
 use twilight_model::{
     application::interaction::application_command::CommandData,
@@ -12,10 +11,13 @@ use crate::{
     context::Context,
     utils::{
         box_commands::{CommandBox, RunnableCommand},
+        player_source::{discord_user_or_caller, resolve_discord_user, GameSelector, PlayerSource},
         timer::Timer,
     },
 };
 
+use super::ts::TsCommand;
+
 use crate::interactions::commands::subcommands::teto::{
     discord_user_sub_command::DiscordUserSubCommand, tetrio_user_sub_command::TetrioUserSubCommand,
 };
@@ -31,6 +33,40 @@ pub enum TetoCommand {
     Tetrio(TetrioUserSubCommand),
 }
 
+impl TetoCommand {
+    /// Answers with the stats of the profile as text when the image server is down.
+    async fn degraded_response(
+        username: String,
+        interaction: &InteractionCreate,
+        context: &Context<'_>,
+    ) -> anyhow::Result<anyhow::Result<()>> {
+        let source = PlayerSource::Tetrio {
+            user: username.clone(),
+            selector: GameSelector::default(),
+        };
+        let player = match source.resolve(context).await? {
+            Ok(player) => player,
+            Err(err) => return Ok(Err(err)),
+        };
+
+        let embed = TsCommand::player_embed(
+            player,
+            format!("The image server is unavailable, here are the stats instead.\nProfile link: <https://ch.tetr.io/u/{username}>"),
+            context,
+        )
+        .await?;
+
+        context
+            .http_client
+            .interaction(context.application.id)
+            .update_response(&interaction.token)
+            .embeds(Some(&[embed]))?
+            .await?;
+
+        Ok(Ok(()))
+    }
+}
+
 #[async_trait::async_trait]
 impl RunnableCommand for TetoCommand {
     async fn run(
@@ -52,16 +88,21 @@ impl RunnableCommand for TetoCommand {
           
             let username = match &model {
                 TetoCommand::Discord(discord) => {
-                    let packet = context
-                        .tetrio_client
-                        .search_discord_user(&discord.user.resolved.id.to_string())
-                        .await?;
+                    let id = match discord_user_or_caller(discord.user.as_ref(), interaction) {
+                        Ok(id) => id,
+                        Err(err) => return Ok(Err(err)),
+                    };
+                    let id = match resolve_discord_user(id, context).await? {
+                        Ok(id) => id,
+                        Err(err) => return Ok(Err(err)),
+                    };
 
+                    let packet = context.tetrio_client.fetch_user_info(&id).await?;
                     let Some(data) = &packet.data else {
-                        return Ok(Err(anyhow!("❌ Couldn't find your tetrio id from the discord account, they might have not linked it publicly to their tetrio profile")));
+                        return Ok(Err(anyhow!("❌ Couldn't find tetrio user {id}")));
                     };
 
-                    data.user.username.clone()
+                    data.username.to_string().into()
                 }
                 TetoCommand::Tetrio(tetrio) => {
                     tetrio.tetrio_user.clone().into()
@@ -72,32 +113,25 @@ impl RunnableCommand for TetoCommand {
 
         context.tetrio_client.fetch_user_summaries(&username).await?;
 
-        let buffer = 
-            reqwest::get(format!("{}/api/v1/teto/{username}", context.api_url)).await?.json::<Packet<Box<[u8]>>>().await?;
-
-        match buffer {
-            Packet { success: true, data: Some(data), .. } => {
-                context
-                .http_client
-                .interaction(context.application.id)
-                .update_response(&interaction.token)
-                .content(Some(&format!(
-                    "Profile link: <https://ch.tetr.io/u/{}>",
-                    username
-                )))?
-                .attachments(&[Attachment::from_bytes("tetra.png".to_string(), data.to_vec(), 1)])?
-                .await?;
+        let buffer = match context.image_server.teto(&username).await {
+            Ok(buffer) => buffer,
+            Err(err) if err.is_unavailable() => {
+                log::warn!("teto image unavailable, answering with text: {err}");
+                return Self::degraded_response(username.to_string(), interaction, context).await;
             }
-            Packet { error: Some(error), .. } => {
-                return Ok(Err(anyhow!("❌ {}", error.msg)));
-            }
-            _ => {
-                return Ok(Err(anyhow!("❌ Unknown error!")));
-
-            }
-        }
-
+            Err(err) => return Ok(Err(anyhow!("❌ {err}"))),
+        };
 
+        context
+            .http_client
+            .interaction(context.application.id)
+            .update_response(&interaction.token)
+            .content(Some(&format!(
+                "Profile link: <https://ch.tetr.io/u/{}>",
+                username
+            )))?
+            .attachments(&[Attachment::from_bytes("tetra.png".to_string(), buffer, 1)])?
+            .await?;
 
         Ok(Ok(()))
     }
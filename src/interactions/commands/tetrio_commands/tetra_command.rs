@@ -4,7 +4,6 @@ use anyhow::anyhow;
 
 use common::LeagueRecord;
 use serde_json::json;
-use tetrio_api::models::packet::Packet;
 use twilight_interactions::command::{CommandInputData, CommandModel, CreateCommand};
 
 use twilight_model::application::interaction::application_command::CommandData;
@@ -13,17 +12,22 @@ use twilight_model::{gateway::payload::incoming::InteractionCreate, http::attach
 use crate::context::Context;
 use crate::interactions::commands::subcommands::tetra::ttrm_replay_sub_command::TetrioReplaySubCommand;
 use crate::utils::box_commands::{CommandBox, RunnableCommand};
+use crate::utils::image_server::TetraImage;
+use crate::utils::player_source::{discord_user_or_caller, resolve_discord_user, GameSelector, PlayerSource, ResolvedPlayer};
+use crate::utils::replay_analysis::ReplayAnalysis;
+use crate::utils::replay_archive::{fetch_replay, ReplayInput, ReplayMetadata};
 
 use crate::interactions::commands::subcommands::tetra::discord_user_sub_command::DiscordUserSubCommand;
 use crate::interactions::commands::subcommands::tetra::tetrio_user_sub_command::TetrioUserSubCommand;
 use crate::utils::timer::Timer;
 
-use serde::Deserialize;
+use super::ts::TsCommand;
 
-#[derive(Deserialize)]
-struct TetraData {
-    replay_id: Option<String>, 
-    buffer: Vec<u8>
+/// What to show when the image server is down.
+enum Fallback {
+    Player(PlayerSource),
+    /// Bytes of a ttrm replay
+    Replay(Vec<u8>),
 }
 
 #[derive(CreateCommand, CommandModel)]
@@ -40,6 +44,59 @@ pub enum TetraCommand {
     Replay(TetrioReplaySubCommand),
 }
 
+impl TetraCommand {
+    /// Answers with the stats of the players as text when the image server is down.
+    async fn degraded_response(
+        fallback: Fallback,
+        interaction: &InteractionCreate,
+        context: &Context<'_>,
+    ) -> anyhow::Result<anyhow::Result<()>> {
+        let players = match fallback {
+            Fallback::Player(source) => match source.resolve(context).await? {
+                Ok(player) => vec![player],
+                Err(err) => return Ok(Err(err)),
+            },
+            Fallback::Replay(bytes) => {
+                let analysis = match ReplayAnalysis::parse(&bytes) {
+                    Ok(analysis) => analysis,
+                    Err(err) => return Ok(Err(err)),
+                };
+
+                analysis
+                    .players
+                    .iter()
+                    .map(|player| ResolvedPlayer {
+                        name: player.username.clone(),
+                        stats: player.average(),
+                        avatar_url: None,
+                        replay_url: None,
+                        round: None,
+                        games: None,
+                    })
+                    .collect()
+            }
+        };
+
+        let mut embeds = vec![];
+        for player in players {
+            let description = match &player.replay_url {
+                Some(url) => format!("The image server is unavailable, here are the stats instead.\nReplay url: <{url}>"),
+                None => "The image server is unavailable, here are the stats instead.".to_string(),
+            };
+            embeds.push(TsCommand::player_embed(player, description, context).await?);
+        }
+
+        context
+            .http_client
+            .interaction(context.application.id)
+            .update_response(&interaction.token)
+            .embeds(Some(&embeds))?
+            .await?;
+
+        Ok(Ok(()))
+    }
+}
+
 #[async_trait::async_trait]
 impl RunnableCommand for TetraCommand {
     async fn run(
@@ -56,22 +113,25 @@ impl RunnableCommand for TetraCommand {
             options: data.options,
             resolved: data.resolved.map(Cow::Owned),
         })?;
-        let buffer = {
+        let (image, fallback) = {
             let _timer = Timer::new("tetra command parsing input & fetching user");
             match model {
                 TetraCommand::Discord(discord) => {
-                    let packet = context
-                        .tetrio_client
-                        .search_discord_user(&discord.user.resolved.id.to_string())
-                        .await?;
-
-                    let Some(data) = &packet.data else {
-                        return Ok(Err(anyhow!("❌ Couldn't find your tetrio id from the discord account, they might have not linked it publicly to their tetrio profile")));
+                    let id = match discord_user_or_caller(discord.user.as_ref(), interaction) {
+                        Ok(id) => id,
+                        Err(err) => return Ok(Err(err)),
+                    };
+                    let id = match resolve_discord_user(id, context).await? {
+                        Ok(id) => id,
+                        Err(err) => return Ok(Err(err)),
                     };
 
-                    let (id, game_num) = (data.user.id.clone(), discord.game_number.unwrap_or(1));
+                    let game_num = discord.game_number.unwrap_or(1);
 
-                    reqwest::get(format!("{}/api/v1/tetra?user_id={id}&game_num={game_num}", context.api_url)).await?.json::<Packet<TetraData>>().await?
+                    (
+                        context.image_server.tetra(&id, game_num).await,
+                        Fallback::Player(PlayerSource::Tetrio { user: id, selector: GameSelector::new(Some(game_num), None) }),
+                    )
                 }
                 TetraCommand::Tetrio(tetrio) => {
                     let packet = context
@@ -83,24 +143,38 @@ impl RunnableCommand for TetraCommand {
                         return Ok(Err(anyhow!("❌ Couldn't find tetrio user")));
                     };
 
-                    let (id, game_num) = (data.id.clone(), tetrio.game_number.unwrap_or(1));
+                    let (id, game_num) = (data.id.to_string(), tetrio.game_number.unwrap_or(1));
 
-                    reqwest::get(format!("{}/api/v1/tetra?user_id={id}&game_num={game_num}", context.api_url)).await?.json::<Packet<TetraData>>().await?            
+                    (
+                        context.image_server.tetra(&id, game_num).await,
+                        Fallback::Player(PlayerSource::Tetrio { user: id, selector: GameSelector::new(Some(game_num), None) }),
+                    )
                 },
                 TetraCommand::Replay(replay) => {
                     
-                    let attachment = &replay.replay;
+                    let input = ReplayInput {
+                        attachment: replay.replay.as_ref(),
+                        tetrio: replay.tetrio_replay.as_deref(),
+                        archive_id: replay.replay_id,
+                    };
+                    let uploaded = match fetch_replay(input, interaction, context).await? {
+                        Ok(uploaded) => uploaded,
+                        Err(err) => return Ok(Err(err)),
+                    };
                     // check that extension is ttrm
-                    if !attachment.filename.ends_with("ttrm") {
-                        return Ok(Err(anyhow!("❌ File type not supported: {:?}", attachment.filename)));
+                    if !uploaded.filename.ends_with("ttrm") {
+                        return Ok(Err(anyhow!("❌ File type not supported: {:?}", uploaded.filename)));
                     };
 
-                    let bytes = reqwest::get(&attachment.url)
-                        .await?
-                        .bytes()
-                        .await?;
+                    let raw: serde_json::Value = serde_json::from_slice(&uploaded.bytes)
+                    .map_err(|err| anyhow!("❌ Couldn't parse ttrm replay: {:?}", err))?;
+                    if let Ok(analysis) = ReplayAnalysis::from_raw(raw.clone()) {
+                        uploaded
+                            .archive(&ReplayMetadata::from_analysis(&uploaded.filename, &analysis), interaction, context)
+                            .await;
+                    }
 
-                    let replay_data:common::replay::ttrm::models::Root = serde_json::from_slice(&bytes)
+                    let replay_data:common::replay::ttrm::models::Root = serde_json::from_value(raw)
                     .map_err(|err| anyhow!("❌ Couldn't parse ttrm replay: {:?}", err))?;
 
 
@@ -116,56 +190,36 @@ impl RunnableCommand for TetraCommand {
                     if league_record.rounds.len() > 14 {
                         return Ok(Err(anyhow!("❌ Replay has more than 14 rounds")));
                     }
-                    let reqwest_client = reqwest::Client::new();
 
-                    reqwest_client.post(format!("{}/api/v1/tetra/replay", context.api_url))
-                    .json(&json!{
-                        {
-                            "ts": ts,
-                            "league_record": league_record
-                        }
-                    })
-                    .send()
-                    .await?
-                    .json()
-                    .await?
+                    (
+                        context.image_server.tetra_replay(json!(ts), &league_record).await,
+                        Fallback::Replay(uploaded.bytes),
+                    )
                 }
             }
         };
 
-
-
-        match buffer {
-            Packet { success: true, data: Some(TetraData { replay_id: Some(replay_id), buffer }), .. } => {
-                context
-                .http_client
-                .interaction(context.application.id)
-                .update_response(&interaction.token)
-                .content(Some(&format!(
-                    "Replay url: <https://tetr.io/#r:{}>",
-                    replay_id
-                )))?
-                .attachments(&[Attachment::from_bytes("tetra.png".to_string(), buffer, 1)])?
-                .await?;
-            },
-            Packet { success: true, data: Some(TetraData { replay_id: _replay_id, buffer }), .. } => {
-                context
-                .http_client
-                .interaction(context.application.id)
-                .update_response(&interaction.token)
-                .attachments(&[Attachment::from_bytes("tetra.png".to_string(), buffer, 1)])?
-                .await?;
-            }
-            Packet { error: Some(error), .. } => {
-                return Ok(Err(anyhow!("❌ {}", error.msg)));
-            }
-            _ => {
-                return Ok(Err(anyhow!("❌ Unknown error!")));
-
+        let TetraImage { replay_id, buffer } = match image {
+            Ok(image) => image,
+            Err(err) if err.is_unavailable() => {
+                log::warn!("tetra image unavailable, answering with text: {err}");
+                return Self::degraded_response(fallback, interaction, context).await;
             }
-        }
-
+            Err(err) => return Ok(Err(anyhow!("❌ {err}"))),
+        };
 
+        let update = context
+            .http_client
+            .interaction(context.application.id)
+            .update_response(&interaction.token);
+        let replay_url = replay_id.map(|replay_id| format!("Replay url: <https://tetr.io/#r:{}>", replay_id));
+        let update = match &replay_url {
+            Some(replay_url) => update.content(Some(replay_url))?,
+            None => update,
+        };
+        update
+            .attachments(&[Attachment::from_bytes("tetra.png".to_string(), buffer, 1)])?
+            .await?;
 
         Ok(Ok(()))
     }
@@ -2,13 +2,13 @@ use std::borrow::Cow;
 
 
 use anyhow::anyhow;
-use common::Average;
-use common::replay::ttrm::models::events::Event;
 use tetrio_api::http::parameters::personal_user_records::{PersonalLeaderboard, PersonalRecordsQuery};
 use twilight_interactions::command::{CommandInputData, CommandModel, CreateCommand};
 
 use twilight_model::application::interaction::application_command::CommandData;
 use twilight_model::channel::message::embed::EmbedField;
+use twilight_model::channel::message::Embed;
+use twilight_model::id::{marker::GuildMarker, Id};
 use twilight_model::gateway::payload::incoming::InteractionCreate;
 use twilight_model::http::attachment::Attachment;
 
@@ -17,18 +17,40 @@ use twilight_util::builder::embed::{EmbedBuilder, ImageSource};
 use crate::context::Context;
 
 use crate::interactions::commands::subcommands::ts::ttrm_replay_sub_command::TetrioReplaySubCommand;
+use crate::utils::archetype::ArchetypeClassifier;
 use crate::utils::average_of_rank::average_of_rank;
 use crate::utils::box_commands::{CommandBox, RunnableCommand};
 use crate::utils::create_embed::create_embed;
+use crate::utils::player_source::{
+    avatar_url, discord_user_or_caller, resolve_discord_user, PlayerSource, ResolvedPlayer,
+};
+use crate::utils::replay_analysis::ReplayAnalysis;
+use crate::utils::replay_archive::{archive_replay, fetch_replay, ReplayInput, ReplayMetadata};
+use crate::utils::replay_batch::{download_replays, parse_replays, SessionStats};
+#[cfg(feature = "local_image_generation")]
+use crate::utils::stat_card::{render_card, CardPlayer, CARD_FILENAME};
 
 use crate::utils::stats::{stringified_stats, PlayerStats, StringifiedStats};
+#[cfg(feature = "database")]
+use crate::{
+    services::stat_formula::StatFormulaPDO,
+    utils::{
+        stat_formula::{FormulaVariables, StatFormula},
+        stats::calculate_stats,
+    },
+};
 
 use crate::interactions::commands::subcommands::ts::average_sub_command::AverageSubCommand;
 use crate::interactions::commands::subcommands::ts::discord_user_sub_command::DiscordUserSubCommand;
+use crate::interactions::commands::subcommands::ts::source_sub_command::SourceSubCommand;
 use crate::interactions::commands::subcommands::ts::stats_sub_command::StatsSubCommand;
 use crate::interactions::commands::subcommands::ts::tetrio_user_sub_command::TetrioUserSubCommand;
+use crate::utils::table::format_table;
 use crate::utils::timer::Timer;
 
+#[cfg(feature = "database")]
+const MAX_EMBED_FIELDS: usize = 25;
+
 #[derive(CreateCommand, CommandModel)]
 #[command(name = "ts", desc = "Calculate the tetrio stats for a user")]
 pub enum TsCommand {
@@ -49,6 +71,10 @@ pub enum TsCommand {
     #[command(name = "average")]
     /// Use average stats
     Average(AverageSubCommand),
+
+    #[command(name = "source")]
+    /// Use any player source
+    Source(SourceSubCommand),
 }
 
 impl TsCommand {
@@ -56,6 +82,8 @@ impl TsCommand {
         id: String,
         interaction: &InteractionCreate,
         show_details: bool,
+        card: bool,
+        dark_mode: bool,
         tetra_league_game: Option<i64>,
         tetra_league_round: Option<i64>,
         context: &Context<'_>,
@@ -131,7 +159,21 @@ impl TsCommand {
             String::new()
         };
 
+        let player_stats = PlayerStats {
+            apm,
+            pps,
+            vs,
+            rd: Some(rd),
+            tr,
+            glicko: Some(glicko),
+            rank: rank.clone(),
+        };
+
         let avatar_revision = data.avatar_revision.unwrap_or(0);
+        if card {
+            return Self::reply_with_card(username.clone(), avatar_url(&id.to_string(), avatar_revision), player_stats, dark_mode, interaction, context).await;
+        }
+
         let builder = create_embed(None, &context).await?
             .title(username.to_uppercase())
             .url(format!("https://ch.tetr.io/u/{username}"))
@@ -150,21 +192,16 @@ impl TsCommand {
         let builder = Self::embed_with_stats(
             builder,
             show_details,
-            PlayerStats {
-                apm,
-                pps,
-                vs,
-                rd: Some(rd),
-                tr,
-                glicko: Some(glicko),
-                rank: rank.clone(),
-            },
+            player_stats.clone(),
             None,
             None,
         )
         .await;
 
-        let embed = builder.build();
+        let mut embed = builder.build();
+        let games = (league_data.gameswon as f64, league_data.gamesplayed as f64);
+        Self::add_archetype_field(&mut embed, player_stats.clone(), context).await;
+        Self::add_formula_fields(&mut embed, interaction.guild_id, player_stats, Some(games), context).await;
 
         if avatar_revision == 0 {
             context
@@ -195,88 +232,63 @@ impl TsCommand {
         interaction: &InteractionCreate,
         context: &Context<'_>,
     ) -> anyhow::Result<anyhow::Result<()>> {
-        let attachment = &replay.replay;
+        let attachments = [&replay.replay, &replay.replay_2, &replay.replay_3, &replay.replay_4]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>();
+        // the session is only read from the uploads, the other sources would be silently ignored
+        let other_replays = [&replay.replay_2, &replay.replay_3, &replay.replay_4].iter().any(|other| other.is_some());
+        if other_replays && (replay.replay_id.is_some() || replay.tetrio_replay.is_some()) {
+            return Ok(Err(anyhow!(
+                "❌ `replay_2` to `replay_4` can't be combined with `replay_id` or `tetrio_replay`, upload every replay of the session"
+            )));
+        }
+        if attachments.len() > 1 || attachments.iter().any(|attachment| attachment.filename.ends_with(".zip")) {
+            if replay.replay_id.is_some() || replay.tetrio_replay.is_some() {
+                return Ok(Err(anyhow!(
+                    "❌ A zip of replays can't be combined with `replay_id` or `tetrio_replay`, upload every replay of the session"
+                )));
+            }
+            return Self::with_replay_session(&attachments, &replay, interaction, context).await;
+        }
+
+        let input = ReplayInput {
+            attachment: attachments.first().copied(),
+            tetrio: replay.tetrio_replay.as_deref(),
+            archive_id: replay.replay_id,
+        };
+        let uploaded = match fetch_replay(input, interaction, context).await? {
+            Ok(uploaded) => uploaded,
+            Err(err) => return Ok(Err(err)),
+        };
         // check that extension is ttrm
-        if !attachment.filename.ends_with("ttrm") {
-            return Ok(Err(anyhow!("❌ File type not supported: {:?}", attachment.filename)));
+        if !uploaded.filename.ends_with("ttrm") {
+            return Ok(Err(anyhow!("❌ File type not supported: {:?}", uploaded.filename)));
         };
 
-        let bytes = reqwest::get(&attachment.url)
-            .await?
-            .bytes()
-            .await?;
-
-        let replay_data:common::replay::ttrm::models::Root = serde_json::from_slice(&bytes)
-        .map_err(|err| anyhow!("❌ Couldn't parse ttrm replay: {:?}", err))?;
-
-
-        let username = replay.user.clone();
-        let Some(_) = replay_data.endcontext.iter().find(move |endcontext| {
-            endcontext.get_username() == Some(username.clone())
-        }) else {
-            return Ok(Err(anyhow!("❌ Couldn't find user in replay")));
+        let analysis = match ReplayAnalysis::parse(&uploaded.bytes) {
+            Ok(analysis) => analysis,
+            Err(err) => return Ok(Err(err)),
         };
+        uploaded
+            .archive(&ReplayMetadata::from_analysis(&uploaded.filename, &analysis), interaction, context)
+            .await;
 
-        let username = replay.user.clone();
-        let mut end_frames = replay_data.data.into_iter().map(|round| {
-            let username = username.clone();
-            round.replays.into_iter().filter_map(move |replayd| replayd.events.into_iter().find(|event|{
-                match event {
-                    Event::End { data, .. } => data.export.options.username == username,
-                    _ => false
-                }
-            }).and_then(|obj| {
-                match obj {
-                    Event::End { data, .. } => Some(data),
-                    _ => None
-                }
-            }))
-        }).flatten()
-        ;        
-
-
-
-        let Some((stats, tetra_league_game_str)) = ({
-            if let Some(game_number) = replay.game_number {
-            end_frames.nth(game_number as usize).and_then(|data| {
-
-            Some((Average {
-                username: replay.user.clone(),
-                apm: data.export.aggregatestats.apm,
-                pps: data.export.aggregatestats.pps,
-                vs: data.export.aggregatestats.vsscore,
-                score: 0
-            }, format!("Stats from round {}.", game_number)))
-        })
-
-        } else {
-            // get average
-            Some((end_frames.fold(Average{
-                username: replay.user.clone(),
-                apm: 0.0,
-                pps: 0.0,
-                vs: 0.0,
-                score: 0
-            }, |acc,  event| {
-                    let apm = event.export.aggregatestats.apm;
-                    let pps = event.export.aggregatestats.pps;
-                    let vs = event.export.aggregatestats.vsscore;
-                    Average {
-                        username: replay.user.clone(),
-                        apm: acc.apm + apm,
-                        pps: acc.pps + pps,
-                        vs: acc.vs + vs,
-                        score: 0
-                    }
-                }
-            ), "Stats from Average.".to_string()))
-        }
-        }) else {
-            return Ok(Err(anyhow!("❌ Couldn't find user in replay")));
+        // game_number is the round of the replay starting at 1, like the rounds of GameSelector
+        let round = replay.game_number.map(|game_number| game_number.max(1) as usize);
+        let player_stats = match analysis.player(&replay.user).and_then(|player| player.stats(round)) {
+            Ok(stats) => stats,
+            Err(err) => return Ok(Err(err)),
         };
 
+        let tetra_league_game_str = match round {
+            Some(round) => format!("Stats from round {}.", round),
+            None => "Stats from Average.".to_string(),
+        };
 
-
+        if replay.card.unwrap_or(false) {
+            return Self::reply_with_card(replay.user, None, player_stats, replay.dark_mode.unwrap_or(false), interaction, context).await;
+        }
 
         let builder = create_embed(None, &context).await?
         .title(replay.user.to_uppercase())
@@ -284,23 +296,112 @@ impl TsCommand {
         .description(format!("Takathebot - A bot attempting to copy sheetBot and but hiyajo maho but somehow does things in a better yet worse way.\n{}", tetra_league_game_str))
         ;
             
-        let embed = Self::embed_with_stats(
+        let mut embed = Self::embed_with_stats(
             builder,
             replay.show_details.unwrap_or(false),
-            PlayerStats {
-                apm: stats.apm,
-                pps: stats.pps,
-                vs: stats.vs,
-                rd: None,
-                tr: None,
-                glicko: None,
-                rank: None,
-            },
+            player_stats.clone(),
+            None,
+            None,
+        )
+        .await
+        .build();
+        Self::add_archetype_field(&mut embed, player_stats.clone(), context).await;
+        Self::add_formula_fields(&mut embed, interaction.guild_id, player_stats, None, context).await;
+
+        context
+            .http_client
+            .interaction(context.application.id)
+            .update_response(&interaction.token)
+            .embeds(Some(&[embed]))?
+            .await?;
+
+        Ok(Ok(()))
+    }
+
+    /// Stats of a player across several replays, like a whole session against the same opponent.
+    async fn with_replay_session(
+        attachments: &[&twilight_model::channel::Attachment],
+        replay: &TetrioReplaySubCommand,
+        interaction: &InteractionCreate,
+        context: &Context<'_>,
+    ) -> anyhow::Result<anyhow::Result<()>> {
+        let replays = match download_replays(attachments).await? {
+            Ok(replays) => replays,
+            Err(err) => return Ok(Err(err)),
+        };
+        let replays = {
+            let _timer = Timer::new("replay session parsing");
+            match parse_replays(replays).await? {
+                Ok(replays) => replays,
+                Err(err) => return Ok(Err(err)),
+            }
+        };
+        for batch_replay in &replays {
+            let metadata = ReplayMetadata::from_analysis(&batch_replay.filename, &batch_replay.analysis);
+            archive_replay(&batch_replay.filename, &batch_replay.bytes, &metadata, interaction, context).await;
+        }
+        let session = match SessionStats::new(&replay.user, &replays) {
+            Ok(session) => session,
+            Err(err) => return Ok(Err(err)),
+        };
+
+        let win_rate = session
+            .win_rate()
+            .map(|win_rate| format!("{:.2}%", win_rate * 100.0))
+            .unwrap_or_else(|| "unknown".to_string());
+        let summary = format!(
+            "Stats from {} games and {} rounds, averaged over every round.\nWin rate: {win_rate}\nTrend per game: {:+.2} APM, {:+.3} PPS, {:+.2} VS",
+            session.games.len(),
+            session.rounds(),
+            session.trend(|stats| stats.apm),
+            session.trend(|stats| stats.pps),
+            session.trend(|stats| stats.vs),
+        );
+
+        let mut rows = vec![["Game", "Rounds", "Won", "APM", "PPS", "VS"].map(String::from).to_vec()];
+        rows.extend(session.games.iter().enumerate().map(|(i, game)| {
+            vec![
+                (i + 1).to_string(),
+                game.rounds.to_string(),
+                match game.won {
+                    Some(true) => format!("{} W", game.rounds_won),
+                    Some(false) => format!("{} L", game.rounds_won),
+                    None => "?".to_string(),
+                },
+                format!("{:.2}", game.stats.apm),
+                format!("{:.2}", game.stats.pps),
+                format!("{:.2}", game.stats.vs),
+            ]
+        }));
+        rows.push(vec![
+            "Avg".to_string(),
+            String::new(),
+            String::new(),
+            format!("{:.2}", session.per_game.apm),
+            format!("{:.2}", session.per_game.pps),
+            format!("{:.2}", session.per_game.vs),
+        ]);
+
+        let builder = create_embed(None, context).await?
+            .title(session.username.to_uppercase())
+            .url(format!("https://ch.tetr.io/u/{}", session.username))
+            .description(summary);
+
+        let mut embed = Self::embed_with_stats(
+            builder,
+            replay.show_details.unwrap_or(false),
+            session.overall.clone(),
             None,
             None,
         )
         .await
+        .field(EmbedField {
+            inline: false,
+            name: "Games".to_string(),
+            value: format!("```\n{}\n```", format_table(&rows)),
+        })
         .build();
+        Self::add_archetype_field(&mut embed, session.overall.clone(), context).await;
 
         context
             .http_client
@@ -312,6 +413,80 @@ impl TsCommand {
         Ok(Ok(()))
     }
 
+    pub async fn with_source(
+        source: SourceSubCommand,
+        interaction: &InteractionCreate,
+        context: &Context<'_>,
+    ) -> anyhow::Result<anyhow::Result<()>> {
+        let show_details = source.show_details.unwrap_or(false);
+        let card = source.card.unwrap_or(false);
+        let dark_mode = source.dark_mode.unwrap_or(false);
+        let source = match PlayerSource::parse(&source.source) {
+            Ok(source) => source,
+            Err(err) => return Ok(Err(err)),
+        };
+
+        // tetrio users keep their profile embed, with their avatar and rank
+        let (user, selector) = match source {
+            PlayerSource::Tetrio { user, selector } => (user, selector),
+            PlayerSource::Discord { id, selector } => match resolve_discord_user(id, context).await? {
+                Ok(user) => (user, selector),
+                Err(err) => return Ok(Err(err)),
+            },
+            source => {
+                let player = match source.resolve(context).await? {
+                    Ok(player) => player,
+                    Err(err) => return Ok(Err(err)),
+                };
+
+                if card {
+                    return Self::reply_with_card(player.name, player.avatar_url, player.stats, dark_mode, interaction, context).await;
+                }
+
+                let description = match &player.replay_url {
+                    Some(url) => format!("Takathebot - A bot attempting to copy sheetBot and but hiyajo maho but somehow does things in a better yet worse way.\n[Replay]({url})"),
+                    None => "Takathebot - A bot attempting to copy sheetBot and but hiyajo maho but somehow does things in a better yet worse way.".to_string(),
+                };
+                let builder = create_embed(None, &context).await?
+                    .title(player.name.to_uppercase())
+                    .description(description);
+
+                let mut embed = Self::embed_with_stats(
+                    builder,
+                    show_details,
+                    player.stats.clone(),
+                    None,
+                    None,
+                )
+                .await
+                .build();
+                Self::add_archetype_field(&mut embed, player.stats.clone(), context).await;
+                Self::add_formula_fields(&mut embed, interaction.guild_id, player.stats, None, context).await;
+
+                context
+                    .http_client
+                    .interaction(context.application.id)
+                    .update_response(&interaction.token)
+                    .embeds(Some(&[embed]))?
+                    .await?;
+
+                return Ok(Ok(()));
+            }
+        };
+
+        Self::with_user(
+            user,
+            interaction,
+            show_details,
+            card,
+            dark_mode,
+            selector.game.map(|game| game as i64),
+            selector.round.map(|round| round as i64),
+            context,
+        )
+        .await
+    }
+
     pub async fn with_stats(
         stats: StatsSubCommand,
         interaction: &InteractionCreate,
@@ -322,23 +497,31 @@ impl TsCommand {
             .description("Takathebot - A bot attempting to copy sheetBot and but hiyajo maho but somehow does things in a better yet worse way.")
             ;
 
-        let embed = Self::embed_with_stats(
+        let player_stats = PlayerStats {
+            apm: stats.apm,
+            pps: stats.pps,
+            vs: stats.vs,
+            rd: None,
+            tr: None,
+            glicko: None,
+            rank: None,
+        };
+
+        if stats.card.unwrap_or(false) {
+            return Self::reply_with_card("Stats".to_string(), None, player_stats, stats.dark_mode.unwrap_or(false), interaction, context).await;
+        }
+
+        let mut embed = Self::embed_with_stats(
             builder,
             stats.show_details.unwrap_or(false),
-            PlayerStats {
-                apm: stats.apm,
-                pps: stats.pps,
-                vs: stats.vs,
-                rd: None,
-                tr: None,
-                glicko: None,
-                rank: None,
-            },
+            player_stats.clone(),
             None,
             None,
         )
         .await
         .build();
+        Self::add_archetype_field(&mut embed, player_stats.clone(), context).await;
+        Self::add_formula_fields(&mut embed, interaction.guild_id, player_stats, None, context).await;
 
         context
             .http_client
@@ -389,23 +572,31 @@ impl TsCommand {
             builder.thumbnail(ImageSource::url("https://tetr.io/res/logo.png")?)
         };
 
-        let embed = Self::embed_with_stats(
+        let player_stats = PlayerStats {
+            apm: avg.apm,
+            pps: avg.pps,
+            vs: avg.vs,
+            rd: Some(avg.rd),
+            tr: Some(avg.tr),
+            glicko: Some(avg.glicko),
+            rank: avg.rank,
+        };
+
+        if average.card.unwrap_or(false) {
+            return Self::reply_with_card(format!("Average of {rank}"), None, player_stats, average.dark_mode.unwrap_or(false), interaction, context).await;
+        }
+
+        let mut embed = Self::embed_with_stats(
             builder,
             average.details.unwrap_or(false),
-            PlayerStats {
-                apm: avg.apm,
-                pps: avg.pps,
-                vs: avg.vs,
-                rd: Some(avg.rd),
-                tr: Some(avg.tr),
-                glicko: Some(avg.glicko),
-                rank: avg.rank,
-            },
+            player_stats.clone(),
             Some(lowest),
             Some(count),
         )
         .await
         .build();
+        Self::add_archetype_field(&mut embed, player_stats.clone(), context).await;
+        Self::add_formula_fields(&mut embed, interaction.guild_id, player_stats, None, context).await;
 
         context
             .http_client
@@ -417,6 +608,107 @@ impl TsCommand {
         Ok(Ok(()))
     }
 
+    /// Replies with a rendered stat card instead of the embed.
+    pub async fn reply_with_card(
+        name: String,
+        avatar_url: Option<String>,
+        stats: PlayerStats,
+        dark_mode: bool,
+        interaction: &InteractionCreate,
+        context: &Context<'_>,
+    ) -> anyhow::Result<anyhow::Result<()>> {
+        #[cfg(feature = "local_image_generation")]
+        {
+            let player = CardPlayer::fetch(name, avatar_url, stats).await;
+            let card = render_card(&player, dark_mode)?;
+
+            context
+                .http_client
+                .interaction(context.application.id)
+                .update_response(&interaction.token)
+                .attachments(&[Attachment::from_bytes(CARD_FILENAME.to_string(), card, 1)])?
+                .await?;
+
+            Ok(Ok(()))
+        }
+        #[cfg(not(feature = "local_image_generation"))]
+        {
+            let _ = (name, avatar_url, stats, dark_mode, interaction, context);
+            Ok(Err(anyhow!("❌ Stat cards are not available on this bot")))
+        }
+    }
+
+    /// Plain embed with the stats of a player, used when an image can't be generated.
+    pub async fn player_embed(
+        player: ResolvedPlayer,
+        description: String,
+        context: &Context<'_>,
+    ) -> anyhow::Result<Embed> {
+        let builder = create_embed(None, &context).await?
+            .title(player.name.to_uppercase())
+            .description(description);
+
+        let builder = match &player.avatar_url {
+            Some(url) => builder.thumbnail(ImageSource::url(url)?),
+            None => builder,
+        };
+
+        Ok(Self::embed_with_stats(builder, false, player.stats, None, None).await.build())
+    }
+
+    /// Adds the archetype of the player and its strengths and weaknesses.
+    pub async fn add_archetype_field(embed: &mut Embed, stats: PlayerStats, context: &Context<'_>) {
+        let classifier = ArchetypeClassifier::fetch(context).await;
+        if let Some(archetype) = classifier.classify_player(stats) {
+            embed.fields.push(EmbedField {
+                inline: false,
+                name: format!("Archetype: {archetype}"),
+                value: format!("➕ {}\n➖ {}", archetype.strengths(), archetype.weaknesses()),
+            });
+        }
+    }
+
+    /// Adds the formulas saved in the guild as extra fields, within the limit of fields of an embed.
+    /// `games` holds the tetra league games won and played, when known.
+    pub async fn add_formula_fields(
+        embed: &mut Embed,
+        guild_id: Option<Id<GuildMarker>>,
+        stats: PlayerStats,
+        games: Option<(f64, f64)>,
+        context: &Context<'_>,
+    ) {
+        #[cfg(feature = "database")]
+        if let Some(guild_id) = guild_id {
+            let formulas = StatFormulaPDO::fetch_formulas(context, guild_id.get()).await;
+            let variables = FormulaVariables::from_stats(&calculate_stats(stats));
+            let variables = match games {
+                Some((won, played)) => variables.with_games(won, played),
+                None => variables,
+            };
+            let remaining = MAX_EMBED_FIELDS.saturating_sub(embed.fields.len());
+
+            embed.fields.extend(
+                formulas
+                    .into_iter()
+                    .filter_map(|formula| {
+                        let value = StatFormula::parse(&formula.expression)
+                            .ok()?
+                            .evaluate(&variables)
+                            .map(|value| format!("{value:.4}"))
+                            .unwrap_or("N/A".to_string());
+                        Some(EmbedField {
+                            inline: true,
+                            name: formula.name,
+                            value,
+                        })
+                    })
+                    .take(remaining),
+            );
+        }
+        #[cfg(not(feature = "database"))]
+        let _ = (embed, guild_id, stats, games, context);
+    }
+
     pub async fn embed_with_stats(
         embed: EmbedBuilder,
         show_details: bool,
@@ -699,30 +991,34 @@ impl RunnableCommand for TsCommand {
 
         match model {
             TsCommand::Discord(discord) => {
-                let packet = context
-                    .tetrio_client
-                    .search_discord_user(&discord.user.resolved.id.to_string())
-                    .await?;
+                let id = match discord_user_or_caller(discord.user.as_ref(), interaction) {
+                    Ok(id) => id,
+                    Err(err) => return Ok(Err(err)),
+                };
+                let user = match resolve_discord_user(id, context).await? {
+                    Ok(user) => user,
+                    Err(err) => return Ok(Err(err)),
+                };
 
-                if let Some(data) = &packet.data {
-                    Self::with_user(
-                        data.user.id.to_string(),
-                        interaction,
-                        discord.details.unwrap_or(false),
-                        discord.tetra_league_game,
-                        discord.tetra_league_round,
-                        &context,
-                    )
-                    .await
-                } else {
-                    return Ok(Err(anyhow!("❌ Couldn't find your tetrio id from the discord account, they might have not linked it publicly to their tetrio profile")));
-                }
+                Self::with_user(
+                    user,
+                    interaction,
+                    discord.details.unwrap_or(false),
+                    discord.card.unwrap_or(false),
+                    discord.dark_mode.unwrap_or(false),
+                    discord.tetra_league_game,
+                    discord.tetra_league_round,
+                    &context,
+                )
+                .await
             }
             TsCommand::Tetrio(tetrio) => {
                 Self::with_user(
                     tetrio.tetrio_user,
                     interaction,
                     tetrio.details.unwrap_or(false),
+                    tetrio.card.unwrap_or(false),
+                    tetrio.dark_mode.unwrap_or(false),
                     tetrio.tetra_league_game,
                     tetrio.tetra_league_round,
                     &context,
@@ -738,6 +1034,9 @@ impl RunnableCommand for TsCommand {
             TsCommand::Replay(replay) => {
                 Self::with_replay(replay, interaction, &context).await
             }
+            TsCommand::Source(source) => {
+                Self::with_source(source, interaction, &context).await
+            }
         }
     }
 }
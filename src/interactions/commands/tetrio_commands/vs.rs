@@ -1,10 +1,6 @@
 use std::borrow::Cow;
 
-use anyhow::anyhow;
 use async_trait::async_trait;
-use itertools::Itertools;
-use serde_json::json;
-use tetrio_api::{http::parameters::personal_user_records::{PersonalLeaderboard, PersonalRecordsQuery}, models::users::user_rank::UserRank};
 use twilight_interactions::command::{CommandInputData, CommandModel, CreateCommand};
 use twilight_model::{
     application::interaction::application_command::CommandData,
@@ -14,8 +10,9 @@ use twilight_model::{
 use crate::{
     context::Context,
     utils::{
-        average_of_rank::average_of_rank,
         box_commands::RunnableCommand,
+        charts::{Chart, ChartBackend, ChartDataset, ChartKind, RenderedChart},
+        player_source::{source_or_caller, PlayerSource, ResolvedPlayer},
         stats::{
             calculate_stats, PlayerStats, APM_WEIGHT, APP_WEIGHT, CHEESE_WEIGHT, DSAPPPIECE_WEIGHT,
             DSPIECE_WEIGHT, DSSECOND_WEIGHT, GARBAGEEFFI_WEIGHT, PPS_WEIGHT, VSAPM_WEIGHT,
@@ -28,265 +25,154 @@ use crate::{
 #[derive(CommandModel, CreateCommand)]
 #[command(name = "vs", desc = "Get a graph of player stats")]
 pub struct VsCommand {
-    /// A tetrio user, (pps, apm, vs), discord ping, $avgX where X is a rank, e.g S+ or $avgX:COUNTRY_CODE
-    pub user_1: String,
     /// Get a dark mode chart
     pub dark_mode: bool,
+    /// A tetrio user, (pps, apm, vs), discord ping, $avgX where X is a rank, e.g S+ or $avgX:COUNTRY_CODE, defaults to you
+    pub user_1: Option<String>,
     /// A tetrio user, (pps, apm, vs), discord ping $avgX where X is a rank, e.g S+ or $avgX:COUNTRY_CODE
     pub user_2: Option<String>,
+    /// A tetrio user, (pps, apm, vs), discord ping $avgX where X is a rank, e.g S+ or $avgX:COUNTRY_CODE
+    pub user_3: Option<String>,
+    /// A tetrio user, (pps, apm, vs), discord ping $avgX where X is a rank, e.g S+ or $avgX:COUNTRY_CODE
+    pub user_4: Option<String>,
+    /// A tetrio user, (pps, apm, vs), discord ping $avgX where X is a rank, e.g S+ or $avgX:COUNTRY_CODE
+    pub user_5: Option<String>,
+    /// A tetrio user, (pps, apm, vs), discord ping $avgX where X is a rank, e.g S+ or $avgX:COUNTRY_CODE
+    pub user_6: Option<String>,
 }
 
+/// How many players can be compared at once by vs, vsr and vst
+pub const MAX_COMPARED_USERS: usize = 6;
+
 impl VsCommand {
-    fn parse_average_rank(rank: &str) -> anyhow::Result<Option<UserRank>> {
-        let (_, rank) = match rank.split_once("$avg") {
-            Some(data) => data,
-            None => unreachable!(),
+    /// Resolves a player following the grammar of [`PlayerSource`]
+    pub async fn resolve_user(
+        user: String,
+        context: &Context<'_>,
+    ) -> anyhow::Result<anyhow::Result<ResolvedPlayer>> {
+        let source = match PlayerSource::parse(&user) {
+            Ok(source) => source,
+            Err(err) => return Ok(Err(err)),
         };
 
-        if rank.is_empty() {
-            return Ok(None);
-        }
-
-        match serde_json::from_str::<UserRank>(&format!("\"{}\"", rank.to_lowercase())) {
-            Ok(ok) => Ok(Some(ok)),
-            Err(err) => Err(anyhow!("❌ Couldn't find rank in {rank} because {err}")),
-        }
+        source.resolve(context).await
     }
 
     pub async fn parse_user(
         user: String,
         context: &Context<'_>,
     ) -> anyhow::Result<anyhow::Result<(String, PlayerStats)>> {
-        let user = user.trim();
-        if user.starts_with("$avg") {
-            if user.contains(':') {
-                let (left, right) = match user.split_once(':') {
-                    Some(data) => data,
-                    None => unreachable!(),
-                };
-
-                let rank = match Self::parse_average_rank(left) {
-                    Ok(ok) => ok,
-                    Err(err) => return Ok(Err(err)),
-                };
-                let country = right;
-                let stats =
-                    average_of_rank(rank.clone(), context).await;
-
-                let rank_str = format!(
-                    "$avg{}:{country}",
-                    rank.map(|r| r.to_string()).unwrap_or("".to_string())
-                );
-
-                stats.map(|result| result.map(|stats| (rank_str, stats.0.into())))
-            } else {
-                let rank = match Self::parse_average_rank(user) {
-                    Ok(ok) => ok,
-                    Err(err) => return Ok(Err(err)),
-                };
-
-                let stats = average_of_rank(rank.clone(), context).await;
-
-                let rank_str = format!(
-                    "$avg{}",
-                    rank.map(|r| r.to_string()).unwrap_or("".to_string())
-                );
-
-                stats.map(|result| result.map(|stats| (rank_str, stats.0.into())))
-            }
-        } else if user.starts_with("<@") {
-            let str = user.split(':').collect_vec();
-            let user = str[0].trim();
-            let user = {
-                let user = user.chars().skip(2).collect::<String>();
-                let user = user.chars().rev().skip(1).collect::<String>();
-                user.chars().rev().collect::<String>()
-            };
+        Ok(Self::resolve_user(user, context)
+            .await?
+            .map(|player| (player.name, player.stats)))
+    }
 
-            let user_id: u64 = match user.parse() {
+    /// Resolves every source concurrently, keeping the order they were given in
+    pub async fn resolve_users(
+        users: Vec<String>,
+        context: &Context<'_>,
+    ) -> anyhow::Result<anyhow::Result<Vec<ResolvedPlayer>>> {
+        let result = users
+            .into_iter()
+            .take(MAX_COMPARED_USERS)
+            .map(|user| Self::resolve_user(user, context));
+
+        let mut resolved = vec![];
+        for result in futures::future::join_all(result).await {
+            resolved.push(match result? {
                 Ok(ok) => ok,
-                Err(_) => return Ok(Err(anyhow!("❌ Couldn't find discord user <@{user}>"))),
-            };
-
-            let discord_user = context
-                .tetrio_client
-                .search_discord_user(&user_id.to_string())
-                .await?;
-
-            let data = match &discord_user.data {
-                Some(data) => data,
-                None => return Ok(Err(anyhow!("❌ Couldn't find tetrio user linked to discord user `<@{user}>`, they might have not linked their discord account to their tetrio account"))),
-            };
-
-            Self::parse_tetrio_user(&data.user.username, str, context).await
-        } else {
-            let strs = user.split(',');
-
-            if strs.clone().count() == 3 {
-                let Some((pps, apm, vs)) = strs.collect_tuple() else {
-                    return Ok(Err(anyhow!("❌ Couldn't parse stats {user}")));
-                };
-
-                let pps = if pps.starts_with('(') {
-                    pps.chars().skip(1).collect()
-                } else {
-                    pps.to_string()
-                };
-
-                let vs = if vs.ends_with('(') {
-                    let vs = vs.chars().rev().skip(1).collect::<String>();
-                    vs.chars().rev().collect()
-                } else {
-                    vs.to_string()
-                };
-
-                let (pps, apm, vs) = (pps.trim(), apm.trim(), vs.trim());
-
-                let name = format!("{pps},{apm},{vs}");
-
-                let (pps, apm, vs): (f64, f64, f64) = (pps.parse()?, apm.parse()?, vs.parse()?);
-
-                return Ok(Ok((
-                    name,
-                    PlayerStats {
-                        apm,
-                        pps,
-                        vs,
-                        rd: None,
-                        tr: None,
-                        glicko: None,
-                        rank: None,
-                    },
-                )));
-            } else {
-                let str = user.split(':').collect_vec();
-
-                Self::parse_tetrio_user(str[0], str, context).await
-            }
+                Err(err) => return Ok(Err(err)),
+            });
         }
+
+        Ok(Ok(resolved))
     }
 
-    async fn parse_tetrio_user(
-        user_name: &str,
-        params: Vec<&str>,
+    pub async fn parse_users(
+        users: Vec<String>,
         context: &Context<'_>,
-    ) -> anyhow::Result<anyhow::Result<(String, PlayerStats)>> {
-        let user = context
-            .tetrio_client
-            .fetch_user_info(&user_name.to_lowercase())
-            .await?;
-        match &user.error {
-            Some(err) => {
-                return Ok(Err(anyhow!(
-                    "❌ Couldn't find user data for {user_name} because {}"
-                , err.msg)))
-            }
-            None => {}
-        };
-
-        let data = match &user.data {
-            Some(data) => data,
-            None => return Ok(Err(anyhow!("❌ Couldn't find user data for {user_name}"))),
-        };
-        let id = &data.id;
-
-        let tetrio_league_summary = context.tetrio_client.fetch_user_league_summaries(&id).await?;
-
-        let Some(league_data) = &tetrio_league_summary.data else {
-            return Ok(Err(anyhow::anyhow!("❌ No data has been found. User might be anonymous or banned.")));
-        };
-
-        let (Some(mut pps), Some(mut apm), Some(mut vs)) = (
-            league_data.pps,
-            league_data.apm,
-            league_data.vs,
-        ) else {
-            return Ok(Err(anyhow!(
-                "❌ {user_name} doesn't have a valid tetra league record "
-            )));
-        };
-
-        let league_str = 
-        if let Some(Ok(mut tetra_league_game)) =
-            params.get(1).map(|s| str::parse::<usize>(s))
-        {            if tetra_league_game <= 0 {
-            tetra_league_game = 1;
-        }
+    ) -> anyhow::Result<anyhow::Result<Vec<(String, PlayerStats)>>> {
+        Ok(Self::resolve_users(users, context).await?.map(|players| {
+            players
+                .into_iter()
+                .map(|player| (player.name, player.stats))
+                .collect()
+        }))
+    }
 
-        let game = context.tetrio_client.fetch_user_personal_league_records(id, PersonalLeaderboard::Recent, PersonalRecordsQuery::None).await?;
-        let Some(data) = game.data else {
-            return Ok(Err(anyhow::anyhow!("❌ Couldn't find tetra league game")));
-        };
-        let records = data.entries.get((tetra_league_game - 1) as usize);
-        let Some(records) = &records else {
-            return Err(anyhow::anyhow!("❌ Couldn't find tetra league game"));
+    pub async fn graph_with_stats(
+        users: Vec<(String, PlayerStats)>,
+        dark_mode: bool,
+        backend: ChartBackend,
+    ) -> anyhow::Result<RenderedChart> {
+        let background_colors = Self::get_background_colors(dark_mode);
+        let datasets = {
+            let _timer2 = Timer::new("vs calculating stats");
+            users
+                .into_iter()
+                .enumerate()
+                .map(|(i, v)| (i, v.0, calculate_stats(v.1)))
+                .map(|(i, label, v)| ChartDataset {
+                    label,
+                    data: vec![
+                        v.apm * APM_WEIGHT,
+                        v.pps * PPS_WEIGHT,
+                        v.vs * VS_WEIGHT,
+                        v.app * APP_WEIGHT,
+                        v.dssecond * DSSECOND_WEIGHT,
+                        v.dspiece * DSPIECE_WEIGHT,
+                        v.dsapppiece * DSAPPPIECE_WEIGHT,
+                        v.vsapm * VSAPM_WEIGHT,
+                        v.cheese * CHEESE_WEIGHT,
+                        v.garbage_effi * GARBAGEEFFI_WEIGHT,
+                    ],
+                    color: background_colors[i % MAX_COMPARED_USERS],
+                })
+                .collect::<Vec<_>>()
         };
 
+        let _timer = Timer::new("vs generating graph");
 
-
-        if let Some(Ok(mut tetra_league_round)) = params.get(2).map(|s| str::parse::<usize>(s)) {
-            if tetra_league_round <= 0 {
-                tetra_league_round = 1;
-            }
-
-            let Some(round) = records.results.rounds.get((tetra_league_round - 1) as usize) else {
-                return Err(anyhow::anyhow!("❌ Invalid round!"));
-            };
-
-            let Some(round) = round.iter().find(|user| &user.id == id) else {
-                return Err(anyhow::anyhow!("❌ Couldn't find stats!"));
-            };
-
-            pps = round.stats.pps;
-            apm = round.stats.apm;
-            vs  = round.stats.vsscore;
-
-            format!(":{}:{}", tetra_league_game, tetra_league_round)
-
-        } else {
-            let Some(left) = records.results.leaderboard.iter().find(|user| &user.id == id) else {
-                return Err(anyhow::anyhow!("❌ Couldn't find tetra league game"));
-            };
-            pps = left.stats.pps.unwrap_or(0.0);
-            apm = left.stats.apm.unwrap_or(0.0);
-            vs  = left.stats.vsscore.unwrap_or(0.0);
-
-            format!(":{}", tetra_league_game)
-
+        Chart {
+            kind: ChartKind::Radar,
+            labels: Self::chart_labels(),
+            datasets,
+            min: 0.0,
+            max: 180.0,
+            dark_mode,
         }
-        } else {
-            String::new()
-        };
-
-        Ok(Ok((
-            format!("{}{}", user_name, league_str),
-            PlayerStats {
-                apm,
-                glicko: league_data.glicko,
-                pps,
-                vs,
-                rd: league_data.rd,
-                tr: league_data.tr,
-                rank: league_data.rank.clone(),
-            },
-        )))
+        .render(backend)
+        .await
     }
 
-    pub fn get_background_colors(dark_mode: bool) -> [&'static str; 2] {
-        if dark_mode {
-            ["rgba(254,190,9,0.7)", "rgba(123,124,132,0.7)"]
-        } else {
-            ["rgba(132,92,248,0.7)", "rgba(123,124,132,0.7)"]
-        }
+    pub fn chart_labels() -> Vec<String> {
+        ["APM", "PPS", "VS", "APP", "DS/Second", "DS/Piece", "APP+DS/Piece", "VS/APM", "Cheese\nIndex", "Garbage\nEffi."]
+            .map(String::from)
+            .to_vec()
     }
 
-    pub fn get_font_color(dark_mode: bool) -> &'static str {
+    pub fn get_background_colors(dark_mode: bool) -> [&'static str; MAX_COMPARED_USERS] {
         if dark_mode {
-            "#F5F5F5"
+            [
+                "rgba(254,190,9,0.7)",
+                "rgba(123,124,132,0.7)",
+                "rgba(0,200,180,0.6)",
+                "rgba(240,80,110,0.6)",
+                "rgba(90,160,255,0.6)",
+                "rgba(160,230,90,0.6)",
+            ]
         } else {
-            "#000000"
+            [
+                "rgba(132,92,248,0.7)",
+                "rgba(123,124,132,0.7)",
+                "rgba(0,150,136,0.6)",
+                "rgba(220,50,90,0.6)",
+                "rgba(30,110,230,0.6)",
+                "rgba(110,180,40,0.6)",
+            ]
         }
     }
+
 }
 
 #[async_trait]
@@ -307,111 +193,33 @@ impl RunnableCommand for VsCommand {
                 resolved: data.resolved.map(Cow::Owned),
             })?;
 
-            let result = vec![Some(model.user_1), model.user_2]
-                .into_iter()
-                .filter_map(|c| {
-                    c.map(|c| async { Self::parse_user(c, &context).await })
-                })
-                .rev()
-                .collect::<Vec<_>>();
-            let mut result = futures::future::join_all(result).await;
-
-            let mut new_vec = vec![];
-
-            loop {
-                let r = match result.pop() {
-                    Some(r) => r,
-                    None => break,
-                };
-
-                new_vec.push(match r? {
-                    Ok(ok) => ok,
-                    Err(err) => return Ok(Err(err)),
-                })
-            }
+            let user_1 = match source_or_caller(model.user_1, interaction) {
+                Ok(user) => user,
+                Err(err) => return Ok(Err(err)),
+            };
+            let users = [
+                Some(user_1),
+                model.user_2,
+                model.user_3,
+                model.user_4,
+                model.user_5,
+                model.user_6,
+            ]
+            .into_iter()
+            .flatten()
+            .collect();
+
+            let new_vec = match Self::parse_users(users, context).await? {
+                Ok(users) => users,
+                Err(err) => return Ok(Err(err)),
+            };
 
             (model.dark_mode, new_vec)
         };
 
-        let response = {
-            let background_colors = Self::get_background_colors(dark_mode);
-            let datasets = {
-                let _timer2 = Timer::new("vs calculating stats");
-                let datasets = new_vec
-                    .into_iter()
-                    .enumerate()
-                    .map(|(i, v)| (i, v.0, calculate_stats(v.1)))
-                    .map(|(i, label, v)| {
-                        json!({
-                            "label": label,
-                            "data": [
-                                v.apm * APM_WEIGHT,
-                                v.pps * PPS_WEIGHT,
-                                v.vs * VS_WEIGHT,
-                                v.app * APP_WEIGHT,
-                                v.dssecond * DSSECOND_WEIGHT,
-                                v.dspiece * DSPIECE_WEIGHT,
-                                v.dsapppiece * DSAPPPIECE_WEIGHT,
-                                v.vsapm * VSAPM_WEIGHT,
-                                v.cheese * CHEESE_WEIGHT,
-                                v.garbage_effi * GARBAGEEFFI_WEIGHT,
-                            ],
-                            "backgroundColor": background_colors[i],
-                            "borderColor": background_colors[i],
-                            "borderWidth": 0,
-                            "pointRadius": 0
-                        })
-                    })
-                    .collect::<Vec<_>>();
-                datasets
-            };
-
-            let _timer = Timer::new("vs generating graph");
-
-            let json = json!({
-                "type": "radar",
-                "data": {
-                    "labels": ["APM", "PPS", "VS", "APP", "DS/Second", "DS/Piece", "APP+DS/Piece", "VS/APM", "Cheese\nIndex", "Garbage\nEffi."],
-                    "datasets": datasets
-                },
-                "options":{"legend": { "labels": { "fontColor": Self::get_font_color(dark_mode), "fontSize": 16}}, "scale":{"pointLabels":{"fontColor":Self::get_font_color(dark_mode), "fontSize": 16},"rAxis":{"ticks":{"display":false}},"ticks":{"min":0,"max":180,"stepSize":"30","fontColor":"blue","display":false},"gridLines":{"color":"gray"},"angleLines":{"color":"gray"}}}
-            });
-
-            let json = json!({
-                "width": 500,
-                "height": 300,
-                "format": "webp",
-                "background": "transparent",
-                "version": 2,
-                "chart": json
-            });
-
-            log::debug!("{json}");
-
-            let response = reqwest::Client::builder()
-                .build()?
-                .post("https://quickchart.io/chart/create")
-                .header("Content-Type", "application/json")
-                .body(json.to_string())
-                .send()
-                .await?
-                .json::<serde_json::Value>()
-                .await?;
-
-            response
-        };
+        let chart = Self::graph_with_stats(new_vec, dark_mode, context.chart_backend).await?;
 
-        let interaction_client = context.http_client.interaction(context.application.id);
-        interaction_client
-            .update_response(&interaction.token)
-            .content(Some(
-                response
-                    .get("url")
-                    .ok_or(anyhow!("Couldn't find graph url"))?
-                    .as_str()
-                    .ok_or(anyhow!("Couldn't find graph url"))?,
-            ))?
-            .await?;
+        chart.send("", interaction, context).await?;
 
         Ok(Ok(()))
     }
@@ -1,8 +1,6 @@
 use std::borrow::Cow;
 
-use anyhow::anyhow;
 use async_trait::async_trait;
-use serde_json::json;
 use twilight_interactions::command::{CommandInputData, CommandModel, CreateCommand};
 use twilight_model::{
     application::interaction::application_command::CommandData,
@@ -11,9 +9,11 @@ use twilight_model::{
 
 use crate::{
     context::Context,
-    interactions::commands::tetrio_commands::vs::VsCommand,
+    interactions::commands::tetrio_commands::vs::{VsCommand, MAX_COMPARED_USERS},
     utils::{
         box_commands::RunnableCommand,
+        charts::{Chart, ChartDataset, ChartKind},
+        player_source::source_or_caller,
         stats::{
             calculate_stats, APM_WEIGHT, APP_WEIGHT, CHEESE_WEIGHT, DSAPPPIECE_WEIGHT,
             DSPIECE_WEIGHT, DSSECOND_WEIGHT, GARBAGEEFFI_WEIGHT, PPS_WEIGHT, VSAPM_WEIGHT,
@@ -29,30 +29,20 @@ use crate::{
     desc = "Get a graph of player stats relative to the highest stat"
 )]
 pub struct VsrCommand {
-    /// A tetrio user, (pps, apm, vs), discord ping, $avgX where X is a rank, e.g S+ or $avgX:COUNTRY_CODE
-    pub user_1: String,
     /// Get a dark mode chart
     pub dark_mode: bool,
+    /// A tetrio user, (pps, apm, vs), discord ping, $avgX where X is a rank, e.g S+ or $avgX:COUNTRY_CODE, defaults to you
+    pub user_1: Option<String>,
     /// A tetrio user, (pps, apm, vs), discord ping $avgX where X is a rank, e.g S+ or $avgX:COUNTRY_CODE
     pub user_2: Option<String>,
-}
-
-impl VsrCommand {
-    pub fn get_background_colors(dark_mode: bool) -> [&'static str; 2] {
-        if dark_mode {
-            ["rgba(254,190,9,0.7)", "rgba(123,124,132,0.7)"]
-        } else {
-            ["rgba(132,92,248,0.7)", "rgba(123,124,132,0.7)"]
-        }
-    }
-
-    pub fn get_font_color(dark_mode: bool) -> &'static str {
-        if dark_mode {
-            "#F5F5F5"
-        } else {
-            "#000000"
-        }
-    }
+    /// A tetrio user, (pps, apm, vs), discord ping $avgX where X is a rank, e.g S+ or $avgX:COUNTRY_CODE
+    pub user_3: Option<String>,
+    /// A tetrio user, (pps, apm, vs), discord ping $avgX where X is a rank, e.g S+ or $avgX:COUNTRY_CODE
+    pub user_4: Option<String>,
+    /// A tetrio user, (pps, apm, vs), discord ping $avgX where X is a rank, e.g S+ or $avgX:COUNTRY_CODE
+    pub user_5: Option<String>,
+    /// A tetrio user, (pps, apm, vs), discord ping $avgX where X is a rank, e.g S+ or $avgX:COUNTRY_CODE
+    pub user_6: Option<String>,
 }
 
 #[async_trait]
@@ -73,29 +63,27 @@ impl RunnableCommand for VsrCommand {
                 resolved: data.resolved.map(Cow::Owned),
             })?;
 
-            let result = vec![Some(model.user_1), model.user_2]
-                .into_iter()
-                .filter_map(|c| {
-                    c.map(|c| async { VsCommand::parse_user(c, &context).await })
-                })
-                .rev()
-                .collect::<Vec<_>>();
-            let mut result = futures::future::join_all(result).await;
-
-            let mut new_vec = vec![];
-
-            loop {
-                let r = match result.pop() {
-                    Some(r) => r,
-                    None => break,
-                };
-
-                new_vec.push(match r? {
-                    Ok(ok) => ok,
-                    Err(err) => return Ok(Err(err)),
-                })
-            }
-            let background_colors = Self::get_background_colors(model.dark_mode);
+            let user_1 = match source_or_caller(model.user_1, interaction) {
+                Ok(user) => user,
+                Err(err) => return Ok(Err(err)),
+            };
+            let users = [
+                Some(user_1),
+                model.user_2,
+                model.user_3,
+                model.user_4,
+                model.user_5,
+                model.user_6,
+            ]
+            .into_iter()
+            .flatten()
+            .collect();
+
+            let new_vec = match VsCommand::parse_users(users, context).await? {
+                Ok(users) => users,
+                Err(err) => return Ok(Err(err)),
+            };
+            let background_colors = VsCommand::get_background_colors(model.dark_mode);
             (model.dark_mode, background_colors, new_vec)
         };
 
@@ -131,78 +119,41 @@ impl RunnableCommand for VsrCommand {
             (background_colors, data, max_stat)
         };
 
-        let response = {
+        let chart = {
             let _timer = Timer::new("vsr generating graph");
             let datasets = data
                 .into_iter()
-                .map(|(i, label, v)| {
-                    json!({
-                        "label": label,
-                        "data": [
-                            v.apm * APM_WEIGHT,
-                            v.pps * PPS_WEIGHT,
-                            v.vs * VS_WEIGHT,
-                            v.app * APP_WEIGHT,
-                            v.dssecond * DSSECOND_WEIGHT,
-                            v.dspiece * DSPIECE_WEIGHT,
-                            v.dsapppiece * DSAPPPIECE_WEIGHT,
-                            ((v.vsapm - 2.0).abs() * VSAPM_WEIGHT),
-                            v.cheese * CHEESE_WEIGHT,
-                            v.garbage_effi * GARBAGEEFFI_WEIGHT,
-                            ],
-                            "backgroundColor": background_colors[i],
-                            "borderColor": background_colors[i],
-                            "borderWidth": 0,
-                        "pointRadius": 0
-                    })
+                .map(|(i, label, v)| ChartDataset {
+                    label,
+                    data: vec![
+                        v.apm * APM_WEIGHT,
+                        v.pps * PPS_WEIGHT,
+                        v.vs * VS_WEIGHT,
+                        v.app * APP_WEIGHT,
+                        v.dssecond * DSSECOND_WEIGHT,
+                        v.dspiece * DSPIECE_WEIGHT,
+                        v.dsapppiece * DSAPPPIECE_WEIGHT,
+                        ((v.vsapm - 2.0).abs() * VSAPM_WEIGHT),
+                        v.cheese * CHEESE_WEIGHT,
+                        v.garbage_effi * GARBAGEEFFI_WEIGHT,
+                    ],
+                    color: background_colors[i % MAX_COMPARED_USERS],
                 })
                 .collect::<Vec<_>>();
 
-            let json = json!({
-                "type": "radar",
-                "data": {
-                    "labels": ["APM", "PPS", "VS", "APP", "DS/Second", "DS/Piece", "APP+DS/Piece", "VS/APM", "Cheese\nIndex", "Garbage\nEffi."],
-                    "datasets": datasets
-                },
-                "options":{"legend": { "labels": { "fontColor": Self::get_font_color(dark_mode), "fontSize": 16}}, "scale":{"pointLabels":{"fontColor":Self::get_font_color(dark_mode), "fontSize": 16},"rAxis":{"ticks":{"display":false}},"ticks":{"min":0,"max":max_stat,"stepSize":"30","fontColor":"blue","display":false},"gridLines":{"color":"gray"},"angleLines":{"color":"gray"}}}
-            });
-
-            let json = json!({
-                "width": 500,
-                "height": 300,
-                "format": "webp",
-                "background": "transparent",
-                "version": 2,
-                "chart": json
-            });
-
-            log::debug!("{json}");
-
-            let response = reqwest::Client::builder()
-                .build()?
-                .post("https://quickchart.io/chart/create")
-                .header("Content-Type", "application/json")
-                .body(json.to_string())
-                .send()
-                .await?
-                .json::<serde_json::Value>()
-                .await?;
-
-            response
+            Chart {
+                kind: ChartKind::Radar,
+                labels: VsCommand::chart_labels(),
+                datasets,
+                min: 0.0,
+                max: max_stat,
+                dark_mode,
+            }
+            .render(context.chart_backend)
+            .await?
         };
 
-        let interaction_client = context.http_client.interaction(context.application.id);
-        
-        interaction_client
-            .update_response(&interaction.token)
-            .content(Some(
-                response
-                    .get("url")
-                    .ok_or(anyhow!("Couldn't find graph url"))?
-                    .as_str()
-                    .ok_or(anyhow!("Couldn't find graph url"))?,
-            ))?
-            .await?;
+        chart.send("", interaction, context).await?;
 
         Ok(Ok(()))
     }
@@ -10,21 +10,31 @@ use twilight_model::{
 use crate::{
     context::Context,
     utils::{
-        average_of_rank::average_of_rank,
         box_commands::{CommandBox, RunnableCommand},
+        create_embed::create_embed,
+        player_source::{GameSelector, PlayerSource},
         stats::{calculate_stats, calculate_win_chance, PlayerStats, Stats},
+        table::format_table,
         timer::Timer,
     },
 };
+#[cfg(feature = "local_image_generation")]
+use crate::utils::stat_card::{render_comparison, CardPlayer, CARD_FILENAME};
+#[cfg(feature = "local_image_generation")]
+use twilight_model::http::attachment::Attachment;
 
 use crate::interactions::commands::{
     options::user_rank_option::UserRankOption,
-    subcommands::vst::{average, discord, stats, tetrio},
+    subcommands::vst::{average, discord, list_sub_command, stats, tetrio},
 };
 
+use super::vs::VsCommand;
+
 #[derive(CreateCommand, CommandModel)]
-#[command(name = "vst", desc = "Compare stats from two users")]
+#[command(name = "vst", desc = "Compare stats from two or more users")]
 pub enum VstCommand {
+    #[command(name = "list")]
+    List(list_sub_command::ListSubCommand),
     #[command(name = "discord")]
     Discord(discord::DiscordSubCommandGroup),
     #[command(name = "tetrio")]
@@ -36,59 +46,42 @@ pub enum VstCommand {
 }
 
 impl VstCommand {
+    async fn from_source(
+        source: PlayerSource,
+        context: &Context<'_>,
+    ) -> anyhow::Result<anyhow::Result<(String, Stats)>> {
+        Ok(source
+            .resolve(context)
+            .await?
+            .map(|player| (player.name, calculate_stats(player.stats))))
+    }
+
     pub async fn from_discord_user(
         user: &ResolvedUser,
         context: &Context<'_>,
     ) -> anyhow::Result<anyhow::Result<(String, Stats)>> {
-        let tetrio_user = context
-            .tetrio_client
-            .search_discord_user(&user.resolved.id.to_string())
-            .await?;
-
-        let Some(data) = &tetrio_user.data else {
-            return Err(anyhow::anyhow!(
-                "❌ Couldn't find user `@{}#{}`",
-                user.resolved.name,
-                user.resolved.discriminator()
-            ));
-        };
-
-        Self::from_tetrio_user(data.user.username.as_ref(), context).await
+        Self::from_source(
+            PlayerSource::Discord {
+                id: user.resolved.id.get(),
+                selector: GameSelector::default(),
+            },
+            context,
+        )
+        .await
     }
 
     pub async fn from_tetrio_user(
         user: &str,
         context: &Context<'_>,
     ) -> anyhow::Result<anyhow::Result<(String, Stats)>> {
-        let tetrio_user = context.tetrio_client.fetch_user_info(&user).await?;
-
-        let Some(data) = &tetrio_user.data else {
-            return Ok(Err(anyhow::anyhow!("❌ No data has been found. User might be anonymous or banned.")));
-        };
-
-        let tetrio_league_summary = context.tetrio_client.fetch_user_league_summaries(&user).await?;
-
-        let Some(league_data) = &tetrio_league_summary.data else {
-            return Ok(Err(anyhow::anyhow!("❌ No data has been found. User might be anonymous or banned.")));
-        };
-
-        let (_id, username, Some(apm), Some(pps), Some(vs), rank, tr, Some(glicko), Some(rd)) = (&data.id, &data.username, league_data.apm, league_data.pps, league_data.vs, &league_data.rank, league_data.tr, league_data.glicko, league_data.rd) else {
-            return Ok(Err(anyhow::anyhow!("❌ No tetra league stats have been found.")));
-        };
-
-
-        Ok(Ok((
-            username.to_string(),
-            calculate_stats(PlayerStats {
-                apm,
-                pps,
-                vs,
-                rd: Some(rd),
-                tr: tr,
-                glicko: Some(glicko),
-                rank: rank.clone(),
-            }),
-        )))
+        Self::from_source(
+            PlayerSource::Tetrio {
+                user: user.to_string(),
+                selector: GameSelector::default(),
+            },
+            context,
+        )
+        .await
     }
 
     async fn from_average(
@@ -96,24 +89,14 @@ impl VstCommand {
         country: Option<String>,
         context: &Context<'_>,
     ) -> anyhow::Result<anyhow::Result<(String, Stats)>> {
-        let stats = average_of_rank(
-            rank.clone().map(|r| r.into()),
+        Self::from_source(
+            PlayerSource::Average {
+                rank: rank.map(|rank| rank.into()),
+                country,
+            },
             context,
         )
-        .await?;
-
-        let stats = match stats {
-            Ok(stats) => stats,
-            Err(err) => return Ok(Err(err)),
-        };
-
-        let name = format!(
-            "$avg:{}:{}",
-            rank.map(|r| r.value()).unwrap_or("*"),
-            country.unwrap_or(String::from("*"))
-        );
-
-        Ok(Ok((name, calculate_stats(stats.0.into()))))
+        .await
     }
 
     pub async fn from_stats(
@@ -134,6 +117,95 @@ impl VstCommand {
             }),
         )))
     }
+
+    /// A row of the comparison table, the highest value is marked with `*` when `highlight` is set
+    fn stat_row(
+        label: &str,
+        players: &[(String, Stats)],
+        stat: impl Fn(&Stats) -> f64,
+        highlight: bool,
+    ) -> Vec<String> {
+        let values = players.iter().map(|(_, stats)| stat(stats)).collect::<Vec<_>>();
+        Self::format_row(label, &values, highlight)
+    }
+
+    fn format_row(label: &str, values: &[f64], highlight: bool) -> Vec<String> {
+        let best = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+
+        std::iter::once(label.to_string())
+            .chain(values.iter().map(|value| {
+                if highlight && values.len() > 1 && *value == best {
+                    format!("{value:.4}*")
+                } else {
+                    format!("{value:.4}")
+                }
+            }))
+            .collect()
+    }
+
+    /// Average chance of every player to win against each of the other players
+    fn win_chance_row(players: &[(String, Stats)]) -> Vec<String> {
+        let values = players
+            .iter()
+            .enumerate()
+            .map(|(i, (_, left))| {
+                let chances = players
+                    .iter()
+                    .enumerate()
+                    .filter(|(j, _)| *j != i)
+                    .map(|(_, (_, right))| {
+                        calculate_win_chance(
+                            left.glicko.unwrap_or(left.estglicko),
+                            right.glicko.unwrap_or(right.estglicko),
+                            left.rd.unwrap_or(60.9),
+                            right.rd.unwrap_or(60.9),
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                chances.iter().sum::<f64>() / chances.len().max(1) as f64
+            })
+            .collect::<Vec<_>>();
+
+        Self::format_row("Win Chance:", &values, true)
+    }
+
+    /// Replies with the players rendered side by side in an image card.
+    async fn reply_with_card(
+        users: Vec<String>,
+        dark_mode: bool,
+        interaction: &InteractionCreate,
+        context: &Context<'_>,
+    ) -> anyhow::Result<anyhow::Result<()>> {
+        #[cfg(feature = "local_image_generation")]
+        {
+            let players = match VsCommand::resolve_users(users, context).await? {
+                Ok(players) => players,
+                Err(err) => return Ok(Err(err)),
+            };
+
+            let cards = futures::future::join_all(
+                players
+                    .into_iter()
+                    .map(|player| CardPlayer::fetch(player.name, player.avatar_url, player.stats)),
+            )
+            .await;
+            let card = render_comparison(&cards, dark_mode)?;
+
+            context
+                .http_client
+                .interaction(context.application.id)
+                .update_response(&interaction.token)
+                .attachments(&[Attachment::from_bytes(CARD_FILENAME.to_string(), card, 1)])?
+                .await?;
+
+            Ok(Ok(()))
+        }
+        #[cfg(not(feature = "local_image_generation"))]
+        {
+            let _ = (users, dark_mode, interaction, context);
+            Ok(Err(anyhow::anyhow!("❌ Stat cards are not available on this bot")))
+        }
+    }
 }
 
 #[async_trait::async_trait]
@@ -151,41 +223,66 @@ impl RunnableCommand for VstCommand {
             options: data.options,
             resolved: data.resolved.map(Cow::Owned),
         })?;
-        let (left, right) = {
+        let players = {
             let _timer = crate::utils::timer::Timer::new("vst data fetching");
-            let (left, right) = match model {
+            let players = match model {
+                VstCommand::List(list) => {
+                    let users = [
+                        Some(list.user_1),
+                        Some(list.user_2),
+                        list.user_3,
+                        list.user_4,
+                        list.user_5,
+                        list.user_6,
+                    ]
+                    .into_iter()
+                    .flatten()
+                    .collect();
+
+                    if list.card.unwrap_or(false) {
+                        return Self::reply_with_card(users, list.dark_mode.unwrap_or(false), interaction, context).await;
+                    }
+
+                    match VsCommand::parse_users(users, context).await? {
+                        Ok(users) => users
+                            .into_iter()
+                            .map(|(name, stats)| Ok(Ok((name, calculate_stats(stats)))))
+                            .collect(),
+                        Err(err) => return Ok(Err(err)),
+                    }
+                }
                 VstCommand::Discord(discord) => match discord {
-                    discord::DiscordSubCommandGroup::Discord(discord) => (
+                    discord::DiscordSubCommandGroup::Discord(discord) => vec![
                         Self::from_discord_user(&discord.user1, &context).await,
                         Self::from_discord_user(&discord.user2, &context).await,
-                    ),
-                    discord::DiscordSubCommandGroup::Stats(stats) => (
+                    ],
+                    discord::DiscordSubCommandGroup::Stats(stats) => vec![
                         Self::from_discord_user(&stats.discord_user, &context).await,
                         Self::from_stats(stats.pps, stats.apm, stats.vs).await,
-                    ),
+                    ],
                 },
                 VstCommand::Tetrio(tetrio) => match tetrio.as_ref() {
-                    tetrio::TetrioSubCommandGroup::Discord(discord) => (
+                    tetrio::TetrioSubCommandGroup::Discord(discord) => vec![
                         Self::from_tetrio_user(&discord.tetrio_user, &context).await,
                         Self::from_discord_user(&discord.discord_user, &context).await,
-                    ),
-                    tetrio::TetrioSubCommandGroup::Tetrio(tetrio) => (
+                    ],
+                    tetrio::TetrioSubCommandGroup::Tetrio(tetrio) => vec![
                         Self::from_tetrio_user(&tetrio.user1, &context).await,
                         Self::from_tetrio_user(&tetrio.user2, &context).await,
-                    ),
-                    tetrio::TetrioSubCommandGroup::Stats(stats) => (
+                    ],
+                    tetrio::TetrioSubCommandGroup::Stats(stats) => vec![
                         Self::from_tetrio_user(&stats.tetrio_user, &context).await,
                         Self::from_stats(stats.pps, stats.apm, stats.vs).await,
-                    ),
+                    ],
                 },
                 VstCommand::Stats(stats) => match stats {
-                    stats::StatsSubCommandGroup::Stats(stats) => (
+                    stats::StatsSubCommandGroup::Stats(stats) => vec![
                         Self::from_stats(stats.pps1, stats.apm1, stats.vs1).await,
                         Self::from_stats(stats.pps2, stats.apm2, stats.vs2).await,
-                    ),
+                    ],
                 },
                 VstCommand::Average(average) => match average {
-                    average::AverageSubCommandGroup::Stats(data) => (
+                    average::AverageSubCommandGroup::Stats(data) => vec![
                         Self::from_stats(data.pps, data.apm, data.vs).await,
                         Self::from_average(
                             data.average_rank.clone(),
@@ -193,8 +290,8 @@ impl RunnableCommand for VstCommand {
                             &context,
                         )
                         .await,
-                    ),
-                    average::AverageSubCommandGroup::Discord(data) => (
+                    ],
+                    average::AverageSubCommandGroup::Discord(data) => vec![
                         Self::from_discord_user(&data.discord_user, &context).await,
                         Self::from_average(
                             data.average_rank.clone(),
@@ -202,8 +299,8 @@ impl RunnableCommand for VstCommand {
                             &context,
                         )
                         .await,
-                    ),
-                    average::AverageSubCommandGroup::Tetrio(data) => (
+                    ],
+                    average::AverageSubCommandGroup::Tetrio(data) => vec![
                         Self::from_tetrio_user(&data.tetrio_user, &context).await,
                         Self::from_average(
                             data.average_rank,
@@ -211,8 +308,8 @@ impl RunnableCommand for VstCommand {
                             &context,
                         )
                         .await,
-                    ),
-                    average::AverageSubCommandGroup::Average(data) => (
+                    ],
+                    average::AverageSubCommandGroup::Average(data) => vec![
                         Self::from_average(
                             data.average_rank1,
                             data.average_country1,
@@ -225,237 +322,50 @@ impl RunnableCommand for VstCommand {
                             &context,
                         )
                         .await,
-                    ),
+                    ],
                 },
             };
 
-            let (left, right) = (left?, right?);
-
-            let left = match left {
-                Ok(result) => result,
-                Err(err) => return Ok(Err(err)),
-            };
 
-            let right = match right {
-                Ok(result) => result,
-                Err(err) => return Ok(Err(err)),
-            };
+            let mut parsed = vec![];
+            for player in players {
+                parsed.push(match player? {
+                    Ok(result) => result,
+                    Err(err) => return Ok(Err(err)),
+                });
+            }
 
-            (left, right)
+            parsed
         };
 
         let v = {
-            let _timer = Timer::new(format!("vst data parsing {} {}", left.0, right.0));
-            let v = [
-                ["Names:".to_string(), left.0, right.0],
-                [
-                    "APM:".to_string(),
-                    format!("{:.4}", left.1.apm),
-                    format!("{:.4}", right.1.apm),
-                ],
-                [
-                    "PPS:".to_string(),
-                    format!("{:.4}", left.1.pps),
-                    format!("{:.4}", right.1.pps),
-                ],
-                [
-                    "VS:".to_string(),
-                    format!("{:.4}", left.1.vs),
-                    format!("{:.4}", right.1.vs),
-                ],
-                [
-                    "APP:".to_string(),
-                    format!("{:.4}", left.1.app),
-                    format!("{:.4}", right.1.app),
-                ],
-                [
-                    "DS/Piece:".to_string(),
-                    format!("{:.4}", left.1.dspiece),
-                    format!("{:.4}", right.1.dspiece),
-                ],
-                [
-                    "APP+DS/Piece:".to_string(),
-                    format!("{:.4}", left.1.dsapppiece),
-                    format!("{:.4}", right.1.dsapppiece),
-                ],
-                [
-                    "DS/Second:".to_string(),
-                    format!("{:.4}", left.1.dssecond),
-                    format!("{:.4}", right.1.dssecond),
-                ],
-                [
-                    "VS/APM:".to_string(),
-                    format!("{:.4}", left.1.vsapm),
-                    format!("{:.4}", right.1.vsapm),
-                ],
-                [
-                    "Cheese Index:".to_string(),
-                    format!("{:.4}", left.1.cheese),
-                    format!("{:.4}", right.1.cheese),
-                ],
-                [
-                    "Garbage Effi:".to_string(),
-                    format!("{:.4}", left.1.garbage_effi),
-                    format!("{:.4}", right.1.garbage_effi),
-                ],
-                [
-                    "Weighted APP:".to_string(),
-                    format!("{:.4}", left.1.weighted_app),
-                    format!("{:.4}", right.1.weighted_app),
-                ],
-                [
-                    "Area:".to_string(),
-                    format!("{:.4}", left.1.area),
-                    format!("{:.4}", right.1.area),
-                ],
-                [
-                    "Win Chance:".to_string(),
-                    format!(
-                        "{:.4}",
-                        calculate_win_chance(
-                            left.1.glicko.unwrap_or(left.1.estglicko),
-                            right.1.glicko.unwrap_or(right.1.estglicko),
-                            left.1.rd.unwrap_or(60.9),
-                            right.1.rd.unwrap_or(60.9)
-                        )
-                    ),
-                    format!(
-                        "{:.4}",
-                        calculate_win_chance(
-                            right.1.glicko.unwrap_or(right.1.estglicko),
-                            left.1.glicko.unwrap_or(left.1.estglicko),
-                            right.1.rd.unwrap_or(60.9),
-                            left.1.rd.unwrap_or(60.9)
-                        )
-                    ),
-                ],
-            ];
-
-            // v.push(["Names:".to_string(), left.0, right.0]);
-            // v.push([
-            //     "APM:".to_string(),
-            //     format!("{:.4}", left.1.apm),
-            //     format!("{:.4}", right.1.apm),
-            // ]);
-            // v.push([
-            //     "PPS:".to_string(),
-            //     format!("{:.4}", left.1.pps),
-            //     format!("{:.4}", right.1.pps),
-            // ]);
-            // v.push([
-            //     "VS:".to_string(),
-            //     format!("{:.4}", left.1.vs),
-            //     format!("{:.4}", right.1.vs),
-            // ]);
-            // v.push([
-            //     "APP:".to_string(),
-            //     format!("{:.4}", left.1.app),
-            //     format!("{:.4}", right.1.app),
-            // ]);
-            // v.push([
-            //     "DS/Piece:".to_string(),
-            //     format!("{:.4}", left.1.dspiece),
-            //     format!("{:.4}", right.1.dspiece),
-            // ]);
-            // v.push([
-            //     "APP+DS/Piece:".to_string(),
-            //     format!("{:.4}", left.1.dsapppiece),
-            //     format!("{:.4}", right.1.dsapppiece),
-            // ]);
-            // v.push([
-            //     "DS/Second:".to_string(),
-            //     format!("{:.4}", left.1.dssecond),
-            //     format!("{:.4}", right.1.dssecond),
-            // ]);
-            // v.push([
-            //     "VS/APM:".to_string(),
-            //     format!("{:.4}", left.1.vsapm),
-            //     format!("{:.4}", right.1.vsapm),
-            // ]);
-            // v.push([
-            //     "Cheese Index:".to_string(),
-            //     format!("{:.4}", left.1.cheese),
-            //     format!("{:.4}", right.1.cheese),
-            // ]);
-            // v.push([
-            //     "Garbage Effi:".to_string(),
-            //     format!("{:.4}", left.1.garbage_effi),
-            //     format!("{:.4}", right.1.garbage_effi),
-            // ]);
-            // v.push([
-            //     "Weighted APP:".to_string(),
-            //     format!("{:.4}", left.1.weighted_app),
-            //     format!("{:.4}", right.1.weighted_app),
-            // ]);
-            // v.push([
-            //     "Area:".to_string(),
-            //     format!("{:.4}", left.1.area),
-            //     format!("{:.4}", right.1.area),
-            // ]);
-            // v.push([
-            //     "Win Chance:".to_string(),
-            //     format!(
-            //         "{:.4}",
-            //         calculate_win_chance(
-            //             left.1.glicko.unwrap_or(left.1.estglicko),
-            //             right.1.glicko.unwrap_or(right.1.estglicko),
-            //             left.1.rd.unwrap_or(60.9),
-            //             right.1.rd.unwrap_or(60.9)
-            //         )
-            //     ),
-            //     format!(
-            //         "{:.4}",
-            //         calculate_win_chance(
-            //             right.1.glicko.unwrap_or(right.1.estglicko),
-            //             left.1.glicko.unwrap_or(left.1.estglicko),
-            //             right.1.rd.unwrap_or(60.9),
-            //             left.1.rd.unwrap_or(60.9)
-            //         )
-            //     ),
-            // ]);
-            v
+            let _timer = Timer::new(format!(
+                "vst data parsing {}",
+                players.iter().map(|(name, _)| name).join(" ")
+            ));
+            vec![
+                std::iter::once("Names:".to_string())
+                    .chain(players.iter().map(|(name, _)| name.clone()))
+                    .collect(),
+                Self::stat_row("APM:", &players, |stats| stats.apm, true),
+                Self::stat_row("PPS:", &players, |stats| stats.pps, true),
+                Self::stat_row("VS:", &players, |stats| stats.vs, true),
+                Self::stat_row("APP:", &players, |stats| stats.app, true),
+                Self::stat_row("DS/Piece:", &players, |stats| stats.dspiece, true),
+                Self::stat_row("APP+DS/Piece:", &players, |stats| stats.dsapppiece, true),
+                Self::stat_row("DS/Second:", &players, |stats| stats.dssecond, true),
+                Self::stat_row("VS/APM:", &players, |stats| stats.vsapm, false),
+                Self::stat_row("Cheese Index:", &players, |stats| stats.cheese, false),
+                Self::stat_row("Garbage Effi:", &players, |stats| stats.garbage_effi, true),
+                Self::stat_row("Weighted APP:", &players, |stats| stats.weighted_app, true),
+                Self::stat_row("Area:", &players, |stats| stats.area, true),
+                Self::win_chance_row(&players),
+            ]
         };
 
         let final_str = {
-            let _timer = Timer::new(format!("vst data formatting {} {}", v[0][1], v[0][2]));
-            let mut columns_size = vec![0usize; v[0].len()];
-            for i in 0..v[0].len() {
-                columns_size[i] = v.iter().map(|array| array[i].len()).max().unwrap_or(0);
-            }
-            log::debug!("{:?}", columns_size);
-            let top = String::from("╔")
-                + &columns_size
-                    .iter()
-                    .map(|size| "═".repeat(size + 2))
-                    .join("╤")
-                + "╗\n";
-            let bottom = String::from("╚")
-                + &columns_size
-                    .iter()
-                    .map(|size| "═".repeat(size + 2))
-                    .join("╧")
-                + "╝";
-            let separator = String::from("╟")
-                + &columns_size
-                    .iter()
-                    .map(|size| "═".repeat(size + 2))
-                    .join("┼")
-                + "╢\n";
-
-            let final_str = top
-                + &v.into_iter()
-                    .map(|mut array| {
-                        for i in 0..array.len() {
-                            array[i] = format!(" {:<width$}", array[i], width = columns_size[i] + 1)
-                        }
-
-                        String::from("║") + &array.join("│") + "║\n"
-                    })
-                    .join(&separator)
-                + &bottom;
-
-            let final_str = format!("```\n{}\n```", final_str);
-            final_str
+            let _timer = Timer::new("vst data formatting");
+            format!("```\n{}\n```\n`*` best value", format_table(&v))
         };
         log::debug!("{}", final_str.len());
 
@@ -466,10 +376,22 @@ impl RunnableCommand for VstCommand {
         match r {
             Ok(response) => response.await?,
             Err(_) => {
-                interaction_client
+                // Comparing many players doesn't fit in a message, embed descriptions are allowed to be longer
+                let embed = create_embed(None, context).await?
+                    .description(final_str)
+                    .build();
+                match interaction_client
                     .update_response(&interaction.token)
-                    .content(Some("❌ Message was too long."))?
-                    .await?
+                    .embeds(Some(&[embed]))
+                {
+                    Ok(response) => response.await?,
+                    Err(_) => {
+                        interaction_client
+                            .update_response(&interaction.token)
+                            .content(Some("❌ Message was too long."))?
+                            .await?
+                    }
+                }
             }
         };
         Ok(Ok(()))
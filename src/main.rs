@@ -37,6 +37,7 @@ use twilight_model::id::Id;
 
 
 use crate::interactions::commands::get_commands;
+use crate::utils::charts::ChartBackend;
 async fn run_bot() -> anyhow::Result<anyhow::Result<()>> {
 
     #[cfg(feature = "tetrio")]
@@ -143,6 +144,8 @@ async fn run_bot() -> anyhow::Result<anyhow::Result<()>> {
         let ai_channel: u64 = std::env::var("AI_CHANNEL").expect("Couldn't get AI channel").parse().expect("Couldn't parse AI channel");
     
 
+        let api_url = std::env::var("API_URL").expect("Couldn't get api server url");
+
         let context = Arc::new(Context {
             application: discord_application,
             http_client: discord_client,
@@ -150,9 +153,22 @@ async fn run_bot() -> anyhow::Result<anyhow::Result<()>> {
             tetrio_client,
             test_guild,
             local_server_url: std::env::var("HTML_SERVER_URL").expect("Couldn't get html server url"),
-            api_url: std::env::var("API_URL").expect("Couldn't get api server url"),
+            #[cfg(all(feature = "tetrio", feature = "html_server_image_generation"))]
+            image_server: crate::utils::image_server::from_env(&api_url),
+            #[cfg(feature = "tetrio")]
+            replay_fetcher: crate::utils::replay_fetcher::from_env(),
+            #[cfg(feature = "tetrio")]
+            leaderboard_cache: Default::default(),
+            api_url,
+            chart_backend: ChartBackend::from_env(),
             #[cfg(feature = "database")]
             sql_connection,
+            #[cfg(all(feature = "database", feature = "tetrio"))]
+            replay_storage_dir: std::env::var("REPLAY_STORAGE_DIR").unwrap_or("replays".to_string()).into(),
+            #[cfg(all(feature = "database", feature = "tetrio"))]
+            link_verifier: crate::utils::account_link::from_env(),
+            #[cfg(all(feature = "database", feature = "tetrio"))]
+            member_cache: Default::default(),
             commands: get_commands(),
             author_id: std::env::var("AUTHOR_ID").expect("Couldn't get the ID of the creator of the bot").parse().expect("Couldn't parse discord bot author"),
             #[cfg(feature = "ai")]
@@ -167,6 +183,17 @@ async fn run_bot() -> anyhow::Result<anyhow::Result<()>> {
     #[cfg(feature = "database")]
     log::info!("{row:?}; SQL database initialized!");
 
+    #[cfg(all(feature = "database", feature = "tetrio"))]
+    let _rank_role_sync = crate::utils::rank_roles::spawn_sync_job(Arc::clone(&context));
+    #[cfg(all(feature = "database", feature = "tetrio"))]
+    let _announcement_poller = crate::utils::announcements::spawn_poller(Arc::clone(&context));
+    #[cfg(all(feature = "database", feature = "tetrio"))]
+    let _match_feed_poller = crate::utils::match_feed::spawn_poller(Arc::clone(&context));
+    #[cfg(all(feature = "database", feature = "tetrio"))]
+    let _rank_cutoff_snapshots = crate::utils::rank_cutoffs::spawn_snapshot_job(Arc::clone(&context));
+    #[cfg(all(feature = "database", feature = "tetrio"))]
+    let _leaderboard_exports = crate::utils::leaderboard_export::spawn_export_job(Arc::clone(&context));
+
         println!("Hello World!");
 
         tokio::spawn(async {
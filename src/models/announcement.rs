@@ -0,0 +1,28 @@
+use sqlx::FromRow;
+
+#[derive(FromRow, Debug)]
+pub struct AnnouncementChannelData {
+    pub guild_id: String,
+    pub channel_id: String,
+}
+
+/// A player announced in a guild without being linked to one of its members
+#[derive(FromRow, Debug)]
+pub struct TrackedPlayerData {
+    pub guild_id: String,
+    pub tetrio_id: String,
+    pub username: String,
+}
+
+/// What is compared between two polls of a player
+#[derive(FromRow, Debug, Clone, Default)]
+pub struct PlayerSnapshotData {
+    pub tetrio_id: String,
+    /// Lowercase, `None` when unranked
+    pub rank: Option<String>,
+    pub tr: Option<f64>,
+    pub games_played: Option<i64>,
+    /// 40 lines personal best, in seconds
+    pub sprint_time: Option<f64>,
+    pub blitz_score: Option<i64>,
+}
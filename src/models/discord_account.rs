@@ -0,0 +1,8 @@
+use sqlx::FromRow;
+
+/// The tetrio account found from the discord connection of a tetrio profile, `None` when there is none
+#[derive(FromRow, Debug)]
+pub struct CachedDiscordAccountData {
+    pub discord_id: String,
+    pub tetrio_id: Option<String>,
+}
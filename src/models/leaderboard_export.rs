@@ -0,0 +1,12 @@
+use sqlx::FromRow;
+
+/// A channel receiving a daily export of the leaderboard
+#[derive(FromRow, Debug)]
+pub struct LeaderboardExportData {
+    pub guild_id: String,
+    pub channel_id: String,
+    /// `csv` or `json`
+    pub format: String,
+    pub rank: Option<String>,
+    pub country: Option<String>,
+}
@@ -0,0 +1,10 @@
+use sqlx::FromRow;
+
+/// A channel receiving the tetra league matches of a player
+#[derive(FromRow, Debug)]
+pub struct MatchFeedSubscriptionData {
+    pub guild_id: String,
+    pub channel_id: String,
+    pub tetrio_id: String,
+    pub username: String,
+}
@@ -1,2 +1,20 @@
+#[cfg(all(feature = "database", feature = "tetrio"))]
+pub mod announcement;
+#[cfg(all(feature = "database", feature = "tetrio"))]
+pub mod discord_account;
+#[cfg(all(feature = "database", feature = "tetrio"))]
+pub mod leaderboard_export;
+#[cfg(all(feature = "database", feature = "tetrio"))]
+pub mod match_feed;
+#[cfg(all(feature = "database", feature = "tetrio"))]
+pub mod rank_cutoff;
+#[cfg(all(feature = "database", feature = "tetrio"))]
+pub mod rank_role;
+#[cfg(all(feature = "database", feature = "tetrio"))]
+pub mod replay;
 #[cfg(feature = "database")]
 pub mod silly_command;
+#[cfg(all(feature = "database", feature = "tetrio"))]
+pub mod stat_formula;
+#[cfg(all(feature = "database", feature = "tetrio"))]
+pub mod tetrio_link;
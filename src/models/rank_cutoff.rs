@@ -0,0 +1,14 @@
+use sqlx::FromRow;
+
+/// The cutoff of a rank at some point in time
+#[derive(FromRow, Debug, Clone)]
+pub struct RankCutoffData {
+    pub rank: String,
+    /// TR of the lowest player of the rank
+    pub tr: f64,
+    pub target_tr: f64,
+    pub percentile: f64,
+    pub players: i64,
+    /// Unix timestamp in seconds
+    pub taken_at: i64,
+}
@@ -0,0 +1,22 @@
+use sqlx::FromRow;
+
+/// A role given to the members matching every condition that is set
+#[derive(FromRow, Debug, Clone)]
+pub struct RankRoleData {
+    pub guild_id: String,
+    pub role_id: String,
+    pub rank: Option<String>,
+    pub min_tr: Option<f64>,
+    pub max_tr: Option<f64>,
+    pub country: Option<String>,
+}
+
+#[derive(FromRow, Debug)]
+pub struct RankRoleAuditData {
+    pub discord_id: String,
+    pub role_id: String,
+    pub added: bool,
+    pub reason: String,
+    /// Formatted as `YYYY-MM-DD HH:MM`
+    pub created_at: String,
+}
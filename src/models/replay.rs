@@ -0,0 +1,14 @@
+use sqlx::FromRow;
+
+#[derive(FromRow, Debug)]
+pub struct ReplayData {
+    pub id_replay: i32,
+    pub hash: String,
+    pub filename: String,
+    /// Lowercase usernames
+    pub players: Vec<String>,
+    /// Formatted as `YYYY-MM-DD HH:MM`
+    pub ts: Option<String>,
+    pub rounds: i32,
+    pub result: Option<String>,
+}
@@ -0,0 +1,10 @@
+use sqlx::FromRow;
+
+#[derive(FromRow, Debug)]
+pub struct StatFormulaData {
+    pub id_stat_formula: i32,
+    pub guild_id: String,
+    pub name: String,
+    pub expression: String,
+    pub author_id: String,
+}
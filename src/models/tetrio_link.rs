@@ -0,0 +1,17 @@
+use sqlx::FromRow;
+
+#[derive(FromRow, Debug)]
+pub struct TetrioLinkData {
+    pub discord_id: String,
+    pub tetrio_id: String,
+    pub username: String,
+}
+
+/// A link waiting for the code to appear in the tetrio bio
+#[derive(FromRow, Debug)]
+pub struct TetrioLinkRequestData {
+    pub discord_id: String,
+    pub tetrio_id: String,
+    pub username: String,
+    pub code: String,
+}
@@ -0,0 +1,161 @@
+use sqlx::{FromRow, Row};
+
+use crate::{
+    context::Context,
+    models::announcement::{AnnouncementChannelData, PlayerSnapshotData, TrackedPlayerData},
+};
+
+pub struct AnnouncementPDO;
+impl AnnouncementPDO {
+    pub async fn upsert_channel(
+        context: &Context<'_>,
+        guild_id: u64,
+        channel_id: u64,
+    ) -> anyhow::Result<()> {
+        sqlx::query(include_str!("../sql/announcements/upsert_channel.sql"))
+            .bind(guild_id.to_string())
+            .bind(channel_id.to_string())
+            .execute(&context.sql_connection)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn delete_channel(
+        context: &Context<'_>,
+        guild_id: u64,
+    ) -> anyhow::Result<bool> {
+        let result = sqlx::query(include_str!("../sql/announcements/delete_channel.sql"))
+            .bind(guild_id.to_string())
+            .execute(&context.sql_connection)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn fetch_channel(
+        context: &Context<'_>,
+        guild_id: u64,
+    ) -> anyhow::Result<Option<AnnouncementChannelData>> {
+        let row = sqlx::query(include_str!("../sql/announcements/fetch_channel.sql"))
+            .bind(guild_id.to_string())
+            .fetch_optional(&context.sql_connection)
+            .await?;
+
+        Ok(row.map(|row| AnnouncementChannelData::from_row(&row)).transpose()?)
+    }
+
+    pub async fn fetch_channels(context: &Context<'_>) -> anyhow::Result<Vec<AnnouncementChannelData>> {
+        let rows = sqlx::query(include_str!("../sql/announcements/fetch_channels.sql"))
+            .fetch_all(&context.sql_connection)
+            .await?;
+
+        Ok(rows.iter().map(AnnouncementChannelData::from_row).collect::<Result<_, _>>()?)
+    }
+
+    pub async fn upsert_tracked_player(
+        context: &Context<'_>,
+        guild_id: u64,
+        tetrio_id: &str,
+        username: &str,
+    ) -> anyhow::Result<()> {
+        sqlx::query(include_str!("../sql/announcements/upsert_tracked_player.sql"))
+            .bind(guild_id.to_string())
+            .bind(tetrio_id)
+            .bind(username)
+            .execute(&context.sql_connection)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn delete_tracked_player(
+        context: &Context<'_>,
+        guild_id: u64,
+        username: &str,
+    ) -> anyhow::Result<bool> {
+        let result = sqlx::query(include_str!("../sql/announcements/delete_tracked_player.sql"))
+            .bind(guild_id.to_string())
+            .bind(username)
+            .execute(&context.sql_connection)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn fetch_tracked_players(context: &Context<'_>) -> anyhow::Result<Vec<TrackedPlayerData>> {
+        let rows = sqlx::query(include_str!("../sql/announcements/fetch_tracked_players.sql"))
+            .fetch_all(&context.sql_connection)
+            .await?;
+
+        Ok(rows.iter().map(TrackedPlayerData::from_row).collect::<Result<_, _>>()?)
+    }
+
+    pub async fn fetch_guild_tracked_players(
+        context: &Context<'_>,
+        guild_id: u64,
+    ) -> anyhow::Result<Vec<TrackedPlayerData>> {
+        let rows = sqlx::query(include_str!("../sql/announcements/fetch_guild_tracked_players.sql"))
+            .bind(guild_id.to_string())
+            .fetch_all(&context.sql_connection)
+            .await?;
+
+        Ok(rows.iter().map(TrackedPlayerData::from_row).collect::<Result<_, _>>()?)
+    }
+
+    /// The snapshot of the previous poll of a player.
+    pub async fn fetch_snapshot(
+        context: &Context<'_>,
+        tetrio_id: &str,
+    ) -> anyhow::Result<Option<PlayerSnapshotData>> {
+        let row = sqlx::query(include_str!("../sql/announcements/fetch_snapshot.sql"))
+            .bind(tetrio_id)
+            .fetch_optional(&context.sql_connection)
+            .await?;
+
+        Ok(row.map(|row| PlayerSnapshotData::from_row(&row)).transpose()?)
+    }
+
+    pub async fn upsert_snapshot(
+        context: &Context<'_>,
+        snapshot: &PlayerSnapshotData,
+    ) -> anyhow::Result<()> {
+        sqlx::query(include_str!("../sql/announcements/upsert_snapshot.sql"))
+            .bind(&snapshot.tetrio_id)
+            .bind(&snapshot.rank)
+            .bind(snapshot.tr)
+            .bind(snapshot.games_played)
+            .bind(snapshot.sprint_time)
+            .bind(snapshot.blitz_score)
+            .execute(&context.sql_connection)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn upsert_preference(
+        context: &Context<'_>,
+        discord_id: u64,
+        enabled: bool,
+    ) -> anyhow::Result<()> {
+        sqlx::query(include_str!("../sql/announcements/upsert_preference.sql"))
+            .bind(discord_id.to_string())
+            .bind(enabled)
+            .execute(&context.sql_connection)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Discord users who don't want their progress announced.
+    pub async fn fetch_opted_out(context: &Context<'_>) -> anyhow::Result<Vec<u64>> {
+        let rows = sqlx::query(include_str!("../sql/announcements/fetch_opted_out.sql"))
+            .fetch_all(&context.sql_connection)
+            .await?;
+
+        Ok(rows
+            .iter()
+            .filter_map(|row| row.try_get::<String, _>("discord_id").ok()?.parse().ok())
+            .collect())
+    }
+}
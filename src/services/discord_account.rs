@@ -0,0 +1,34 @@
+use sqlx::FromRow;
+
+use crate::{context::Context, models::discord_account::CachedDiscordAccountData};
+
+pub struct DiscordAccountPDO;
+impl DiscordAccountPDO {
+    /// The accounts looked up in the last week among the discord users.
+    pub async fn fetch_cached_accounts(
+        context: &Context<'_>,
+        discord_ids: &[u64],
+    ) -> anyhow::Result<Vec<CachedDiscordAccountData>> {
+        let discord_ids = discord_ids.iter().map(u64::to_string).collect::<Vec<_>>();
+        let rows = sqlx::query(include_str!("../sql/discord_accounts/fetch_cached_accounts.sql"))
+            .bind(&discord_ids)
+            .fetch_all(&context.sql_connection)
+            .await?;
+
+        Ok(rows.iter().map(CachedDiscordAccountData::from_row).collect::<Result<_, _>>()?)
+    }
+
+    pub async fn upsert_cached_account(
+        context: &Context<'_>,
+        discord_id: u64,
+        tetrio_id: Option<&str>,
+    ) -> anyhow::Result<()> {
+        sqlx::query(include_str!("../sql/discord_accounts/upsert_cached_account.sql"))
+            .bind(discord_id.to_string())
+            .bind(tetrio_id)
+            .execute(&context.sql_connection)
+            .await?;
+
+        Ok(())
+    }
+}
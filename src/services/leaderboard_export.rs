@@ -0,0 +1,59 @@
+use sqlx::FromRow;
+
+use crate::{context::Context, models::leaderboard_export::LeaderboardExportData};
+
+pub struct LeaderboardExportPDO;
+impl LeaderboardExportPDO {
+    pub async fn upsert_export(context: &Context<'_>, export: &LeaderboardExportData) -> anyhow::Result<()> {
+        sqlx::query(include_str!("../sql/leaderboard_exports/upsert_export.sql"))
+            .bind(&export.guild_id)
+            .bind(&export.channel_id)
+            .bind(&export.format)
+            .bind(&export.rank)
+            .bind(&export.country)
+            .execute(&context.sql_connection)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn delete_export(context: &Context<'_>, guild_id: u64, channel_id: u64) -> anyhow::Result<bool> {
+        let result = sqlx::query(include_str!("../sql/leaderboard_exports/delete_export.sql"))
+            .bind(guild_id.to_string())
+            .bind(channel_id.to_string())
+            .execute(&context.sql_connection)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn fetch_guild_exports(
+        context: &Context<'_>,
+        guild_id: u64,
+    ) -> anyhow::Result<Vec<LeaderboardExportData>> {
+        let rows = sqlx::query(include_str!("../sql/leaderboard_exports/fetch_guild_exports.sql"))
+            .bind(guild_id.to_string())
+            .fetch_all(&context.sql_connection)
+            .await?;
+
+        Ok(rows.iter().map(LeaderboardExportData::from_row).collect::<Result<_, _>>()?)
+    }
+
+    /// The exports not sent during the last day.
+    pub async fn fetch_due_exports(context: &Context<'_>) -> anyhow::Result<Vec<LeaderboardExportData>> {
+        let rows = sqlx::query(include_str!("../sql/leaderboard_exports/fetch_due_exports.sql"))
+            .fetch_all(&context.sql_connection)
+            .await?;
+
+        Ok(rows.iter().map(LeaderboardExportData::from_row).collect::<Result<_, _>>()?)
+    }
+
+    pub async fn mark_exported(context: &Context<'_>, channel_id: &str) -> anyhow::Result<()> {
+        sqlx::query(include_str!("../sql/leaderboard_exports/mark_exported.sql"))
+            .bind(channel_id)
+            .execute(&context.sql_connection)
+            .await?;
+
+        Ok(())
+    }
+}
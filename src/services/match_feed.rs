@@ -0,0 +1,89 @@
+use sqlx::FromRow;
+
+use crate::{context::Context, models::match_feed::MatchFeedSubscriptionData};
+
+pub struct MatchFeedPDO;
+impl MatchFeedPDO {
+    pub async fn upsert_subscription(
+        context: &Context<'_>,
+        guild_id: u64,
+        channel_id: u64,
+        tetrio_id: &str,
+        username: &str,
+    ) -> anyhow::Result<()> {
+        sqlx::query(include_str!("../sql/match_feed/upsert_subscription.sql"))
+            .bind(guild_id.to_string())
+            .bind(channel_id.to_string())
+            .bind(tetrio_id)
+            .bind(username)
+            .execute(&context.sql_connection)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Removes the player from every channel of the guild.
+    pub async fn delete_subscription(
+        context: &Context<'_>,
+        guild_id: u64,
+        username: &str,
+    ) -> anyhow::Result<bool> {
+        let result = sqlx::query(include_str!("../sql/match_feed/delete_subscription.sql"))
+            .bind(guild_id.to_string())
+            .bind(username)
+            .execute(&context.sql_connection)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn fetch_subscriptions(context: &Context<'_>) -> anyhow::Result<Vec<MatchFeedSubscriptionData>> {
+        let rows = sqlx::query(include_str!("../sql/match_feed/fetch_subscriptions.sql"))
+            .fetch_all(&context.sql_connection)
+            .await?;
+
+        Ok(rows.iter().map(MatchFeedSubscriptionData::from_row).collect::<Result<_, _>>()?)
+    }
+
+    pub async fn fetch_guild_subscriptions(
+        context: &Context<'_>,
+        guild_id: u64,
+    ) -> anyhow::Result<Vec<MatchFeedSubscriptionData>> {
+        let rows = sqlx::query(include_str!("../sql/match_feed/fetch_guild_subscriptions.sql"))
+            .bind(guild_id.to_string())
+            .fetch_all(&context.sql_connection)
+            .await?;
+
+        Ok(rows.iter().map(MatchFeedSubscriptionData::from_row).collect::<Result<_, _>>()?)
+    }
+
+    /// Marks the match as posted in the channel, `false` when it already was.
+    pub async fn insert_post(
+        context: &Context<'_>,
+        channel_id: u64,
+        replay_id: &str,
+    ) -> anyhow::Result<bool> {
+        let result = sqlx::query(include_str!("../sql/match_feed/insert_post.sql"))
+            .bind(channel_id.to_string())
+            .bind(replay_id)
+            .execute(&context.sql_connection)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Forgets that the match was posted in the channel, so that it is posted again.
+    pub async fn delete_post(
+        context: &Context<'_>,
+        channel_id: u64,
+        replay_id: &str,
+    ) -> anyhow::Result<()> {
+        sqlx::query(include_str!("../sql/match_feed/delete_post.sql"))
+            .bind(channel_id.to_string())
+            .bind(replay_id)
+            .execute(&context.sql_connection)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,47 @@
+use sqlx::FromRow;
+
+use crate::{context::Context, models::rank_cutoff::RankCutoffData};
+
+pub struct RankCutoffPDO;
+impl RankCutoffPDO {
+    pub async fn insert_snapshot(
+        context: &Context<'_>,
+        rank: &str,
+        tr: f64,
+        target_tr: f64,
+        percentile: f64,
+        players: i64,
+    ) -> anyhow::Result<()> {
+        sqlx::query(include_str!("../sql/rank_cutoffs/insert_snapshot.sql"))
+            .bind(rank)
+            .bind(tr)
+            .bind(target_tr)
+            .bind(percentile)
+            .bind(players)
+            .execute(&context.sql_connection)
+            .await?;
+
+        Ok(())
+    }
+
+    /// The latest snapshot of every rank taken at least `hours` ago.
+    pub async fn fetch_snapshots_before(context: &Context<'_>, hours: i32) -> anyhow::Result<Vec<RankCutoffData>> {
+        let rows = sqlx::query(include_str!("../sql/rank_cutoffs/fetch_snapshots_before.sql"))
+            .bind(hours)
+            .fetch_all(&context.sql_connection)
+            .await?;
+
+        Ok(rows.iter().map(RankCutoffData::from_row).collect::<Result<_, _>>()?)
+    }
+
+    /// The snapshots of a rank over the last `days`, the oldest first.
+    pub async fn fetch_history(context: &Context<'_>, rank: &str, days: i32) -> anyhow::Result<Vec<RankCutoffData>> {
+        let rows = sqlx::query(include_str!("../sql/rank_cutoffs/fetch_history.sql"))
+            .bind(rank)
+            .bind(days)
+            .fetch_all(&context.sql_connection)
+            .await?;
+
+        Ok(rows.iter().map(RankCutoffData::from_row).collect::<Result<_, _>>()?)
+    }
+}
@@ -0,0 +1,99 @@
+use sqlx::{FromRow, Row};
+
+use crate::{
+    context::Context,
+    models::rank_role::{RankRoleAuditData, RankRoleData},
+};
+
+pub struct RankRolePDO;
+impl RankRolePDO {
+    pub async fn upsert_rank_role(
+        context: &Context<'_>,
+        rule: &RankRoleData,
+    ) -> anyhow::Result<()> {
+        sqlx::query(include_str!("../sql/rank_roles/upsert_rank_role.sql"))
+            .bind(&rule.guild_id)
+            .bind(&rule.role_id)
+            .bind(&rule.rank)
+            .bind(rule.min_tr)
+            .bind(rule.max_tr)
+            .bind(&rule.country)
+            .execute(&context.sql_connection)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn delete_rank_role(
+        context: &Context<'_>,
+        guild_id: u64,
+        role_id: u64,
+    ) -> anyhow::Result<bool> {
+        let result = sqlx::query(include_str!("../sql/rank_roles/delete_rank_role.sql"))
+            .bind(guild_id.to_string())
+            .bind(role_id.to_string())
+            .execute(&context.sql_connection)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn fetch_rank_roles(
+        context: &Context<'_>,
+        guild_id: u64,
+    ) -> anyhow::Result<Vec<RankRoleData>> {
+        let rows = sqlx::query(include_str!("../sql/rank_roles/fetch_rank_roles.sql"))
+            .bind(guild_id.to_string())
+            .fetch_all(&context.sql_connection)
+            .await?;
+
+        Ok(rows.iter().map(RankRoleData::from_row).collect::<Result<_, _>>()?)
+    }
+
+    /// Every guild with at least one rank role.
+    pub async fn fetch_guilds(context: &Context<'_>) -> anyhow::Result<Vec<u64>> {
+        let rows = sqlx::query(include_str!("../sql/rank_roles/fetch_guilds.sql"))
+            .fetch_all(&context.sql_connection)
+            .await?;
+
+        Ok(rows
+            .iter()
+            .filter_map(|row| row.try_get::<String, _>("guild_id").ok()?.parse().ok())
+            .collect())
+    }
+
+    pub async fn insert_audit(
+        context: &Context<'_>,
+        guild_id: u64,
+        discord_id: u64,
+        role_id: u64,
+        added: bool,
+        reason: &str,
+    ) -> anyhow::Result<()> {
+        sqlx::query(include_str!("../sql/rank_roles/insert_audit.sql"))
+            .bind(guild_id.to_string())
+            .bind(discord_id.to_string())
+            .bind(role_id.to_string())
+            .bind(added)
+            .bind(reason)
+            .execute(&context.sql_connection)
+            .await?;
+
+        Ok(())
+    }
+
+    /// The last role changes made in a guild, the most recent first.
+    pub async fn fetch_audit(
+        context: &Context<'_>,
+        guild_id: u64,
+        limit: i64,
+    ) -> anyhow::Result<Vec<RankRoleAuditData>> {
+        let rows = sqlx::query(include_str!("../sql/rank_roles/fetch_audit.sql"))
+            .bind(guild_id.to_string())
+            .bind(limit)
+            .fetch_all(&context.sql_connection)
+            .await?;
+
+        Ok(rows.iter().map(RankRoleAuditData::from_row).collect::<Result<_, _>>()?)
+    }
+}
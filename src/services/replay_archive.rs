@@ -0,0 +1,70 @@
+use sqlx::FromRow;
+
+use crate::{context::Context, models::replay::ReplayData, utils::replay_archive::ReplayMetadata};
+
+/// Most replays listed by a search
+pub const MAX_SEARCH_RESULTS: i64 = 15;
+
+pub struct ReplayArchivePDO;
+impl ReplayArchivePDO {
+    /// Indexes a replay, or returns the id of the replay of the guild with the same hash.
+    pub async fn insert_replay(
+        context: &Context<'_>,
+        guild_id: u64,
+        hash: &str,
+        metadata: &ReplayMetadata,
+        uploader_id: Option<u64>,
+    ) -> anyhow::Result<i32> {
+        let (id,): (i32,) = sqlx::query_as(include_str!("../sql/replays/insert_replay.sql"))
+            .bind(guild_id.to_string())
+            .bind(hash)
+            .bind(&metadata.filename)
+            .bind(&metadata.players)
+            .bind(&metadata.ts)
+            .bind(metadata.rounds as i32)
+            .bind(&metadata.result)
+            .bind(uploader_id.map(|id| id.to_string()))
+            .fetch_one(&context.sql_connection)
+            .await?;
+
+        Ok(id)
+    }
+
+    /// A replay archived in the guild.
+    pub async fn fetch_replay_by_id(
+        context: &Context<'_>,
+        guild_id: u64,
+        id: i32,
+    ) -> anyhow::Result<Option<ReplayData>> {
+        let row = sqlx::query(include_str!("../sql/replays/fetch_replay_by_id.sql"))
+            .bind(id)
+            .bind(guild_id.to_string())
+            .fetch_optional(&context.sql_connection)
+            .await?;
+
+        Ok(row.map(|row| ReplayData::from_row(&row)).transpose()?)
+    }
+
+    /// Latest replays of the guild matching every given filter, the date being `YYYY-MM-DD`.
+    pub async fn search_replays(
+        context: &Context<'_>,
+        guild_id: u64,
+        player: Option<&str>,
+        opponent: Option<&str>,
+        date: Option<&str>,
+    ) -> anyhow::Result<Vec<ReplayData>> {
+        let rows = sqlx::query(include_str!("../sql/replays/search_replays.sql"))
+            .bind(guild_id.to_string())
+            .bind(player.map(str::to_lowercase))
+            .bind(opponent.map(str::to_lowercase))
+            .bind(date)
+            .bind(MAX_SEARCH_RESULTS)
+            .fetch_all(&context.sql_connection)
+            .await?;
+
+        Ok(rows
+            .iter()
+            .map(ReplayData::from_row)
+            .collect::<Result<Vec<_>, _>>()?)
+    }
+}
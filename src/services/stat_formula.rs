@@ -0,0 +1,69 @@
+use sqlx::FromRow;
+
+use crate::{context::Context, models::stat_formula::StatFormulaData};
+
+pub struct StatFormulaPDO;
+impl StatFormulaPDO {
+    pub async fn upsert_formula(
+        context: &Context<'_>,
+        guild_id: u64,
+        name: &str,
+        expression: &str,
+        author_id: u64,
+    ) -> anyhow::Result<()> {
+        sqlx::query(include_str!("../sql/stat_formulas/upsert_formula.sql"))
+            .bind(guild_id.to_string())
+            .bind(name)
+            .bind(expression)
+            .bind(author_id.to_string())
+            .execute(&context.sql_connection)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn delete_formula(
+        context: &Context<'_>,
+        guild_id: u64,
+        name: &str,
+    ) -> anyhow::Result<bool> {
+        let result = sqlx::query(include_str!("../sql/stat_formulas/delete_formula.sql"))
+            .bind(guild_id.to_string())
+            .bind(name)
+            .execute(&context.sql_connection)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn fetch_formula_by_name(
+        context: &Context<'_>,
+        guild_id: u64,
+        name: &str,
+    ) -> anyhow::Result<Option<StatFormulaData>> {
+        let row = sqlx::query(include_str!("../sql/stat_formulas/fetch_formula_by_name.sql"))
+            .bind(guild_id.to_string())
+            .bind(name)
+            .fetch_optional(&context.sql_connection)
+            .await?;
+
+        Ok(row.map(|row| StatFormulaData::from_row(&row)).transpose()?)
+    }
+
+    pub async fn fetch_formulas(
+        context: &Context<'_>,
+        guild_id: u64,
+    ) -> Vec<StatFormulaData> {
+        let Ok(formulas) = sqlx::query(include_str!("../sql/stat_formulas/fetch_formulas.sql"))
+            .bind(guild_id.to_string())
+            .fetch_all(&context.sql_connection)
+            .await else {
+                return vec![]
+            };
+
+        formulas
+            .into_iter()
+            .filter_map(|row| StatFormulaData::from_row(&row).ok())
+            .collect()
+    }
+}
@@ -0,0 +1,106 @@
+use sqlx::FromRow;
+
+use crate::{
+    context::Context,
+    models::tetrio_link::{TetrioLinkData, TetrioLinkRequestData},
+};
+
+pub struct TetrioLinkPDO;
+impl TetrioLinkPDO {
+    /// Links the accounts, `false` when the tetrio account is already linked to another discord account.
+    pub async fn upsert_link(
+        context: &Context<'_>,
+        discord_id: u64,
+        tetrio_id: &str,
+        username: &str,
+    ) -> anyhow::Result<bool> {
+        let result = sqlx::query(include_str!("../sql/tetrio_links/upsert_link.sql"))
+            .bind(discord_id.to_string())
+            .bind(tetrio_id)
+            .bind(username)
+            .execute(&context.sql_connection)
+            .await;
+
+        match result {
+            Ok(_) => Ok(true),
+            Err(sqlx::Error::Database(err)) if err.is_unique_violation() => Ok(false),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    pub async fn fetch_link(
+        context: &Context<'_>,
+        discord_id: u64,
+    ) -> anyhow::Result<Option<TetrioLinkData>> {
+        let row = sqlx::query(include_str!("../sql/tetrio_links/fetch_link.sql"))
+            .bind(discord_id.to_string())
+            .fetch_optional(&context.sql_connection)
+            .await?;
+
+        Ok(row.map(|row| TetrioLinkData::from_row(&row)).transpose()?)
+    }
+
+    /// Every linked account, to keep the roles of linked members up to date.
+    pub async fn fetch_links(context: &Context<'_>) -> anyhow::Result<Vec<TetrioLinkData>> {
+        let rows = sqlx::query(include_str!("../sql/tetrio_links/fetch_links.sql"))
+            .fetch_all(&context.sql_connection)
+            .await?;
+
+        Ok(rows.iter().map(TetrioLinkData::from_row).collect::<Result<_, _>>()?)
+    }
+
+    pub async fn delete_link(
+        context: &Context<'_>,
+        discord_id: u64,
+    ) -> anyhow::Result<bool> {
+        let result = sqlx::query(include_str!("../sql/tetrio_links/delete_link.sql"))
+            .bind(discord_id.to_string())
+            .execute(&context.sql_connection)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn upsert_request(
+        context: &Context<'_>,
+        discord_id: u64,
+        tetrio_id: &str,
+        username: &str,
+        code: &str,
+    ) -> anyhow::Result<()> {
+        sqlx::query(include_str!("../sql/tetrio_links/upsert_request.sql"))
+            .bind(discord_id.to_string())
+            .bind(tetrio_id)
+            .bind(username)
+            .bind(code)
+            .execute(&context.sql_connection)
+            .await?;
+
+        Ok(())
+    }
+
+    /// The pending request of a discord user, requests expire after an hour.
+    pub async fn fetch_request(
+        context: &Context<'_>,
+        discord_id: u64,
+    ) -> anyhow::Result<Option<TetrioLinkRequestData>> {
+        let row = sqlx::query(include_str!("../sql/tetrio_links/fetch_request.sql"))
+            .bind(discord_id.to_string())
+            .fetch_optional(&context.sql_connection)
+            .await?;
+
+        Ok(row.map(|row| TetrioLinkRequestData::from_row(&row)).transpose()?)
+    }
+
+    pub async fn delete_request(
+        context: &Context<'_>,
+        discord_id: u64,
+    ) -> anyhow::Result<()> {
+        sqlx::query(include_str!("../sql/tetrio_links/delete_request.sql"))
+            .bind(discord_id.to_string())
+            .execute(&context.sql_connection)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,29 @@
+CREATE TABLE IF NOT EXISTS announcement_channels (
+	"guild_id" VARCHAR(32) NOT NULL,
+	"channel_id" VARCHAR(32) NOT NULL,
+	PRIMARY KEY ("guild_id")
+);
+
+CREATE TABLE IF NOT EXISTS tracked_players (
+	"guild_id" VARCHAR(32) NOT NULL,
+	"tetrio_id" VARCHAR(32) NOT NULL,
+	"username" VARCHAR(32) NOT NULL,
+	PRIMARY KEY ("guild_id", "tetrio_id")
+);
+
+CREATE TABLE IF NOT EXISTS player_snapshots (
+	"tetrio_id" VARCHAR(32) NOT NULL,
+	"rank" VARCHAR(8) NULL,
+	"tr" DOUBLE PRECISION NULL,
+	"games_played" BIGINT NULL,
+	"sprint_time" DOUBLE PRECISION NULL,
+	"blitz_score" BIGINT NULL,
+	"updated_at" TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+	PRIMARY KEY ("tetrio_id")
+);
+
+CREATE TABLE IF NOT EXISTS announcement_preferences (
+	"discord_id" VARCHAR(32) NOT NULL,
+	"enabled" BOOLEAN NOT NULL,
+	PRIMARY KEY ("discord_id")
+);
@@ -0,0 +1,2 @@
+DELETE FROM announcement_channels
+WHERE guild_id = $1;
@@ -0,0 +1,2 @@
+DELETE FROM tracked_players
+WHERE guild_id = $1 AND LOWER(username) = LOWER($2);
@@ -0,0 +1,3 @@
+SELECT guild_id, channel_id
+FROM announcement_channels
+WHERE guild_id = $1;
@@ -0,0 +1,2 @@
+SELECT guild_id, channel_id
+FROM announcement_channels;
@@ -0,0 +1,4 @@
+SELECT guild_id, tetrio_id, username
+FROM tracked_players
+WHERE guild_id = $1
+ORDER BY username;
@@ -0,0 +1,3 @@
+SELECT discord_id
+FROM announcement_preferences
+WHERE enabled = FALSE;
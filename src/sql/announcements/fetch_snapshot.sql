@@ -0,0 +1,3 @@
+SELECT tetrio_id, rank, tr, games_played, sprint_time, blitz_score
+FROM player_snapshots
+WHERE tetrio_id = $1;
@@ -0,0 +1,2 @@
+SELECT guild_id, tetrio_id, username
+FROM tracked_players;
@@ -0,0 +1,6 @@
+INSERT INTO announcement_channels
+(guild_id, channel_id)
+VALUES
+($1, $2)
+ON CONFLICT (guild_id)
+DO UPDATE SET channel_id = $2;
@@ -0,0 +1,6 @@
+INSERT INTO announcement_preferences
+(discord_id, enabled)
+VALUES
+($1, $2)
+ON CONFLICT (discord_id)
+DO UPDATE SET enabled = $2;
@@ -0,0 +1,6 @@
+INSERT INTO player_snapshots
+(tetrio_id, rank, tr, games_played, sprint_time, blitz_score)
+VALUES
+($1, $2, $3, $4, $5, $6)
+ON CONFLICT (tetrio_id)
+DO UPDATE SET rank = $2, tr = $3, games_played = $4, sprint_time = $5, blitz_score = $6, updated_at = NOW();
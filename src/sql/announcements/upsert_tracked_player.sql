@@ -0,0 +1,6 @@
+INSERT INTO tracked_players
+(guild_id, tetrio_id, username)
+VALUES
+($1, $2, $3)
+ON CONFLICT (guild_id, tetrio_id)
+DO UPDATE SET username = $3;
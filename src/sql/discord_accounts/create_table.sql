@@ -0,0 +1,6 @@
+CREATE TABLE IF NOT EXISTS discord_account_cache (
+	"discord_id" VARCHAR(32) NOT NULL,
+	"tetrio_id" VARCHAR(32) NULL,
+	"checked_at" TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+	PRIMARY KEY ("discord_id")
+);
@@ -0,0 +1,4 @@
+SELECT discord_id, tetrio_id
+FROM discord_account_cache
+WHERE discord_id = ANY($1)
+AND checked_at > NOW() - INTERVAL '7 days';
@@ -0,0 +1,6 @@
+INSERT INTO discord_account_cache
+(discord_id, tetrio_id)
+VALUES
+($1, $2)
+ON CONFLICT (discord_id)
+DO UPDATE SET tetrio_id = $2, checked_at = NOW();
@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS leaderboard_exports (
+	"guild_id" VARCHAR(32) NOT NULL,
+	"channel_id" VARCHAR(32) NOT NULL,
+	"format" VARCHAR(8) NOT NULL,
+	"rank" VARCHAR(8) NULL,
+	"country" VARCHAR(8) NULL,
+	"last_exported_at" TIMESTAMPTZ NULL,
+	PRIMARY KEY ("channel_id")
+);
@@ -0,0 +1,2 @@
+DELETE FROM leaderboard_exports
+WHERE guild_id = $1 AND channel_id = $2;
@@ -0,0 +1,3 @@
+SELECT guild_id, channel_id, format, rank, country
+FROM leaderboard_exports
+WHERE last_exported_at IS NULL OR last_exported_at <= NOW() - INTERVAL '1 day';
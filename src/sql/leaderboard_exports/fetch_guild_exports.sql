@@ -0,0 +1,4 @@
+SELECT guild_id, channel_id, format, rank, country
+FROM leaderboard_exports
+WHERE guild_id = $1
+ORDER BY channel_id;
@@ -0,0 +1,3 @@
+UPDATE leaderboard_exports
+SET last_exported_at = NOW()
+WHERE channel_id = $1;
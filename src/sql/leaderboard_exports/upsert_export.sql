@@ -0,0 +1,6 @@
+INSERT INTO leaderboard_exports
+(guild_id, channel_id, format, rank, country)
+VALUES
+($1, $2, $3, $4, $5)
+ON CONFLICT (channel_id)
+DO UPDATE SET format = $3, rank = $4, country = $5;
@@ -0,0 +1,14 @@
+CREATE TABLE IF NOT EXISTS match_feed_subscriptions (
+	"guild_id" VARCHAR(32) NOT NULL,
+	"channel_id" VARCHAR(32) NOT NULL,
+	"tetrio_id" VARCHAR(32) NOT NULL,
+	"username" VARCHAR(32) NOT NULL,
+	PRIMARY KEY ("channel_id", "tetrio_id")
+);
+
+CREATE TABLE IF NOT EXISTS match_feed_posts (
+	"channel_id" VARCHAR(32) NOT NULL,
+	"replay_id" VARCHAR(64) NOT NULL,
+	"posted_at" TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+	PRIMARY KEY ("channel_id", "replay_id")
+);
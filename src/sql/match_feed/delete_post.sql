@@ -0,0 +1,2 @@
+DELETE FROM match_feed_posts
+WHERE channel_id = $1 AND replay_id = $2;
@@ -0,0 +1,2 @@
+DELETE FROM match_feed_subscriptions
+WHERE guild_id = $1 AND LOWER(username) = LOWER($2);
@@ -0,0 +1,4 @@
+SELECT guild_id, channel_id, tetrio_id, username
+FROM match_feed_subscriptions
+WHERE guild_id = $1
+ORDER BY channel_id, username;
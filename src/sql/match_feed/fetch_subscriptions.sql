@@ -0,0 +1,2 @@
+SELECT guild_id, channel_id, tetrio_id, username
+FROM match_feed_subscriptions;
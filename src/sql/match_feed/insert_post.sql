@@ -0,0 +1,6 @@
+INSERT INTO match_feed_posts
+(channel_id, replay_id)
+VALUES
+($1, $2)
+ON CONFLICT (channel_id, replay_id)
+DO NOTHING;
@@ -0,0 +1,6 @@
+INSERT INTO match_feed_subscriptions
+(guild_id, channel_id, tetrio_id, username)
+VALUES
+($1, $2, $3, $4)
+ON CONFLICT (channel_id, tetrio_id)
+DO UPDATE SET username = $4;
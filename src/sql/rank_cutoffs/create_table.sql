@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS rank_cutoffs (
+	"rank" VARCHAR(8) NOT NULL,
+	"tr" DOUBLE PRECISION NOT NULL,
+	"target_tr" DOUBLE PRECISION NOT NULL,
+	"percentile" DOUBLE PRECISION NOT NULL,
+	"players" BIGINT NOT NULL,
+	"taken_at" TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+	PRIMARY KEY ("rank", "taken_at")
+);
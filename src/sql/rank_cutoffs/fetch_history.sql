@@ -0,0 +1,4 @@
+SELECT rank, tr, target_tr, percentile, players, EXTRACT(EPOCH FROM taken_at)::BIGINT AS taken_at
+FROM rank_cutoffs
+WHERE rank = $1 AND taken_at >= NOW() - MAKE_INTERVAL(days => $2)
+ORDER BY taken_at;
@@ -0,0 +1,4 @@
+SELECT DISTINCT ON (rank) rank, tr, target_tr, percentile, players, EXTRACT(EPOCH FROM taken_at)::BIGINT AS taken_at
+FROM rank_cutoffs
+WHERE taken_at <= NOW() - MAKE_INTERVAL(hours => $1)
+ORDER BY rank, taken_at DESC;
@@ -0,0 +1,4 @@
+INSERT INTO rank_cutoffs
+(rank, tr, target_tr, percentile, players)
+VALUES
+($1, $2, $3, $4, $5);
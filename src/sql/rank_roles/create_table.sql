@@ -0,0 +1,22 @@
+CREATE TABLE IF NOT EXISTS rank_roles (
+	"guild_id" VARCHAR(32) NOT NULL,
+	"role_id" VARCHAR(32) NOT NULL,
+	"rank" VARCHAR(8) NULL,
+	"min_tr" DOUBLE PRECISION NULL,
+	"max_tr" DOUBLE PRECISION NULL,
+	"country" VARCHAR(8) NULL,
+	PRIMARY KEY ("guild_id", "role_id")
+);
+
+CREATE TABLE IF NOT EXISTS rank_role_audit (
+	"id_rank_role_audit" SERIAL,
+	"guild_id" VARCHAR(32) NOT NULL,
+	"discord_id" VARCHAR(32) NOT NULL,
+	"role_id" VARCHAR(32) NOT NULL,
+	"added" BOOLEAN NOT NULL,
+	"reason" VARCHAR(200) NOT NULL,
+	"created_at" TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+	PRIMARY KEY ("id_rank_role_audit")
+);
+
+CREATE INDEX IF NOT EXISTS "rank_role_audit_guild" ON rank_role_audit("guild_id", "created_at");
@@ -0,0 +1,2 @@
+DELETE FROM rank_roles
+WHERE guild_id = $1 AND role_id = $2;
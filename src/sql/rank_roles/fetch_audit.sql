@@ -0,0 +1,5 @@
+SELECT discord_id, role_id, added, reason, TO_CHAR(created_at, 'YYYY-MM-DD HH24:MI') AS created_at
+FROM rank_role_audit
+WHERE guild_id = $1
+ORDER BY created_at DESC
+LIMIT $2;
@@ -0,0 +1,2 @@
+SELECT DISTINCT guild_id
+FROM rank_roles;
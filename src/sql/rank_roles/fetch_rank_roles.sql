@@ -0,0 +1,4 @@
+SELECT guild_id, role_id, rank, min_tr, max_tr, country
+FROM rank_roles
+WHERE guild_id = $1
+ORDER BY min_tr DESC NULLS LAST, rank, country;
@@ -0,0 +1,4 @@
+INSERT INTO rank_role_audit
+(guild_id, discord_id, role_id, added, reason)
+VALUES
+($1, $2, $3, $4, $5);
@@ -0,0 +1,6 @@
+INSERT INTO rank_roles
+(guild_id, role_id, rank, min_tr, max_tr, country)
+VALUES
+($1, $2, $3, $4, $5, $6)
+ON CONFLICT (guild_id, role_id)
+DO UPDATE SET rank = $3, min_tr = $4, max_tr = $5, country = $6;
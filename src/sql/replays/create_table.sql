@@ -0,0 +1,16 @@
+CREATE TABLE IF NOT EXISTS replays (
+	"id_replay" SERIAL,
+	"guild_id" VARCHAR(32) NOT NULL,
+	"hash" CHAR(64) NOT NULL,
+	"filename" VARCHAR(200) NOT NULL,
+	"players" VARCHAR(32)[] NOT NULL DEFAULT '{}',
+	"ts" TIMESTAMPTZ NULL,
+	"rounds" INTEGER NOT NULL,
+	"result" VARCHAR(200) NULL,
+	"uploader_id" VARCHAR(32) NULL,
+	"uploaded_at" TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+	PRIMARY KEY ("id_replay")
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS "unique_guild_replay_hash" ON replays("guild_id", "hash");
+CREATE INDEX IF NOT EXISTS "replay_players" ON replays USING GIN ("players");
@@ -0,0 +1,5 @@
+SELECT
+id_replay, hash, filename, players, TO_CHAR(ts, 'YYYY-MM-DD HH24:MI') AS ts, rounds, result
+FROM replays
+WHERE id_replay = $1
+AND guild_id = $2;
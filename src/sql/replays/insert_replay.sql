@@ -0,0 +1,7 @@
+INSERT INTO replays
+(guild_id, hash, filename, players, ts, rounds, result, uploader_id)
+VALUES
+($1, $2, $3, $4, $5::timestamptz, $6, $7, $8)
+ON CONFLICT (guild_id, hash)
+DO UPDATE SET hash = EXCLUDED.hash
+RETURNING id_replay;
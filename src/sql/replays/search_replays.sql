@@ -0,0 +1,9 @@
+SELECT
+id_replay, hash, filename, players, TO_CHAR(ts, 'YYYY-MM-DD HH24:MI') AS ts, rounds, result
+FROM replays
+WHERE guild_id = $1
+AND ($2::VARCHAR IS NULL OR $2 = ANY(players))
+AND ($3::VARCHAR IS NULL OR $3 = ANY(players))
+AND ($4::DATE IS NULL OR COALESCE(ts, uploaded_at)::DATE = $4::DATE)
+ORDER BY COALESCE(ts, uploaded_at) DESC
+LIMIT $5;
@@ -0,0 +1,10 @@
+CREATE TABLE IF NOT EXISTS stat_formulas (
+	"id_stat_formula" SERIAL,
+	"guild_id" VARCHAR(32) NOT NULL,
+	"name" VARCHAR(32) NOT NULL,
+	"expression" VARCHAR(200) NOT NULL,
+	"author_id" VARCHAR(32) NOT NULL,
+	PRIMARY KEY ("id_stat_formula")
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS "unique_stat_formula_name" ON stat_formulas("guild_id", "name");
@@ -0,0 +1,3 @@
+DELETE FROM stat_formulas
+WHERE guild_id = $1
+AND name = $2;
@@ -0,0 +1,4 @@
+SELECT id_stat_formula, guild_id, name, expression, author_id
+FROM stat_formulas
+WHERE guild_id = $1
+AND name = $2;
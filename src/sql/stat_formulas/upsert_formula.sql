@@ -0,0 +1,7 @@
+INSERT INTO stat_formulas
+(guild_id, name, expression, author_id)
+VALUES
+($1, $2, $3, $4)
+ON CONFLICT (guild_id, name)
+DO UPDATE SET expression = $3, author_id = $4
+RETURNING id_stat_formula;
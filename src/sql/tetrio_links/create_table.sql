@@ -0,0 +1,18 @@
+CREATE TABLE IF NOT EXISTS tetrio_links (
+	"discord_id" VARCHAR(32) NOT NULL,
+	"tetrio_id" VARCHAR(32) NOT NULL,
+	"username" VARCHAR(32) NOT NULL,
+	"linked_at" TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+	PRIMARY KEY ("discord_id")
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS "unique_tetrio_link" ON tetrio_links("tetrio_id");
+
+CREATE TABLE IF NOT EXISTS tetrio_link_requests (
+	"discord_id" VARCHAR(32) NOT NULL,
+	"tetrio_id" VARCHAR(32) NOT NULL,
+	"username" VARCHAR(32) NOT NULL,
+	"code" VARCHAR(32) NOT NULL,
+	"created_at" TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+	PRIMARY KEY ("discord_id")
+);
@@ -0,0 +1,2 @@
+DELETE FROM tetrio_links
+WHERE discord_id = $1;
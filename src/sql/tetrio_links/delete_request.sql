@@ -0,0 +1,2 @@
+DELETE FROM tetrio_link_requests
+WHERE discord_id = $1;
@@ -0,0 +1,3 @@
+SELECT discord_id, tetrio_id, username
+FROM tetrio_links
+WHERE discord_id = $1;
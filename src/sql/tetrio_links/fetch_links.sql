@@ -0,0 +1,2 @@
+SELECT discord_id, tetrio_id, username
+FROM tetrio_links;
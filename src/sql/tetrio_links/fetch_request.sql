@@ -0,0 +1,4 @@
+SELECT discord_id, tetrio_id, username, code
+FROM tetrio_link_requests
+WHERE discord_id = $1
+AND created_at > NOW() - INTERVAL '1 hour';
@@ -0,0 +1,6 @@
+INSERT INTO tetrio_links
+(discord_id, tetrio_id, username)
+VALUES
+($1, $2, $3)
+ON CONFLICT (discord_id)
+DO UPDATE SET tetrio_id = $2, username = $3, linked_at = NOW();
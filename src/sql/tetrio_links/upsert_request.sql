@@ -0,0 +1,6 @@
+INSERT INTO tetrio_link_requests
+(discord_id, tetrio_id, username, code)
+VALUES
+($1, $2, $3, $4)
+ON CONFLICT (discord_id)
+DO UPDATE SET tetrio_id = $2, username = $3, code = $4, created_at = NOW();
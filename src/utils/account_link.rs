@@ -0,0 +1,88 @@
+#![cfg(all(feature = "tetrio", feature = "database"))]
+//! Verification of the tetrio accounts linked to discord users.
+//!
+//! A user asks to link an account, gets a code to put in the bio of that account, then the verifier checks
+//! that the code is there before the link is saved.
+
+use std::time::Duration;
+
+use anyhow::anyhow;
+use rand::{distributions::Alphanumeric, Rng};
+use serde_json::Value;
+
+const USERS_URL: &str = "https://ch.tetr.io/api/users";
+const TIMEOUT: Duration = Duration::from_secs(15);
+const CODE_LENGTH: usize = 8;
+
+#[async_trait::async_trait]
+pub trait LinkVerifier: Send + Sync {
+    /// Whether the tetrio account proves that it belongs to the person who got the code.
+    async fn verify(&self, tetrio_id: &str, code: &str) -> anyhow::Result<bool>;
+}
+
+/// Builds the verifier from the `LINK_VERIFIER` environment variable, `trust` accepts every link and
+/// anything else checks the bio of the account.
+pub fn from_env() -> Box<dyn LinkVerifier> {
+    match std::env::var("LINK_VERIFIER").map(|verifier| verifier.to_lowercase()).as_deref() {
+        Ok("trust") => {
+            log::warn!("Tetrio links are not verified");
+            Box::new(TrustVerifier)
+        }
+        _ => Box::new(BioVerifier::new().expect("Couldn't build the link verifier")),
+    }
+}
+
+pub fn generate_code() -> String {
+    let code: String = rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(CODE_LENGTH)
+        .map(char::from)
+        .collect();
+    format!("taka-{}", code.to_lowercase())
+}
+
+/// Looks for the code in the bio of the tetrio account.
+pub struct BioVerifier {
+    client: reqwest::Client,
+}
+
+impl BioVerifier {
+    pub fn new() -> reqwest::Result<Self> {
+        Ok(Self {
+            client: reqwest::Client::builder().timeout(TIMEOUT).build()?,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl LinkVerifier for BioVerifier {
+    async fn verify(&self, tetrio_id: &str, code: &str) -> anyhow::Result<bool> {
+        let response: Value = self
+            .client
+            .get(format!("{USERS_URL}/{tetrio_id}"))
+            .send()
+            .await?
+            .json()
+            .await?;
+
+        if let Some(error) = response.get("error") {
+            return Err(anyhow!("couldn't fetch the bio of {tetrio_id}: {error}"));
+        }
+
+        Ok(response
+            .pointer("/data/bio")
+            .or_else(|| response.pointer("/data/user/bio"))
+            .and_then(Value::as_str)
+            .is_some_and(|bio| bio.contains(code)))
+    }
+}
+
+/// Accepts every link, to run the bot without checking tetrio bios.
+pub struct TrustVerifier;
+
+#[async_trait::async_trait]
+impl LinkVerifier for TrustVerifier {
+    async fn verify(&self, _tetrio_id: &str, _code: &str) -> anyhow::Result<bool> {
+        Ok(true)
+    }
+}
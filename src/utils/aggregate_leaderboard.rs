@@ -0,0 +1,106 @@
+#![cfg(feature = "tetrio")]
+//! Groups of players of the tetra league leaderboard, by country or by rank, for aggregate leaderboards.
+
+use std::collections::HashMap;
+
+use tetrio_api::models::users::{user_leaderboard::LeaderboardUser, user_rank::UserRank};
+
+/// Ranks from the highest to the lowest
+pub const RANKS: [UserRank; 18] = [
+    UserRank::XPlus,
+    UserRank::X,
+    UserRank::U,
+    UserRank::SS,
+    UserRank::SPlus,
+    UserRank::S,
+    UserRank::SMinus,
+    UserRank::APlus,
+    UserRank::A,
+    UserRank::AMinus,
+    UserRank::BPlus,
+    UserRank::B,
+    UserRank::BMinus,
+    UserRank::CPlus,
+    UserRank::C,
+    UserRank::CMinus,
+    UserRank::DPlus,
+    UserRank::D,
+];
+
+/// The rank written as `UserRank::to_string` does.
+pub fn parse_rank(value: &str) -> Option<UserRank> {
+    RANKS.into_iter().find(|rank| rank.to_string() == value)
+}
+
+/// Players of the leaderboard sharing a country or a rank, with their position in the leaderboard.
+pub struct PlayerGroup<'a> {
+    pub name: String,
+    pub players: Vec<(usize, &'a LeaderboardUser)>,
+}
+
+impl PlayerGroup<'_> {
+    pub fn x_ranks(&self) -> usize {
+        self.players
+            .iter()
+            .filter(|(_, user)| matches!(user.league.rank, Some(UserRank::X | UserRank::XPlus)))
+            .count()
+    }
+}
+
+/// Groups the players by country, players without a country are left out.
+pub fn by_country(data: &[LeaderboardUser]) -> Vec<PlayerGroup<'_>> {
+    let mut countries: HashMap<String, Vec<(usize, &LeaderboardUser)>> = HashMap::new();
+    for (position, user) in data.iter().enumerate() {
+        let Some(country) = user.country.as_deref() else {
+            continue;
+        };
+        countries.entry(country.to_uppercase()).or_default().push((position, user));
+    }
+
+    countries
+        .into_iter()
+        .map(|(name, players)| PlayerGroup { name, players })
+        .collect()
+}
+
+/// Groups the players by rank, from the highest rank, leaving out the ranks without players.
+pub fn by_rank(data: &[LeaderboardUser]) -> Vec<PlayerGroup<'_>> {
+    RANKS
+        .iter()
+        .map(|rank| PlayerGroup {
+            name: rank.to_string(),
+            players: data
+                .iter()
+                .enumerate()
+                .filter(|(_, user)| user.league.rank.as_ref() == Some(rank))
+                .collect(),
+        })
+        .filter(|group| !group.players.is_empty())
+        .collect()
+}
+
+pub fn mean(values: &[f64]) -> Option<f64> {
+    (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64)
+}
+
+pub fn median(values: &[f64]) -> Option<f64> {
+    let mut values = values.to_vec();
+    values.sort_by(f64::total_cmp);
+
+    let middle = values.len() / 2;
+    match values.len() {
+        0 => None,
+        len if len % 2 == 0 => Some((values[middle - 1] + values[middle]) / 2.0),
+        _ => Some(values[middle]),
+    }
+}
+
+/// Rounds the highest value of a chart up to a readable edge.
+pub fn chart_max(max: f64) -> f64 {
+    if max <= 0.0 || !max.is_finite() {
+        return 1.0;
+    }
+
+    let magnitude = 10f64.powf(max.log10().floor());
+    (max / magnitude).ceil() * magnitude
+}
@@ -0,0 +1,307 @@
+#![cfg(all(feature = "tetrio", feature = "database"))]
+//! Announcements of the progress of tracked and linked players.
+//!
+//! A poller takes a snapshot of every watched player, compares it with the one of the previous poll and posts
+//! the rank changes, TR milestones, personal bests and games played milestones in the announcement channel of
+//! each guild the player belongs to. The first snapshot of a player is only stored.
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    time::Duration,
+};
+
+use serde_json::Value;
+use twilight_model::{channel::message::Embed, id::Id};
+use twilight_util::builder::embed::ImageSource;
+
+use crate::{
+    context::Context,
+    models::announcement::PlayerSnapshotData,
+    services::{announcement::AnnouncementPDO, tetrio_link::TetrioLinkPDO},
+};
+
+use super::{
+    create_embed::create_embed,
+    download::url_with_segments,
+    scheduled_job::{interval_from_env, ScheduledJob},
+    solo_replay::{format_duration, SoloStats},
+};
+
+const SUMMARIES_URL: &str = "https://ch.tetr.io/api/users";
+const TIMEOUT: Duration = Duration::from_secs(15);
+/// Wait between two players, the tetra channel api asks to keep a low request rate
+const POLL_DELAY: Duration = Duration::from_secs(1);
+const DEFAULT_POLL_INTERVAL_MINUTES: u64 = 30;
+const TR_MILESTONE_STEP: f64 = 1000.0;
+const GAMES_MILESTONES: [i64; 9] = [100, 250, 500, 1000, 2500, 5000, 10000, 25000, 50000];
+/// Tetra league ranks from the lowest to the highest
+const RANKS: [&str; 18] = [
+    "d", "d+", "c-", "c", "c+", "b-", "b", "b+", "a-", "a", "a+", "s-", "s", "s+", "ss", "u", "x", "x+",
+];
+
+/// Something worth announcing between two snapshots of a player.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Milestone {
+    RankChange { from: Option<String>, to: String },
+    Tr(f64),
+    SprintBest { previous: Option<f64>, time: f64 },
+    BlitzBest { previous: Option<i64>, score: i64 },
+    GamesPlayed(i64),
+}
+
+fn rank_index(rank: &str) -> Option<usize> {
+    RANKS.iter().position(|known| known.eq_ignore_ascii_case(rank))
+}
+
+/// What changed between the previous and the new snapshot of a player.
+pub fn diff(previous: &PlayerSnapshotData, current: &PlayerSnapshotData) -> Vec<Milestone> {
+    let mut milestones = vec![];
+
+    if let Some(rank) = &current.rank {
+        if previous.rank.as_deref() != Some(rank.as_str()) {
+            milestones.push(Milestone::RankChange {
+                from: previous.rank.clone(),
+                to: rank.clone(),
+            });
+        }
+    }
+
+    if let (Some(previous), Some(current)) = (previous.tr, current.tr) {
+        let milestone = (current / TR_MILESTONE_STEP).floor();
+        if previous >= 0.0 && milestone > (previous / TR_MILESTONE_STEP).floor() {
+            milestones.push(Milestone::Tr(milestone * TR_MILESTONE_STEP));
+        }
+    }
+
+    match (previous.sprint_time, current.sprint_time) {
+        (previous, Some(time)) if previous.map_or(true, |previous| time < previous) => {
+            milestones.push(Milestone::SprintBest { previous, time })
+        }
+        _ => {}
+    }
+    match (previous.blitz_score, current.blitz_score) {
+        (previous, Some(score)) if previous.map_or(true, |previous| score > previous) => {
+            milestones.push(Milestone::BlitzBest { previous, score })
+        }
+        _ => {}
+    }
+
+    if let (Some(previous), Some(current)) = (previous.games_played, current.games_played) {
+        if let Some(milestone) = GAMES_MILESTONES
+            .iter()
+            .rev()
+            .find(|milestone| previous < **milestone && current >= **milestone)
+        {
+            milestones.push(Milestone::GamesPlayed(*milestone));
+        }
+    }
+
+    milestones
+}
+
+impl Milestone {
+    async fn embed(&self, username: &str, context: &Context<'_>) -> anyhow::Result<Embed> {
+        let (color, title, description) = match self {
+            Milestone::RankChange { from, to } => {
+                let promoted = match from.as_deref().and_then(rank_index) {
+                    Some(from) => rank_index(to).is_some_and(|to| to > from),
+                    None => true,
+                };
+                let description = match from {
+                    Some(from) => format!(
+                        "**{username}** went from **{}** to **{}**",
+                        from.to_uppercase(),
+                        to.to_uppercase()
+                    ),
+                    None => format!("**{username}** is now ranked **{}**", to.to_uppercase()),
+                };
+                let title = if promoted { "RANK UP" } else { "RANK DOWN" };
+                (if promoted { 0x4caf50 } else { 0x9e9e9e }, title, description)
+            }
+            Milestone::Tr(tr) => (0xf1c40f, "TR MILESTONE", format!("**{username}** reached **{tr:.0} TR**")),
+            Milestone::SprintBest { previous, time } => {
+                let improvement = previous
+                    .map(|previous| format!(" (-{:.3}s)", previous - time))
+                    .unwrap_or_default();
+                (
+                    0x3498db,
+                    "NEW 40 LINES PERSONAL BEST",
+                    format!("**{username}** finished 40 lines in **{}**{improvement}", format_duration(*time)),
+                )
+            }
+            Milestone::BlitzBest { previous, score } => {
+                let improvement = previous
+                    .map(|previous| format!(" (+{})", score - previous))
+                    .unwrap_or_default();
+                (
+                    0x3498db,
+                    "NEW BLITZ PERSONAL BEST",
+                    format!("**{username}** scored **{score}** in blitz{improvement}"),
+                )
+            }
+            Milestone::GamesPlayed(games) => (
+                0xf1c40f,
+                "GAMES PLAYED MILESTONE",
+                format!("**{username}** played **{games}** tetra league games"),
+            ),
+        };
+
+        let builder = create_embed(Some(color), context)
+            .await?
+            .title(title)
+            .url(format!("https://ch.tetr.io/u/{}", username.to_lowercase()))
+            .description(description);
+        let builder = match self {
+            Milestone::RankChange { to, .. } => {
+                builder.thumbnail(ImageSource::url(format!("https://tetr.io/res/league-ranks/{to}.png"))?)
+            }
+            _ => builder,
+        };
+
+        Ok(builder.build())
+    }
+}
+
+/// Takes a snapshot of a player from the tetra channel api.
+async fn fetch_snapshot(client: &reqwest::Client, tetrio_id: &str) -> anyhow::Result<PlayerSnapshotData> {
+    let response: Value = client
+        .get(url_with_segments(SUMMARIES_URL, &[tetrio_id, "summaries"])?)
+        .send()
+        .await?
+        .json()
+        .await?;
+
+    if let Some(error) = response.get("error") {
+        return Err(anyhow::anyhow!("couldn't fetch the summaries of {tetrio_id}: {error}"));
+    }
+
+    let record = |mode: &str| {
+        response
+            .pointer(&format!("/data/{mode}/record"))
+            .filter(|record| !record.is_null())
+            .and_then(SoloStats::from_record)
+    };
+
+    Ok(PlayerSnapshotData {
+        tetrio_id: tetrio_id.to_string(),
+        rank: response
+            .pointer("/data/league/rank")
+            .and_then(Value::as_str)
+            .filter(|rank| rank_index(rank).is_some())
+            .map(str::to_lowercase),
+        tr: response
+            .pointer("/data/league/tr")
+            .and_then(Value::as_f64)
+            .filter(|tr| *tr >= 0.0),
+        games_played: response.pointer("/data/league/gamesplayed").and_then(Value::as_i64),
+        sprint_time: record("40l").map(|stats| stats.final_time),
+        blitz_score: record("blitz").map(|stats| stats.score as i64),
+    })
+}
+
+async fn post(channel_id: u64, embeds: &[Embed], context: &Context<'_>) -> anyhow::Result<()> {
+    context.http_client.create_message(Id::new(channel_id)).embeds(embeds)?.await?;
+    Ok(())
+}
+
+/// A player watched by the poller and where their progress is announced.
+#[derive(Default)]
+struct WatchedPlayer {
+    username: String,
+    /// Guilds tracking the player
+    guilds: HashSet<u64>,
+    /// Discord account linked to the player, announced in the guilds they are a member of
+    discord_id: Option<u64>,
+}
+
+/// Compares every watched player with their previous snapshot and posts what changed.
+pub async fn poll(context: &Context<'_>) -> anyhow::Result<()> {
+    let channels = AnnouncementPDO::fetch_channels(context)
+        .await?
+        .into_iter()
+        .filter_map(|channel| Some((channel.guild_id.parse::<u64>().ok()?, channel.channel_id.parse::<u64>().ok()?)))
+        .collect::<HashMap<_, _>>();
+    if channels.is_empty() {
+        return Ok(());
+    }
+
+    let opted_out = AnnouncementPDO::fetch_opted_out(context).await?.into_iter().collect::<HashSet<_>>();
+    let mut players: HashMap<String, WatchedPlayer> = HashMap::new();
+    for tracked in AnnouncementPDO::fetch_tracked_players(context).await? {
+        let Ok(guild_id) = tracked.guild_id.parse() else {
+            continue;
+        };
+        let player = players.entry(tracked.tetrio_id).or_default();
+        player.username = tracked.username;
+        player.guilds.insert(guild_id);
+    }
+    for link in TetrioLinkPDO::fetch_links(context).await? {
+        let Ok(discord_id) = link.discord_id.parse() else {
+            continue;
+        };
+        let player = players.entry(link.tetrio_id).or_default();
+        player.username = link.username;
+        player.discord_id = Some(discord_id);
+    }
+    players.retain(|_, player| !player.discord_id.is_some_and(|discord_id| opted_out.contains(&discord_id)));
+
+    let client = reqwest::Client::builder().timeout(TIMEOUT).build()?;
+    for (tetrio_id, player) in players {
+        tokio::time::sleep(POLL_DELAY).await;
+
+        let current = match fetch_snapshot(&client, &tetrio_id).await {
+            Ok(snapshot) => snapshot,
+            Err(err) => {
+                log::warn!("couldn't take a snapshot of {}: {err:?}", player.username);
+                continue;
+            }
+        };
+        let previous = AnnouncementPDO::fetch_snapshot(context, &tetrio_id).await?;
+        AnnouncementPDO::upsert_snapshot(context, &current).await?;
+
+        let Some(previous) = previous else {
+            continue;
+        };
+        let milestones = diff(&previous, &current);
+        if milestones.is_empty() {
+            continue;
+        }
+
+        let mut guilds = player.guilds.clone();
+        if let Some(discord_id) = player.discord_id {
+            for guild_id in channels.keys() {
+                if guilds.contains(guild_id) {
+                    continue;
+                }
+                // the member lists are cached, the guilds aren't asked about every linked player
+                match context.member_cache.members(*guild_id, context).await {
+                    Ok(members) if members.contains(&discord_id) => {
+                        guilds.insert(*guild_id);
+                    }
+                    Ok(_) => {}
+                    Err(err) => log::warn!("couldn't fetch the members of guild {guild_id}: {err:?}"),
+                }
+            }
+        }
+
+        let mut embeds = vec![];
+        for milestone in &milestones {
+            embeds.push(milestone.embed(&player.username, context).await?);
+        }
+
+        for channel_id in guilds.iter().filter_map(|guild_id| channels.get(guild_id)) {
+            if let Err(err) = post(*channel_id, &embeds, context).await {
+                log::warn!("couldn't announce {} in channel {channel_id}: {err:?}", player.username);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// The announcement poller, running every `ANNOUNCEMENT_POLL_MINUTES` minutes.
+pub fn spawn_poller(context: Arc<Context<'static>>) -> ScheduledJob {
+    let interval = interval_from_env("ANNOUNCEMENT_POLL_MINUTES", Duration::from_secs(60), DEFAULT_POLL_INTERVAL_MINUTES);
+    ScheduledJob::spawn("announcement poller", interval, context, |context| async move { poll(&context).await })
+}
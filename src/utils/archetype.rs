@@ -0,0 +1,166 @@
+#![cfg(feature = "tetrio")]
+
+use std::{fmt::Display, sync::Arc};
+
+use tetrio_api::models::users::user_leaderboard::LeaderboardUser;
+
+use crate::context::Context;
+
+use super::{
+    similarity::distribution,
+    stats::{calculate_stats, PlayerStats, Stats},
+};
+
+/// Number of playstyle axes used for the classification: opener, plonk, stride and inf ds.
+const AXES: usize = 4;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Archetype {
+    Balanced,
+    OpenerHeavyAttacker,
+    DownstackFocused,
+    AggressiveDownstacker,
+    Plonker,
+    SpeedPlonker,
+    SpeedStacker,
+}
+
+impl Archetype {
+    pub const ALL: [Archetype; 7] = [
+        Archetype::Balanced,
+        Archetype::OpenerHeavyAttacker,
+        Archetype::DownstackFocused,
+        Archetype::AggressiveDownstacker,
+        Archetype::Plonker,
+        Archetype::SpeedPlonker,
+        Archetype::SpeedStacker,
+    ];
+
+    /// Centroid of the archetype, in standard deviations from the leaderboard mean of opener, plonk, stride and inf ds.
+    fn centroid(&self) -> [f64; AXES] {
+        match self {
+            Archetype::Balanced => [0.0, 0.0, 0.0, 0.0],
+            Archetype::OpenerHeavyAttacker => [1.5, 0.0, -0.5, -0.5],
+            Archetype::DownstackFocused => [-0.5, 0.0, 0.0, 1.5],
+            Archetype::AggressiveDownstacker => [1.0, 0.0, 0.0, 1.0],
+            Archetype::Plonker => [0.0, 1.5, -0.5, 0.0],
+            Archetype::SpeedPlonker => [0.0, 1.0, 1.0, 0.0],
+            Archetype::SpeedStacker => [0.0, -0.5, 1.5, 0.0],
+        }
+    }
+
+    pub fn strengths(&self) -> &'static str {
+        match self {
+            Archetype::Balanced => "No obvious weak spot, adapts to the opponent",
+            Archetype::OpenerHeavyAttacker => "Sends a lot of early pressure and wins races",
+            Archetype::DownstackFocused => "Survives long garbage sequences and cleans cheese efficiently",
+            Archetype::AggressiveDownstacker => "Converts incoming garbage back into attack",
+            Archetype::Plonker => "Very efficient attack for every piece placed",
+            Archetype::SpeedPlonker => "Keeps a good efficiency at a high speed",
+            Archetype::SpeedStacker => "Outpaces opponents with raw speed",
+        }
+    }
+
+    pub fn weaknesses(&self) -> &'static str {
+        match self {
+            Archetype::Balanced => "No stand-out stat to rely on in close games",
+            Archetype::OpenerHeavyAttacker => "Struggles once the opener is blocked or the game goes long",
+            Archetype::DownstackFocused => "Sends less attack, can get out-raced",
+            Archetype::AggressiveDownstacker => "Risky when the garbage gets too messy",
+            Archetype::Plonker => "Slow, vulnerable against fast pressure",
+            Archetype::SpeedPlonker => "Misdrops under pressure can snowball",
+            Archetype::SpeedStacker => "Low attack per piece, relies on speed over efficiency",
+        }
+    }
+}
+
+impl Display for Archetype {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Archetype::Balanced => "Balanced",
+            Archetype::OpenerHeavyAttacker => "Opener-heavy attacker",
+            Archetype::DownstackFocused => "Downstack-focused",
+            Archetype::AggressiveDownstacker => "Aggressive downstacker",
+            Archetype::Plonker => "Plonker",
+            Archetype::SpeedPlonker => "Speed plonker",
+            Archetype::SpeedStacker => "Speed stacker",
+        };
+        write!(f, "{name}")
+    }
+}
+
+fn axes(stats: &Stats) -> Option<[f64; AXES]> {
+    let axes = [stats.opener, stats.plonk, stats.stride, stats.infds];
+    axes.iter().all(|value| value.is_finite()).then_some(axes)
+}
+
+/// Classifies players from the distribution of the playstyle axes on the leaderboard.
+pub struct ArchetypeClassifier {
+    mean: [f64; AXES],
+    deviation: [f64; AXES],
+}
+
+impl ArchetypeClassifier {
+    /// Distribution used when the leaderboard can't be fetched, the playstyle axes are centered around 0.5.
+    pub fn fallback() -> Self {
+        Self {
+            mean: [0.5; AXES],
+            deviation: [0.15; AXES],
+        }
+    }
+
+    pub fn from_leaderboard(users: &[LeaderboardUser]) -> Self {
+        let axes = users
+            .iter()
+            .filter_map(|user| {
+                let (Some(pps), Some(apm), Some(vs)) =
+                    (user.league.pps, user.league.apm, user.league.vs) else {
+                        return None
+                    };
+                axes(&calculate_stats(PlayerStats {
+                    apm,
+                    pps,
+                    vs,
+                    rd: Some(user.league.rd),
+                    tr: Some(user.league.tr),
+                    glicko: Some(user.league.glicko),
+                    rank: user.league.rank.clone(),
+                }))
+            })
+            .collect::<Vec<_>>();
+
+        if axes.is_empty() {
+            return Self::fallback();
+        }
+
+        let (mean, deviation) = distribution(axes.iter());
+        Self { mean, deviation }
+    }
+
+    /// The classifier of the global leaderboard, built again only once the cached leaderboard expires.
+    pub async fn fetch(context: &Context<'_>) -> Arc<Self> {
+        context.leaderboard_cache.archetype_classifier(context).await
+    }
+
+    pub fn classify(&self, stats: &Stats) -> Option<Archetype> {
+        let mut scores = axes(stats)?;
+        for ((score, mean), deviation) in scores.iter_mut().zip(&self.mean).zip(&self.deviation) {
+            *score = (*score - mean) / deviation;
+        }
+
+        Archetype::ALL.into_iter().min_by(|a, b| {
+            let distance = |archetype: &Archetype| {
+                scores
+                    .iter()
+                    .zip(archetype.centroid())
+                    .map(|(score, centroid)| (score - centroid).powi(2))
+                    .sum::<f64>()
+            };
+            distance(a).total_cmp(&distance(b))
+        })
+    }
+
+    pub fn classify_player(&self, stats: PlayerStats) -> Option<Archetype> {
+        self.classify(&calculate_stats(stats))
+    }
+}
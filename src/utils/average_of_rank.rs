@@ -61,97 +61,62 @@ pub async fn average_of_rank(
 
 }
 
-// pub async fn average_of_rank_in_country(
-//     rank: UserRank,
-//     country: String,
-//     context: &Context<'_>,
-// ) -> anyhow::Result<anyhow::Result<(PlayerStatsUnwrapped, usize, f64)>> {
-//     let packet = context
-//         .fetch_full_leaderboard(country.as_deref())
-//         .await?;
-
-//     let  Some(data) = &packet.data else {
-//         return Ok(Err(anyhow!("Couldn't find tetra league data")));
-//     };
-
-//     let stats = data.users.iter().filter_map(|u| {
-//         if let (Some(pps), Some(apm), Some(vs), tr, Some(glicko), Some(rd), user_rank) = (
-//             u.league.pps,
-//             u.league.apm,
-//             u.league.vs,
-//             u.league.rating,
-//             u.league.glicko,
-//             u.league.rd,
-//             u.league.rank.clone(),
-//         ) {
-//             if let Some(rank) = &rank {
-//                 if &u.league.rank == rank {
-//                     Some(PlayerStatsUnwrapped {
-//                         apm,
-//                         pps,
-//                         vs,
-//                         rd,
-//                         tr,
-//                         glicko,
-//                         rank: Some(user_rank),
-//                     })
-//                 } else {
-//                     None
-//                 }
-//             } else {
-//                 Some(PlayerStatsUnwrapped {
-//                     apm,
-//                     pps,
-//                     vs,
-//                     rd,
-//                     tr,
-//                     glicko,
-//                     rank: Some(user_rank),
-//                 })
-//             }
-//         } else {
-//             None
-//         }
-//     });
-
-//     let count = stats.clone().count() as f64;
-//     let mut acc = stats.clone().fold(
-//         PlayerStatsUnwrapped {
-//             apm: 0.0,
-//             pps: 0.0,
-//             vs: 0.0,
-//             rd: 0.0,
-//             tr: 0.0,
-//             glicko: 0.0,
-//             rank: rank.clone(),
-//         },
-//         |mut acc, stats| {
-//             acc.apm += stats.apm;
-//             acc.pps += stats.pps;
-//             acc.vs += stats.vs;
-//             acc.tr += stats.tr;
-//             acc.glicko += stats.glicko;
-//             acc.rd += stats.rd;
-
-//             acc
-//         },
-//     );
-
-//     if count != 0.0 {
-//         acc.apm /= count;
-//         acc.pps /= count;
-//         acc.vs /= count;
-//         acc.tr /= count;
-//         acc.glicko /= count;
-//         acc.rd /= count;
-//     }
-
-//     Ok(Ok((
-//         acc,
-//         count as usize,
-//         stats
-//             .filter(|stats| stats.rd < 65.0)
-//             .map(|a| a.tr)
-//             .fold(f64::INFINITY, |a, b| a.min(b)),
-//     )))
-// }
+/// Average of the players of a country, only those of `rank` when it is given.
+pub async fn average_of_rank_in_country(
+    rank: Option<UserRank>,
+    country: &str,
+    context: &Context<'_>,
+) -> anyhow::Result<anyhow::Result<(PlayerStatsUnwrapped, usize, f64)>> {
+    let Some(leaderboard) = context.leaderboard_cache.leaderboard(Some(country), context).await? else {
+        return Ok(Err(anyhow!("❌ Couldn't find tetra league data")));
+    };
+
+    let stats = leaderboard
+        .iter()
+        .filter(|user| rank.is_none() || user.league.rank == rank)
+        .filter_map(|user| {
+            let (Some(apm), Some(pps), Some(vs)) = (user.league.apm, user.league.pps, user.league.vs) else {
+                return None;
+            };
+            Some(PlayerStatsUnwrapped {
+                apm,
+                pps,
+                vs,
+                rd: user.league.rd,
+                tr: user.league.tr,
+                glicko: user.league.glicko,
+                rank: user.league.rank.clone(),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    if stats.is_empty() {
+        return Ok(Err(anyhow!(
+            "❌ Couldn't find any {}player in {}",
+            rank.as_ref().map(|rank| format!("{rank} ")).unwrap_or_default(),
+            country.to_uppercase()
+        )));
+    }
+
+    let count = stats.len() as f64;
+    let mean = |stat: fn(&PlayerStatsUnwrapped) -> f64| stats.iter().map(stat).sum::<f64>() / count;
+    let lowest_tr = stats
+        .iter()
+        .filter(|stats| stats.rd < 65.0)
+        .map(|stats| stats.tr)
+        .fold(f64::INFINITY, f64::min);
+
+    Ok(Ok((
+        PlayerStatsUnwrapped {
+            apm: mean(|stats| stats.apm),
+            pps: mean(|stats| stats.pps),
+            vs: mean(|stats| stats.vs),
+            rd: mean(|stats| stats.rd),
+            tr: mean(|stats| stats.tr),
+            glicko: mean(|stats| stats.glicko),
+            rank,
+        },
+        stats.len(),
+        lowest_tr,
+    )))
+}
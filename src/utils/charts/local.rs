@@ -0,0 +1,301 @@
+#![cfg(feature = "local_image_generation")]
+
+use std::{f32::consts::PI, io::Cursor};
+
+use ab_glyph::{FontRef, PxScale};
+use image::{ImageFormat, Rgba, RgbaImage};
+use imageproc::{
+    drawing::{
+        draw_filled_rect_mut, draw_hollow_polygon_mut, draw_line_segment_mut, draw_polygon_mut,
+        draw_text_mut, text_size, Blend,
+    },
+    point::Point,
+    rect::Rect,
+};
+
+use super::{font_color, Chart, ChartKind, CHART_HEIGHT, CHART_WIDTH, GRID_STEPS};
+
+/// DejaVu Sans, under the Bitstream Vera license of `assets/DejaVuSans.LICENSE`
+pub const FONT: &[u8] = include_bytes!("../../assets/DejaVuSans.ttf");
+const GRID_COLOR: Rgba<u8> = Rgba([128, 128, 128, 255]);
+const LEGEND_HEIGHT: f32 = 30.0;
+const LEGEND_SCALE: f32 = 16.0;
+const LABEL_SCALE: f32 = 14.0;
+/// Left, right, top and bottom of the plot of bar and line charts
+const PLOT_AREA: (f32, f32, f32, f32) = (48.0, CHART_WIDTH as f32 - 10.0, LEGEND_HEIGHT + 10.0, CHART_HEIGHT as f32 - 36.0);
+/// Labels drawn at most below a line chart
+const MAX_LINE_LABELS: usize = 8;
+
+pub type Canvas = Blend<RgbaImage>;
+
+/// Renders the chart as a transparent png.
+pub fn render(chart: &Chart) -> anyhow::Result<Vec<u8>> {
+    let font = FontRef::try_from_slice(FONT)?;
+    let text_color = parse_color(font_color(chart.dark_mode));
+    let mut canvas = Blend(RgbaImage::new(CHART_WIDTH, CHART_HEIGHT));
+
+    draw_legend(&mut canvas, chart, &font, text_color);
+    match chart.kind {
+        ChartKind::Radar => draw_radar(&mut canvas, chart, &font, text_color),
+        ChartKind::Bar => draw_bar(&mut canvas, chart, &font, text_color),
+        ChartKind::Line => draw_line(&mut canvas, chart, &font, text_color),
+    }
+
+    let mut bytes = Vec::new();
+    canvas.0.write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)?;
+    Ok(bytes)
+}
+
+/// Parses the css colors used by the palettes of the commands, `rgba(r,g,b,a)`, `#rrggbb` or `gray`.
+pub fn parse_color(color: &str) -> Rgba<u8> {
+    let color = color.trim();
+    if let Some(hex) = color.strip_prefix('#') {
+        let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2).unwrap_or("80"), 16).unwrap_or(128);
+        return Rgba([channel(0), channel(2), channel(4), 255]);
+    }
+
+    if let Some(values) = color
+        .strip_prefix("rgba(")
+        .or_else(|| color.strip_prefix("rgb("))
+        .and_then(|values| values.strip_suffix(')'))
+    {
+        let values = values
+            .split(',')
+            .map(|value| value.trim().parse::<f32>().unwrap_or(0.0))
+            .collect::<Vec<_>>();
+        let channel = |i: usize| values.get(i).copied().unwrap_or(0.0).clamp(0.0, 255.0) as u8;
+        let alpha = values.get(3).copied().unwrap_or(1.0).clamp(0.0, 1.0);
+        return Rgba([channel(0), channel(1), channel(2), (alpha * 255.0).round() as u8]);
+    }
+
+    GRID_COLOR
+}
+
+/// Draws possibly multiline text centered on `(x, y)`.
+pub fn draw_centered_text(canvas: &mut Canvas, font: &FontRef, color: Rgba<u8>, scale: f32, x: f32, y: f32, text: &str) {
+    let lines = text.lines().collect::<Vec<_>>();
+    let top = y - lines.len() as f32 * scale / 2.0;
+
+    for (i, line) in lines.into_iter().enumerate() {
+        let (width, _) = text_size(PxScale::from(scale), font, line);
+        draw_text_mut(
+            canvas,
+            color,
+            (x - width as f32 / 2.0) as i32,
+            (top + i as f32 * scale) as i32,
+            PxScale::from(scale),
+            font,
+            line,
+        );
+    }
+}
+
+fn draw_legend(canvas: &mut Canvas, chart: &Chart, font: &FontRef, text_color: Rgba<u8>) {
+    const BOX_SIZE: u32 = 14;
+    const SPACING: u32 = 12;
+
+    let widths = chart
+        .datasets
+        .iter()
+        .map(|dataset| BOX_SIZE + 4 + text_size(PxScale::from(LEGEND_SCALE), font, &dataset.label).0)
+        .collect::<Vec<_>>();
+    let total = widths.iter().sum::<u32>() + SPACING * widths.len().saturating_sub(1) as u32;
+
+    let mut x = CHART_WIDTH.saturating_sub(total) as i32 / 2;
+    for (dataset, width) in chart.datasets.iter().zip(widths) {
+        draw_filled_rect_mut(
+            canvas,
+            Rect::at(x, 8).of_size(BOX_SIZE, BOX_SIZE),
+            parse_color(dataset.color),
+        );
+        draw_text_mut(
+            canvas,
+            text_color,
+            x + BOX_SIZE as i32 + 4,
+            6,
+            PxScale::from(LEGEND_SCALE),
+            font,
+            &dataset.label,
+        );
+        x += (width + SPACING) as i32;
+    }
+}
+
+/// Fraction of the chart covered by a value, slightly overflowing values are still drawn.
+fn ratio(value: f64, max: f64) -> f32 {
+    let max = if max.is_finite() && max > 0.0 { max } else { 1.0 };
+    if value.is_finite() {
+        (value / max).clamp(0.0, 1.1) as f32
+    } else {
+        0.0
+    }
+}
+
+fn draw_radar(canvas: &mut Canvas, chart: &Chart, font: &FontRef, text_color: Rgba<u8>) {
+    let count = chart.labels.len();
+    if count == 0 {
+        return;
+    }
+
+    let center_x = CHART_WIDTH as f32 / 2.0;
+    let center_y = LEGEND_HEIGHT + (CHART_HEIGHT as f32 - LEGEND_HEIGHT) / 2.0;
+    let radius = (CHART_HEIGHT as f32 - LEGEND_HEIGHT) / 2.0 - 40.0;
+
+    let vertex = |distance: f32, i: usize| {
+        let angle = -PI / 2.0 + 2.0 * PI * i as f32 / count as f32;
+        (center_x + distance * angle.cos(), center_y + distance * angle.sin())
+    };
+
+    if count >= 3 {
+        for step in 1..=GRID_STEPS {
+            let distance = radius * step as f32 / GRID_STEPS as f32;
+            let points = (0..count)
+                .map(|i| {
+                    let (x, y) = vertex(distance, i);
+                    Point::new(x, y)
+                })
+                .collect::<Vec<_>>();
+            draw_hollow_polygon_mut(canvas, &points, GRID_COLOR);
+        }
+    }
+
+    for (i, label) in chart.labels.iter().enumerate() {
+        draw_line_segment_mut(canvas, (center_x, center_y), vertex(radius, i), GRID_COLOR);
+        let (x, y) = vertex(radius + 22.0, i);
+        draw_centered_text(canvas, font, text_color, LABEL_SCALE, x, y, label);
+    }
+
+    for dataset in &chart.datasets {
+        let mut points = dataset
+            .data
+            .iter()
+            .take(count)
+            .enumerate()
+            .map(|(i, value)| {
+                let (x, y) = vertex(ratio(*value, chart.max) * radius, i);
+                Point::new(x.round() as i32, y.round() as i32)
+            })
+            .collect::<Vec<_>>();
+
+        // polygons can't be closed explicitly or contain a single point
+        points.dedup();
+        while points.len() > 1 && points.first() == points.last() {
+            points.pop();
+        }
+        if points.len() >= 3 {
+            draw_polygon_mut(canvas, &points, parse_color(dataset.color));
+        }
+    }
+}
+
+fn draw_bar(canvas: &mut Canvas, chart: &Chart, font: &FontRef, text_color: Rgba<u8>) {
+    let count = chart.labels.len();
+    if count == 0 || chart.datasets.is_empty() {
+        return;
+    }
+
+    let (left, right, top, bottom) = PLOT_AREA;
+    let height = bottom - top;
+    draw_grid(canvas, chart, font, text_color);
+
+    let group_width = (right - left) / count as f32;
+    let bar_width = group_width * 0.8 / chart.datasets.len() as f32;
+
+    for (i, label) in chart.labels.iter().enumerate() {
+        let group_left = left + group_width * i as f32 + group_width * 0.1;
+
+        for (j, dataset) in chart.datasets.iter().enumerate() {
+            let value = dataset.data.get(i).copied().unwrap_or(0.0);
+            let bar_height = ratio(value - chart.min, chart.max - chart.min).min(1.0) * height;
+            if bar_height < 1.0 {
+                continue;
+            }
+
+            draw_filled_rect_mut(
+                canvas,
+                Rect::at((group_left + bar_width * j as f32) as i32, (bottom - bar_height) as i32)
+                    .of_size(bar_width.max(1.0) as u32, bar_height as u32),
+                parse_color(dataset.color),
+            );
+        }
+
+        draw_centered_text(
+            canvas,
+            font,
+            text_color,
+            12.0,
+            left + group_width * (i as f32 + 0.5),
+            bottom + 16.0,
+            label,
+        );
+    }
+}
+
+/// Horizontal grid lines of bar and line charts, from `chart.min` to `chart.max`.
+fn draw_grid(canvas: &mut Canvas, chart: &Chart, font: &FontRef, text_color: Rgba<u8>) {
+    let (left, right, top, bottom) = PLOT_AREA;
+    let height = bottom - top;
+
+    for step in 0..=GRID_STEPS {
+        let y = bottom - height * step as f32 / GRID_STEPS as f32;
+        draw_line_segment_mut(canvas, (left, y), (right, y), GRID_COLOR);
+
+        let value = chart.min + (chart.max - chart.min) * step as f64 / GRID_STEPS as f64;
+        let value = if chart.max - chart.min >= 10.0 {
+            format!("{value:.0}")
+        } else {
+            format!("{value:.2}")
+        };
+        let (width, _) = text_size(PxScale::from(12.0), font, &value);
+        draw_text_mut(
+            canvas,
+            text_color,
+            (left - 4.0 - width as f32) as i32,
+            (y - 6.0) as i32,
+            PxScale::from(12.0),
+            font,
+            &value,
+        );
+    }
+}
+
+/// Lines between the values of each dataset, missing values (`NaN`) leave a gap.
+fn draw_line(canvas: &mut Canvas, chart: &Chart, font: &FontRef, text_color: Rgba<u8>) {
+    let count = chart.labels.len();
+    if count == 0 || chart.datasets.is_empty() {
+        return;
+    }
+
+    let (left, right, top, bottom) = PLOT_AREA;
+    let height = bottom - top;
+    draw_grid(canvas, chart, font, text_color);
+
+    let step_width = (right - left) / count.saturating_sub(1).max(1) as f32;
+    let x = |i: usize| left + step_width * i as f32;
+
+    let label_step = count.div_ceil(MAX_LINE_LABELS);
+    for (i, label) in chart.labels.iter().enumerate().step_by(label_step) {
+        draw_centered_text(canvas, font, text_color, 12.0, x(i), bottom + 16.0, label);
+    }
+
+    for dataset in &chart.datasets {
+        let color = parse_color(dataset.color);
+        let points = dataset
+            .data
+            .iter()
+            .take(count)
+            .map(|value| {
+                value
+                    .is_finite()
+                    .then(|| bottom - ratio(value - chart.min, chart.max - chart.min).min(1.0) * height)
+            })
+            .collect::<Vec<_>>();
+
+        for (i, pair) in points.windows(2).enumerate() {
+            if let [Some(from), Some(to)] = pair {
+                draw_line_segment_mut(canvas, (x(i), *from), (x(i + 1), *to), color);
+                draw_line_segment_mut(canvas, (x(i), *from + 1.0), (x(i + 1), *to + 1.0), color);
+            }
+        }
+    }
+}
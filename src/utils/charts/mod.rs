@@ -0,0 +1,162 @@
+use twilight_model::{
+    channel::message::Embed, gateway::payload::incoming::InteractionCreate,
+    http::attachment::Attachment,
+};
+
+use crate::context::Context;
+
+pub mod local;
+pub mod quickchart;
+
+pub const CHART_WIDTH: u32 = 500;
+pub const CHART_HEIGHT: u32 = 300;
+/// Number of grid lines drawn between 0 and the maximum of a chart
+pub const GRID_STEPS: usize = 6;
+pub const CHART_FILENAME: &str = "chart.png";
+/// Characters of the content of a discord message
+const MAX_CONTENT_LENGTH: usize = 2000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChartBackend {
+    /// Rendered in process, sent as an attachment
+    Local,
+    /// Rendered by quickchart.io, sent as a link
+    QuickChart,
+}
+
+impl ChartBackend {
+    /// Reads the backend from the `CHART_BACKEND` environment variable, either `local` or `quickchart`.
+    pub fn from_env() -> Self {
+        match std::env::var("CHART_BACKEND").map(|backend| backend.to_lowercase()).as_deref() {
+            Ok("quickchart") => ChartBackend::QuickChart,
+            Ok("local") if cfg!(feature = "local_image_generation") => ChartBackend::Local,
+            Ok("local") => {
+                log::warn!("CHART_BACKEND is local but the bot was built without local_image_generation, using quickchart");
+                ChartBackend::QuickChart
+            }
+            Ok(backend) => {
+                log::warn!("Unknown CHART_BACKEND {backend}, using the default one");
+                Self::default()
+            }
+            Err(_) => Self::default(),
+        }
+    }
+}
+
+impl Default for ChartBackend {
+    fn default() -> Self {
+        if cfg!(feature = "local_image_generation") {
+            ChartBackend::Local
+        } else {
+            ChartBackend::QuickChart
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChartKind {
+    Radar,
+    Bar,
+    Line,
+}
+
+#[derive(Clone, Debug)]
+pub struct ChartDataset {
+    pub label: String,
+    pub data: Vec<f64>,
+    /// A css color, `rgba(r,g,b,a)` or `#rrggbb`
+    pub color: &'static str,
+}
+
+#[derive(Clone, Debug)]
+pub struct Chart {
+    pub kind: ChartKind,
+    pub labels: Vec<String>,
+    pub datasets: Vec<ChartDataset>,
+    /// Value at the bottom of bar and line charts, radar charts always start at 0
+    pub min: f64,
+    /// Value at the edge of the chart
+    pub max: f64,
+    pub dark_mode: bool,
+}
+
+pub enum RenderedChart {
+    Url(String),
+    Image(Vec<u8>),
+}
+
+pub fn font_color(dark_mode: bool) -> &'static str {
+    if dark_mode {
+        "#F5F5F5"
+    } else {
+        "#000000"
+    }
+}
+
+impl Chart {
+    pub async fn render(&self, backend: ChartBackend) -> anyhow::Result<RenderedChart> {
+        match backend {
+            #[cfg(feature = "local_image_generation")]
+            ChartBackend::Local => Ok(RenderedChart::Image(local::render(self)?)),
+            _ => Ok(RenderedChart::Url(quickchart::render(self).await?)),
+        }
+    }
+}
+
+impl RenderedChart {
+    /// Whether `content` fits in a message along with the chart.
+    pub fn fits(&self, content: &str) -> bool {
+        let length = match self {
+            RenderedChart::Url(url) => content.chars().count() + 1 + url.chars().count(),
+            RenderedChart::Image(_) => content.chars().count(),
+        };
+        length <= MAX_CONTENT_LENGTH
+    }
+
+    /// Replies to a deferred interaction with the chart, below `content`.
+    pub async fn send(
+        self,
+        content: &str,
+        interaction: &InteractionCreate,
+        context: &Context<'_>,
+    ) -> anyhow::Result<()> {
+        self.send_with_embeds(content, &[], interaction, context).await
+    }
+
+    /// Replies to a deferred interaction with the chart, below `content` and `embeds`.
+    pub async fn send_with_embeds(
+        self,
+        content: &str,
+        embeds: &[Embed],
+        interaction: &InteractionCreate,
+        context: &Context<'_>,
+    ) -> anyhow::Result<()> {
+        let interaction_client = context.http_client.interaction(context.application.id);
+
+        match self {
+            RenderedChart::Url(url) => {
+                let content = if content.is_empty() {
+                    url
+                } else {
+                    format!("{content}\n{url}")
+                };
+
+                interaction_client
+                    .update_response(&interaction.token)
+                    .content(Some(&content))?
+                    .embeds(Some(embeds))?
+                    .await?;
+            }
+            RenderedChart::Image(bytes) => {
+                interaction_client
+                    .update_response(&interaction.token)
+                    .content((!content.is_empty()).then_some(content))?
+                    .embeds(Some(embeds))?
+                    .attachments(&[Attachment::from_bytes(CHART_FILENAME.to_string(), bytes, 1)])?
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,89 @@
+use anyhow::anyhow;
+use serde_json::json;
+
+use super::{font_color, Chart, ChartKind, CHART_HEIGHT, CHART_WIDTH, GRID_STEPS};
+
+fn chart_json(chart: &Chart) -> serde_json::Value {
+    let font_color = font_color(chart.dark_mode);
+    let step = (chart.max - chart.min) / GRID_STEPS as f64;
+    let datasets = chart
+        .datasets
+        .iter()
+        .map(|dataset| {
+            json!({
+                "label": dataset.label,
+                "data": dataset.data,
+                "backgroundColor": dataset.color,
+                "borderColor": dataset.color,
+                "borderWidth": 0,
+                "pointRadius": 0
+            })
+        })
+        .collect::<Vec<_>>();
+
+    match chart.kind {
+        ChartKind::Radar => json!({
+            "type": "radar",
+            "data": {
+                "labels": chart.labels,
+                "datasets": datasets
+            },
+            "options":{"legend": { "labels": { "fontColor": font_color, "fontSize": 16}}, "scale":{"pointLabels":{"fontColor":font_color, "fontSize": 16},"rAxis":{"ticks":{"display":false}},"ticks":{"min":0,"max":chart.max,"stepSize":step,"fontColor":"blue","display":false},"gridLines":{"color":"gray"},"angleLines":{"color":"gray"}}}
+        }),
+        ChartKind::Bar => json!({
+            "type": "bar",
+            "data": {
+                "labels": chart.labels,
+                "datasets": datasets
+            },
+            "options":{"legend": { "labels": { "fontColor": font_color, "fontSize": 16}}, "scales":{"xAxes":[{"ticks":{"fontColor":font_color},"gridLines":{"color":"gray"}}],"yAxes":[{"ticks":{"min":chart.min,"max":chart.max,"stepSize":step,"fontColor":font_color},"gridLines":{"color":"gray"}}]}}
+        }),
+        ChartKind::Line => json!({
+            "type": "line",
+            "data": {
+                "labels": chart.labels,
+                "datasets": chart.datasets.iter().map(|dataset| json!({
+                    "label": dataset.label,
+                    // missing values are serialized as null, leaving a gap
+                    "data": dataset.data,
+                    "borderColor": dataset.color,
+                    "backgroundColor": dataset.color,
+                    "fill": false,
+                    "pointRadius": 0
+                })).collect::<Vec<_>>()
+            },
+            "options":{"legend": { "labels": { "fontColor": font_color, "fontSize": 16}}, "scales":{"xAxes":[{"ticks":{"fontColor":font_color,"maxTicksLimit":8},"gridLines":{"color":"gray"}}],"yAxes":[{"ticks":{"min":chart.min,"max":chart.max,"stepSize":step,"fontColor":font_color},"gridLines":{"color":"gray"}}]}}
+        }),
+    }
+}
+
+/// Uploads the chart to quickchart.io and returns the url of the image.
+pub async fn render(chart: &Chart) -> anyhow::Result<String> {
+    let json = json!({
+        "width": CHART_WIDTH,
+        "height": CHART_HEIGHT,
+        "format": "webp",
+        "background": "transparent",
+        "version": 2,
+        "chart": chart_json(chart)
+    });
+
+    log::debug!("{json}");
+
+    let response = reqwest::Client::builder()
+        .build()?
+        .post("https://quickchart.io/chart/create")
+        .header("Content-Type", "application/json")
+        .body(json.to_string())
+        .send()
+        .await?
+        .json::<serde_json::Value>()
+        .await?;
+
+    Ok(response
+        .get("url")
+        .ok_or(anyhow!("Couldn't find graph url"))?
+        .as_str()
+        .ok_or(anyhow!("Couldn't find graph url"))?
+        .to_string())
+}
@@ -0,0 +1,83 @@
+//! Downloads of the files given by users, only from trusted hosts and bounded in size.
+
+use std::{sync::OnceLock, time::Duration};
+
+use anyhow::anyhow;
+use reqwest::{redirect::Policy, Client, Url};
+
+/// A tetra league replay weighs a few megabytes
+pub const MAX_REPLAY_SIZE: usize = 16 * 1024 * 1024;
+const MAX_REDIRECTS: usize = 5;
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Hosts files are downloaded from, with their subdomains: tetr.io and the discord cdn.
+const TRUSTED_HOSTS: [&str; 4] = ["tetr.io", "discordapp.com", "discordapp.net", "discord.com"];
+
+fn is_trusted(url: &Url) -> bool {
+    url.scheme() == "https"
+        && url.host_str().is_some_and(|host| {
+            TRUSTED_HOSTS
+                .iter()
+                .any(|trusted| host == *trusted || host.ends_with(&format!(".{trusted}")))
+        })
+}
+
+/// Whether a file can be downloaded from this url.
+pub fn is_trusted_url(url: &str) -> bool {
+    Url::parse(url).is_ok_and(|url| is_trusted(&url))
+}
+
+/// `base` followed by `segments`, each escaped so a name given by a user can't change the path or add a query.
+pub fn url_with_segments(base: &str, segments: &[&str]) -> anyhow::Result<Url> {
+    let mut url = Url::parse(base)?;
+    url.path_segments_mut()
+        .map_err(|_| anyhow!("{base} can't have a path"))?
+        .extend(segments);
+    Ok(url)
+}
+
+/// Client shared by every download, so a host that hangs can't block a command forever.
+fn client() -> anyhow::Result<&'static Client> {
+    static CLIENT: OnceLock<Client> = OnceLock::new();
+    if let Some(client) = CLIENT.get() {
+        return Ok(client);
+    }
+
+    // redirects could lead anywhere, they are only followed to the trusted hosts
+    let client = Client::builder()
+        .timeout(TIMEOUT)
+        .redirect(Policy::custom(|attempt| {
+            if attempt.previous().len() < MAX_REDIRECTS && is_trusted(attempt.url()) {
+                attempt.follow()
+            } else {
+                attempt.stop()
+            }
+        }))
+        .build()?;
+    Ok(CLIENT.get_or_init(|| client))
+}
+
+/// The body at `url`, refused when the host isn't trusted or when it is larger than `max_size` bytes.
+pub async fn download(url: &str, max_size: usize) -> anyhow::Result<anyhow::Result<Vec<u8>>> {
+    if !is_trusted_url(url) {
+        return Ok(Err(anyhow!("❌ Files can only be downloaded from tetr.io or discord")));
+    }
+
+    let mut response = client()?.get(url).send().await?.error_for_status()?;
+
+    let too_large = || anyhow!("❌ The file is larger than {} MiB", max_size / 1024 / 1024);
+    if response.content_length().is_some_and(|length| length > max_size as u64) {
+        return Ok(Err(too_large()));
+    }
+
+    // the announced length can lie, the body is read chunk by chunk up to the limit
+    let mut bytes = Vec::new();
+    while let Some(chunk) = response.chunk().await? {
+        if bytes.len() + chunk.len() > max_size {
+            return Ok(Err(too_large()));
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+
+    Ok(Ok(bytes))
+}
@@ -0,0 +1,127 @@
+#![cfg(all(feature = "tetrio", feature = "database"))]
+//! Tetrio accounts of the members of a guild.
+//!
+//! Members are resolved from their link first, then from the discord connection of tetrio profiles. The member
+//! list is kept in memory for a while and the lookups of discord connections are kept in the database for a week,
+//! including the members without an account, so large guilds are only looked up a few members at a time.
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use twilight_model::id::Id;
+
+use crate::{
+    context::Context,
+    services::{discord_account::DiscordAccountPDO, tetrio_link::TetrioLinkPDO},
+};
+
+const MEMBER_CACHE_TTL: Duration = Duration::from_secs(60 * 60);
+const MEMBERS_PAGE_SIZE: u16 = 1000;
+/// Pages of members fetched at most, enough for a guild of 100k members
+const MAX_MEMBER_PAGES: usize = 100;
+/// Discord connections looked up per command, the others are looked up by the next commands
+const MAX_LOOKUPS: usize = 50;
+
+/// Member lists of the guilds, without the bots.
+#[derive(Default)]
+pub struct MemberCache {
+    guilds: Mutex<HashMap<u64, (Instant, Arc<Vec<u64>>)>>,
+}
+
+impl MemberCache {
+    pub async fn members(&self, guild_id: u64, context: &Context<'_>) -> anyhow::Result<Arc<Vec<u64>>> {
+        let cached = self
+            .guilds
+            .lock()
+            .expect("member cache poisoned")
+            .get(&guild_id)
+            .filter(|(fetched_at, _)| fetched_at.elapsed() < MEMBER_CACHE_TTL)
+            .map(|(_, members)| Arc::clone(members));
+        if let Some(members) = cached {
+            return Ok(members);
+        }
+
+        let mut members = vec![];
+        let mut after = None;
+        for _ in 0..MAX_MEMBER_PAGES {
+            let request = context.http_client.guild_members(Id::new(guild_id)).limit(MEMBERS_PAGE_SIZE)?;
+            let request = match after {
+                Some(after) => request.after(after),
+                None => request,
+            };
+            let page = request.await?.models().await?;
+
+            after = page.last().map(|member| member.user.id);
+            members.extend(page.iter().filter(|member| !member.user.bot).map(|member| member.user.id.get()));
+            if page.len() < MEMBERS_PAGE_SIZE as usize {
+                break;
+            }
+        }
+
+        let members = Arc::new(members);
+        self.guilds
+            .lock()
+            .expect("member cache poisoned")
+            .insert(guild_id, (Instant::now(), Arc::clone(&members)));
+        Ok(members)
+    }
+}
+
+/// The members of a guild with a known tetrio account.
+pub struct GuildAccounts {
+    /// Discord id of each tetrio account
+    pub accounts: HashMap<String, u64>,
+    /// Members not looked up yet
+    pub pending: usize,
+}
+
+pub async fn resolve_guild_accounts(guild_id: u64, context: &Context<'_>) -> anyhow::Result<GuildAccounts> {
+    let members = context.member_cache.members(guild_id, context).await?;
+    let mut accounts = HashMap::new();
+    let mut unresolved = members.iter().copied().collect::<HashSet<_>>();
+
+    for link in TetrioLinkPDO::fetch_links(context).await? {
+        let Ok(discord_id) = link.discord_id.parse::<u64>() else {
+            continue;
+        };
+        if unresolved.remove(&discord_id) {
+            accounts.insert(link.tetrio_id, discord_id);
+        }
+    }
+
+    let remaining = unresolved.iter().copied().collect::<Vec<_>>();
+    for cached in DiscordAccountPDO::fetch_cached_accounts(context, &remaining).await? {
+        let Ok(discord_id) = cached.discord_id.parse::<u64>() else {
+            continue;
+        };
+        unresolved.remove(&discord_id);
+        if let Some(tetrio_id) = cached.tetrio_id {
+            accounts.entry(tetrio_id).or_insert(discord_id);
+        }
+    }
+
+    let lookups = unresolved.iter().copied().take(MAX_LOOKUPS).collect::<Vec<_>>();
+    for discord_id in lookups {
+        let tetrio_id = match context.tetrio_client.search_discord_user(&discord_id.to_string()).await {
+            Ok(packet) => packet.data.map(|data| data.user.id.to_string()),
+            Err(err) => {
+                log::warn!("couldn't look up the tetrio account of {discord_id}: {err:?}");
+                continue;
+            }
+        };
+
+        DiscordAccountPDO::upsert_cached_account(context, discord_id, tetrio_id.as_deref()).await?;
+        unresolved.remove(&discord_id);
+        if let Some(tetrio_id) = tetrio_id {
+            accounts.entry(tetrio_id).or_insert(discord_id);
+        }
+    }
+
+    Ok(GuildAccounts {
+        accounts,
+        pending: unresolved.len(),
+    })
+}
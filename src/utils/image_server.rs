@@ -0,0 +1,199 @@
+#![cfg(all(feature = "tetrio", feature = "html_server_image_generation"))]
+//! Client of the api server rendering the teto and tetra images.
+
+use std::{fmt::Display, time::Duration};
+
+use common::LeagueRecord;
+use reqwest::{RequestBuilder, StatusCode};
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::json;
+use tetrio_api::models::packet::Packet;
+
+const TIMEOUT: Duration = Duration::from_secs(30);
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+/// Number of retries after the first attempt, only when the server is unavailable
+const RETRIES: u32 = 2;
+const RETRY_DELAY: Duration = Duration::from_millis(500);
+
+#[derive(Debug)]
+pub enum ImageServerError {
+    /// The server couldn't be reached or didn't answer in time
+    Unreachable(reqwest::Error),
+    /// The server answered with an unexpected http status
+    Status(StatusCode),
+    /// The server answered with an error message, meant to be shown to the user
+    Api(String),
+    /// The response couldn't be parsed or doesn't contain an image
+    InvalidResponse(String),
+}
+
+impl ImageServerError {
+    /// Whether the server itself is down, in which case the data can still be shown without the image.
+    pub fn is_unavailable(&self) -> bool {
+        match self {
+            ImageServerError::Unreachable(_) => true,
+            ImageServerError::Status(status) => status.is_server_error(),
+            ImageServerError::Api(_) | ImageServerError::InvalidResponse(_) => false,
+        }
+    }
+}
+
+impl Display for ImageServerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ImageServerError::Unreachable(err) if err.is_timeout() => write!(f, "The image server timed out"),
+            ImageServerError::Unreachable(_) => write!(f, "The image server is unreachable"),
+            ImageServerError::Status(status) => write!(f, "The image server answered with {status}"),
+            ImageServerError::Api(msg) => write!(f, "{msg}"),
+            ImageServerError::InvalidResponse(msg) => write!(f, "Invalid response from the image server: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for ImageServerError {}
+
+#[derive(Deserialize)]
+pub struct TetraImage {
+    pub replay_id: Option<String>,
+    pub buffer: Vec<u8>,
+}
+
+#[async_trait::async_trait]
+pub trait ImageServerClient: Send + Sync {
+    /// Profile image of a tetrio user.
+    async fn teto(&self, username: &str) -> Result<Vec<u8>, ImageServerError>;
+
+    /// Image of a recent tetra league game of a user, starting at 1.
+    async fn tetra(&self, user_id: &str, game_num: i64) -> Result<TetraImage, ImageServerError>;
+
+    /// Image of a tetra league game parsed from a ttrm replay.
+    async fn tetra_replay(&self, ts: serde_json::Value, league_record: &LeagueRecord) -> Result<TetraImage, ImageServerError>;
+}
+
+/// Builds the client from the `IMAGE_SERVER` environment variable, `mock` serves canned images and
+/// anything else uses the api server at `api_url`.
+pub fn from_env(api_url: &str) -> Box<dyn ImageServerClient> {
+    match std::env::var("IMAGE_SERVER").map(|server| server.to_lowercase()).as_deref() {
+        Ok("mock") => {
+            log::info!("Using the mock image server");
+            Box::new(MockImageServerClient::default())
+        }
+        _ => Box::new(HttpImageServerClient::new(api_url.to_string()).expect("Couldn't build the image server client")),
+    }
+}
+
+pub struct HttpImageServerClient {
+    client: reqwest::Client,
+    base_url: String,
+}
+
+impl HttpImageServerClient {
+    pub fn new(base_url: String) -> reqwest::Result<Self> {
+        Ok(Self {
+            client: reqwest::Client::builder()
+                .timeout(TIMEOUT)
+                .connect_timeout(CONNECT_TIMEOUT)
+                .build()?,
+            base_url,
+        })
+    }
+
+    /// Sends the request, retrying with a growing delay while the server is unavailable.
+    async fn request<T: DeserializeOwned>(
+        &self,
+        request: impl Fn() -> RequestBuilder + Send + Sync,
+    ) -> Result<T, ImageServerError> {
+        let mut attempt = 0;
+        loop {
+            match Self::send(request()).await {
+                Err(err) if err.is_unavailable() && attempt < RETRIES => {
+                    attempt += 1;
+                    log::warn!("Image server request failed: {err}, retrying ({attempt}/{RETRIES})");
+                    tokio::time::sleep(RETRY_DELAY * attempt).await;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    async fn send<T: DeserializeOwned>(request: RequestBuilder) -> Result<T, ImageServerError> {
+        let response = request.send().await.map_err(ImageServerError::Unreachable)?;
+        let status = response.status();
+        let bytes = response.bytes().await.map_err(ImageServerError::Unreachable)?;
+
+        let packet = match serde_json::from_slice::<Packet<T>>(&bytes) {
+            Ok(packet) => packet,
+            Err(_) if !status.is_success() => return Err(ImageServerError::Status(status)),
+            Err(err) => return Err(ImageServerError::InvalidResponse(err.to_string())),
+        };
+
+        match packet {
+            Packet { success: true, data: Some(data), .. } => Ok(data),
+            Packet { error: Some(error), .. } => Err(ImageServerError::Api(error.msg)),
+            _ if !status.is_success() => Err(ImageServerError::Status(status)),
+            _ => Err(ImageServerError::InvalidResponse("no image in the response".to_string())),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl ImageServerClient for HttpImageServerClient {
+    async fn teto(&self, username: &str) -> Result<Vec<u8>, ImageServerError> {
+        let url = format!("{}/api/v1/teto/{username}", self.base_url);
+        self.request::<Box<[u8]>>(|| self.client.get(&url))
+            .await
+            .map(|buffer| buffer.into_vec())
+    }
+
+    async fn tetra(&self, user_id: &str, game_num: i64) -> Result<TetraImage, ImageServerError> {
+        let url = format!("{}/api/v1/tetra?user_id={user_id}&game_num={game_num}", self.base_url);
+        self.request(|| self.client.get(&url)).await
+    }
+
+    async fn tetra_replay(&self, ts: serde_json::Value, league_record: &LeagueRecord) -> Result<TetraImage, ImageServerError> {
+        let url = format!("{}/api/v1/tetra/replay", self.base_url);
+        let body = json!({
+            "ts": ts,
+            "league_record": league_record
+        });
+        self.request(|| self.client.post(&url).json(&body)).await
+    }
+}
+
+/// Serves the same canned image for every request, to run the bot without the api server.
+pub struct MockImageServerClient {
+    image: Vec<u8>,
+}
+
+impl MockImageServerClient {
+    pub fn new(image: Vec<u8>) -> Self {
+        Self { image }
+    }
+}
+
+impl Default for MockImageServerClient {
+    fn default() -> Self {
+        Self::new(include_bytes!("../assets/unkown_avatar.webp").to_vec())
+    }
+}
+
+#[async_trait::async_trait]
+impl ImageServerClient for MockImageServerClient {
+    async fn teto(&self, _username: &str) -> Result<Vec<u8>, ImageServerError> {
+        Ok(self.image.clone())
+    }
+
+    async fn tetra(&self, _user_id: &str, _game_num: i64) -> Result<TetraImage, ImageServerError> {
+        Ok(TetraImage {
+            replay_id: None,
+            buffer: self.image.clone(),
+        })
+    }
+
+    async fn tetra_replay(&self, _ts: serde_json::Value, _league_record: &LeagueRecord) -> Result<TetraImage, ImageServerError> {
+        Ok(TetraImage {
+            replay_id: None,
+            buffer: self.image.clone(),
+        })
+    }
+}
@@ -0,0 +1,312 @@
+#![cfg(feature = "tetrio")]
+//! Leaderboards browsed with buttons and select menus.
+//!
+//! The whole state of a leaderboard message lives in the custom ids of its components, so a leaderboard can still be
+//! browsed after a restart. The leaderboard itself comes from the leaderboard cache.
+
+use itertools::Itertools;
+use tetrio_api::models::{
+    common::APIstring,
+    users::{user_leaderboard::LeaderboardUser, user_rank::UserRank},
+};
+use twilight_model::{
+    application::interaction::message_component::MessageComponentInteractionData,
+    channel::message::{
+        component::{ActionRow, Button, ButtonStyle, SelectMenu, SelectMenuOption},
+        Component, MessageFlags, ReactionType,
+    },
+    gateway::payload::incoming::InteractionCreate,
+    http::interaction::{InteractionResponse, InteractionResponseType},
+};
+use twilight_util::builder::InteractionResponseDataBuilder;
+
+use crate::{
+    context::Context,
+    interactions::commands::{options::user_stat_options::UserStatOption, tetrio_commands::lb::LbCommand},
+};
+
+use super::aggregate_leaderboard::{parse_rank, RANKS};
+
+/// Prefix of the custom ids of the leaderboard components
+pub const CUSTOM_ID_PREFIX: &str = "lb:";
+const ANY_RANK: &str = "any";
+
+/// A page of a leaderboard, with the stat, the rank and the order it is browsed with.
+pub struct LeaderboardView {
+    pub stat: UserStatOption,
+    pub rank: Option<UserRank>,
+    /// Lowest values first
+    pub reverse: bool,
+    /// Starts at 1
+    pub page: usize,
+    pub limit: usize,
+    pub country: Option<String>,
+}
+
+impl LeaderboardView {
+    /// `lb:<action>:<stat>:<rank>:<reverse>:<page>:<limit>:<country>`, well below the 100 characters of discord.
+    fn custom_id(&self, action: &str) -> String {
+        format!(
+            "{CUSTOM_ID_PREFIX}{action}:{}:{}:{}:{}:{}:{}",
+            self.stat.value(),
+            self.rank.as_ref().map(|rank| rank.to_string()).unwrap_or(ANY_RANK.to_string()),
+            u8::from(self.reverse),
+            self.page,
+            self.limit,
+            self.country.as_deref().unwrap_or_default()
+        )
+    }
+
+    /// The action and the view of a component custom id.
+    pub fn parse(custom_id: &str) -> Option<(String, Self)> {
+        let [action, stat, rank, reverse, page, limit, country] =
+            custom_id.strip_prefix(CUSTOM_ID_PREFIX)?.splitn(7, ':').collect::<Vec<_>>()[..]
+        else {
+            return None;
+        };
+
+        Some((
+            action.to_string(),
+            Self {
+                stat: UserStatOption::from_value(stat)?,
+                rank: parse_rank(rank),
+                reverse: reverse == "1",
+                page: page.parse().ok()?,
+                limit: limit.parse::<usize>().ok().filter(|limit| *limit > 0)?,
+                country: (!country.is_empty()).then(|| country.to_string()),
+            },
+        ))
+    }
+
+    /// Values of the players of the rank, sorted in the order of the view.
+    pub fn stats(&self, data: &[LeaderboardUser]) -> Vec<(usize, APIstring, f64)> {
+        let iter = data
+            .iter()
+            .enumerate()
+            .filter(|(_, user)| match &self.rank {
+                Some(rank) => user.league.rank.as_ref() == Some(rank),
+                None => true,
+            });
+
+        LbCommand::get_stats(&self.stat, iter)
+            .into_iter()
+            .sorted_by(|(_, _, a), (_, _, b)| if self.reverse { a.total_cmp(b) } else { b.total_cmp(a) })
+            .collect()
+    }
+
+    /// The page holding the player at `position` in the leaderboard.
+    pub fn page_of(&self, stats: &[(usize, APIstring, f64)], position: usize) -> Option<usize> {
+        stats
+            .iter()
+            .position(|(leaderboard_position, _, _)| *leaderboard_position == position)
+            .map(|index| index / self.limit + 1)
+    }
+
+    /// The content and the components of the current page, moving back to the last page when it is past it.
+    pub fn render(&mut self, stats: &[(usize, APIstring, f64)]) -> (String, Vec<Component>) {
+        let pages = stats.len().div_ceil(self.limit).max(1);
+        self.page = self.page.clamp(1, pages);
+
+        let content = stats
+            .iter()
+            .enumerate()
+            .skip((self.page - 1) * self.limit)
+            .take(self.limit)
+            .map(|(index, (rank, username, value))| {
+                format!("#{}: {} (Rank: #{}): ({:.4}) ", index + 1, username, rank + 1, value)
+            })
+            .join("\n");
+        let content = format!(
+            "```\n{content}\n```{} by {}, {}, page {}/{pages}",
+            self.country.as_deref().unwrap_or("Leaderboard"),
+            self.stat.name(),
+            if self.reverse { "lowest first" } else { "highest first" },
+            self.page
+        );
+
+        (content, self.components(pages))
+    }
+
+    fn components(&self, pages: usize) -> Vec<Component> {
+        let button = |action: &str, label: &str, emoji: &str, disabled: bool| {
+            Component::Button(Button {
+                custom_id: Some(self.custom_id(action)),
+                disabled,
+                emoji: Some(ReactionType::Unicode { name: emoji.to_string() }),
+                label: Some(label.to_string()),
+                style: ButtonStyle::Primary,
+                url: None,
+            })
+        };
+        let option = |label: String, value: String, default: bool| SelectMenuOption {
+            default,
+            description: None,
+            emoji: None,
+            label,
+            value,
+        };
+        let select = |action: &str, placeholder: &str, options: Vec<SelectMenuOption>| {
+            Component::ActionRow(ActionRow {
+                components: vec![Component::SelectMenu(SelectMenu {
+                    custom_id: self.custom_id(action),
+                    disabled: false,
+                    max_values: Some(1),
+                    min_values: Some(1),
+                    options,
+                    placeholder: Some(placeholder.to_string()),
+                })],
+            })
+        };
+
+        let buttons = vec![
+            button("previous", "Previous", "◀️", self.page <= 1),
+            button("next", "Next", "▶️", self.page >= pages),
+            // finding the caller needs their linked account
+            #[cfg(feature = "database")]
+            button("me", "Jump to me", "🔎", false),
+        ];
+
+        let stats = UserStatOption::ALL
+            .iter()
+            .map(|stat| option(stat.name().to_string(), stat.value().to_string(), stat.value() == self.stat.value()))
+            .collect();
+        let ranks = std::iter::once(option("Any rank".to_string(), ANY_RANK.to_string(), self.rank.is_none()))
+            .chain(RANKS.iter().map(|rank| {
+                option(
+                    rank.to_string().to_uppercase(),
+                    rank.to_string(),
+                    self.rank.as_ref() == Some(rank),
+                )
+            }))
+            .collect();
+        let orders = vec![
+            option("Highest first".to_string(), "highest".to_string(), !self.reverse),
+            option("Lowest first".to_string(), "lowest".to_string(), self.reverse),
+        ];
+
+        vec![
+            Component::ActionRow(ActionRow { components: buttons }),
+            select("stat", "Stat", stats),
+            select("rank", "Rank", ranks),
+            select("order", "Order", orders),
+        ]
+    }
+}
+
+/// Handles the buttons and the select menus of a leaderboard message.
+pub async fn handle_component(
+    _shard: u64,
+    it: Box<InteractionCreate>,
+    data: MessageComponentInteractionData,
+    context: &Context<'_>,
+) -> anyhow::Result<()> {
+    let Some((action, mut view)) = LeaderboardView::parse(&data.custom_id) else {
+        return Ok(());
+    };
+
+    let interaction_client = context.http_client.interaction(context.application.id);
+
+    // only the author of the command browses the message, the others are told so without changing it
+    let invoker = it
+        .message
+        .as_ref()
+        .and_then(|message| message.interaction.as_ref())
+        .map(|interaction| interaction.user.id);
+    if invoker.is_some_and(|invoker| Some(invoker) != it.author_id()) {
+        let data = InteractionResponseDataBuilder::new()
+            .content("❌ Only the author of the command can browse this leaderboard, use /lb or /rlb to get your own")
+            .flags(MessageFlags::EPHEMERAL)
+            .build();
+        interaction_client
+            .create_response(
+                it.id,
+                &it.token,
+                &InteractionResponse {
+                    kind: InteractionResponseType::ChannelMessageWithSource,
+                    data: Some(data),
+                },
+            )
+            .await?;
+        return Ok(());
+    }
+
+    interaction_client
+        .create_response(
+            it.id,
+            &it.token,
+            &InteractionResponse {
+                kind: InteractionResponseType::DeferredUpdateMessage,
+                data: None,
+            },
+        )
+        .await?;
+
+    let selected = data.values.first().map(String::as_str);
+    match (action.as_str(), selected) {
+        ("previous", _) => view.page = view.page.saturating_sub(1),
+        ("next", _) => view.page += 1,
+        ("stat", Some(value)) => {
+            let Some(stat) = UserStatOption::from_value(value) else {
+                return Ok(());
+            };
+            view.stat = stat;
+            view.page = 1;
+        }
+        ("rank", Some(value)) => {
+            view.rank = parse_rank(value);
+            view.page = 1;
+        }
+        ("order", Some(value)) => {
+            view.reverse = value == "lowest";
+            view.page = 1;
+        }
+        ("me", _) => {}
+        _ => return Ok(()),
+    }
+
+    let Some(leaderboard) = context.leaderboard_cache.leaderboard(view.country.as_deref(), context).await? else {
+        return Ok(());
+    };
+    let stats = view.stats(&leaderboard);
+
+    #[cfg(feature = "database")]
+    if action == "me" {
+        let page = match it.author_id() {
+            Some(discord_id) => match crate::services::tetrio_link::TetrioLinkPDO::fetch_link(context, discord_id.get()).await? {
+                Some(link) => leaderboard
+                    .iter()
+                    .position(|user| user.id.to_string() == link.tetrio_id)
+                    .and_then(|position| view.page_of(&stats, position))
+                    .ok_or("❌ Your linked account isn't in this leaderboard"),
+                None => Err("❌ You need to link your tetrio account with /link first"),
+            },
+            None => Err("❌ Couldn't find who clicked the button"),
+        };
+
+        match page {
+            Ok(page) => view.page = page,
+            Err(message) => {
+                interaction_client
+                    .create_followup(&it.token)
+                    .content(message)?
+                    .flags(MessageFlags::EPHEMERAL)
+                    .await?;
+                return Ok(());
+            }
+        }
+    }
+
+    let (content, components) = view.render(&stats);
+    match interaction_client.update_response(&it.token).content(Some(&content)) {
+        Ok(response) => response.components(Some(&components))?.await?,
+        Err(_) => {
+            interaction_client
+                .create_followup(&it.token)
+                .content("❌ Message content was too long, try lowering the limit!")?
+                .flags(MessageFlags::EPHEMERAL)
+                .await?
+        }
+    };
+
+    Ok(())
+}
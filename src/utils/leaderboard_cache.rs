@@ -0,0 +1,84 @@
+#![cfg(feature = "tetrio")]
+//! Full leaderboards kept in memory for a few minutes, so that browsing a leaderboard doesn't fetch it again.
+//! The archetype classifier built from the global leaderboard is kept for as long.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use tetrio_api::models::users::user_leaderboard::LeaderboardUser;
+
+use crate::context::Context;
+
+use super::archetype::ArchetypeClassifier;
+
+const LEADERBOARD_CACHE_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// The global leaderboard and the leaderboards of the countries, by country code.
+#[derive(Default)]
+pub struct LeaderboardCache {
+    leaderboards: Mutex<HashMap<Option<String>, (Instant, Arc<Vec<LeaderboardUser>>)>>,
+    classifier: Mutex<Option<(Instant, Arc<ArchetypeClassifier>)>>,
+}
+
+impl LeaderboardCache {
+    /// The leaderboard of a country or the global one, `None` when the api doesn't return it.
+    pub async fn leaderboard(
+        &self,
+        country: Option<&str>,
+        context: &Context<'_>,
+    ) -> anyhow::Result<Option<Arc<Vec<LeaderboardUser>>>> {
+        let country = country.map(|country| country.to_uppercase());
+        let cached = self
+            .leaderboards
+            .lock()
+            .expect("leaderboard cache poisoned")
+            .get(&country)
+            .filter(|(fetched_at, _)| fetched_at.elapsed() < LEADERBOARD_CACHE_TTL)
+            .map(|(_, leaderboard)| Arc::clone(leaderboard));
+        if let Some(leaderboard) = cached {
+            return Ok(Some(leaderboard));
+        }
+
+        let Some(leaderboard) = context.fetch_full_leaderboard(country.as_deref()).await?.data else {
+            return Ok(None);
+        };
+
+        let leaderboard = Arc::new(leaderboard);
+        self.leaderboards
+            .lock()
+            .expect("leaderboard cache poisoned")
+            .insert(country, (Instant::now(), Arc::clone(&leaderboard)));
+        Ok(Some(leaderboard))
+    }
+
+    /// The archetype classifier of the global leaderboard, the fallback one while the leaderboard can't be fetched.
+    pub async fn archetype_classifier(&self, context: &Context<'_>) -> Arc<ArchetypeClassifier> {
+        let cached = self
+            .classifier
+            .lock()
+            .expect("archetype classifier cache poisoned")
+            .as_ref()
+            .filter(|(built_at, _)| built_at.elapsed() < LEADERBOARD_CACHE_TTL)
+            .map(|(_, classifier)| Arc::clone(classifier));
+        if let Some(classifier) = cached {
+            return classifier;
+        }
+
+        let leaderboard = match self.leaderboard(None, context).await {
+            Ok(Some(leaderboard)) => leaderboard,
+            Ok(None) => return Arc::new(ArchetypeClassifier::fallback()),
+            Err(err) => {
+                log::warn!("Couldn't fetch the leaderboard for archetypes: {err}");
+                return Arc::new(ArchetypeClassifier::fallback());
+            }
+        };
+
+        let classifier = Arc::new(ArchetypeClassifier::from_leaderboard(&leaderboard));
+        *self.classifier.lock().expect("archetype classifier cache poisoned") =
+            Some((Instant::now(), Arc::clone(&classifier)));
+        classifier
+    }
+}
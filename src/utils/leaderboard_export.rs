@@ -0,0 +1,239 @@
+#![cfg(all(feature = "tetrio", feature = "database"))]
+//! Exports of the full leaderboard as csv or json files, on demand or every day in a channel.
+//!
+//! Every row holds the fields of the leaderboard and every stat derived from them, empty when the player has no
+//! apm, pps or vs.
+
+use std::{
+    io::{Cursor, Write},
+    sync::Arc,
+    time::Duration,
+};
+
+use anyhow::anyhow;
+use serde_json::{json, Map, Value};
+use tetrio_api::models::users::{user_leaderboard::LeaderboardUser, user_rank::UserRank};
+use twilight_model::{http::attachment::Attachment, id::Id};
+
+use crate::{
+    context::Context,
+    interactions::commands::options::export_format_option::ExportFormatOption,
+    services::leaderboard_export::LeaderboardExportPDO,
+};
+
+use super::{
+    aggregate_leaderboard::parse_rank,
+    scheduled_job::{interval_from_env, ScheduledJob},
+    stats::{calculate_stats, PlayerStats, Stats},
+};
+
+/// Larger files are zipped to fit in the attachment size limit of discord
+const MAX_ATTACHMENT_SIZE: usize = 8 * 1024 * 1024;
+const DEFAULT_CHECK_INTERVAL_MINUTES: u64 = 60;
+
+/// Names of the columns of the exports
+const COLUMNS: [&str; 39] = [
+    "position", "id", "username", "country", "rank", "tr", "glicko", "rd", "gamesplayed", "gameswon", "apm", "pps",
+    "vs", "vsapm", "app", "dssecond", "dspiece", "dsapppiece", "cheese", "garbage_effi", "weighted_app", "area",
+    "srarea", "stat_rank", "estglicko", "esttr", "atr", "napm", "npps", "nvs", "napp", "ndss", "ndsp", "nge", "nvsapm",
+    "opener", "plonk", "stride", "infds",
+];
+
+/// Values of a player, in the order of [`COLUMNS`].
+fn values(position: usize, user: &LeaderboardUser) -> [Value; COLUMNS.len()] {
+    let league = &user.league;
+    let stats = match (league.apm, league.pps, league.vs) {
+        (Some(apm), Some(pps), Some(vs)) => Some(calculate_stats(PlayerStats {
+            apm,
+            pps,
+            vs,
+            rd: Some(league.rd),
+            tr: Some(league.tr),
+            glicko: Some(league.glicko),
+            rank: league.rank.clone(),
+        })),
+        _ => None,
+    };
+    let derived = |stat: fn(&Stats) -> Option<f64>| json!(stats.as_ref().and_then(stat));
+
+    [
+        json!(position + 1),
+        json!(user.id.to_string()),
+        json!(user.username.to_string()),
+        json!(user.country.as_deref()),
+        json!(league.rank.as_ref().map(|rank| rank.to_string())),
+        json!(league.tr),
+        json!(league.glicko),
+        json!(league.rd),
+        json!(league.gamesplayed),
+        json!(league.gameswon),
+        json!(league.apm),
+        json!(league.pps),
+        json!(league.vs),
+        derived(|stats| Some(stats.vsapm)),
+        derived(|stats| Some(stats.app)),
+        derived(|stats| Some(stats.dssecond)),
+        derived(|stats| Some(stats.dspiece)),
+        derived(|stats| Some(stats.dsapppiece)),
+        derived(|stats| Some(stats.cheese)),
+        derived(|stats| Some(stats.garbage_effi)),
+        derived(|stats| Some(stats.weighted_app)),
+        derived(|stats| Some(stats.area)),
+        derived(|stats| Some(stats.srarea)),
+        derived(|stats| Some(stats.stat_rank)),
+        derived(|stats| Some(stats.estglicko)),
+        derived(|stats| Some(stats.esttr)),
+        derived(|stats| stats.atr),
+        derived(|stats| Some(stats.napm)),
+        derived(|stats| Some(stats.npps)),
+        derived(|stats| Some(stats.nvs)),
+        derived(|stats| Some(stats.napp)),
+        derived(|stats| Some(stats.ndss)),
+        derived(|stats| Some(stats.ndsp)),
+        derived(|stats| Some(stats.nge)),
+        derived(|stats| Some(stats.nvsapm)),
+        derived(|stats| Some(stats.opener)),
+        derived(|stats| Some(stats.plonk)),
+        derived(|stats| Some(stats.stride)),
+        derived(|stats| Some(stats.infds)),
+    ]
+}
+
+fn csv_cell(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(text) if text.contains([',', '"', '\n']) => format!("\"{}\"", text.replace('"', "\"\"")),
+        Value::String(text) => text.clone(),
+        value => value.to_string(),
+    }
+}
+
+/// The players with their position in the leaderboard, as a csv or json file.
+pub fn export<'a>(
+    players: impl Iterator<Item = (usize, &'a LeaderboardUser)>,
+    format: ExportFormatOption,
+) -> anyhow::Result<Vec<u8>> {
+    let rows = players.map(|(position, user)| values(position, user));
+
+    Ok(match format {
+        ExportFormatOption::Csv => {
+            // the header is written even without players, so an empty export is still a valid csv
+            let mut csv = COLUMNS.join(",");
+            csv.push('\n');
+            for row in rows {
+                let cells = row.iter().map(csv_cell).collect::<Vec<_>>();
+                csv.push_str(&cells.join(","));
+                csv.push('\n');
+            }
+            csv.into_bytes()
+        }
+        ExportFormatOption::Json => {
+            let rows = rows
+                .map(|row| {
+                    Value::Object(COLUMNS.iter().map(|name| name.to_string()).zip(row).collect::<Map<_, _>>())
+                })
+                .collect::<Vec<_>>();
+            serde_json::to_vec(&rows)?
+        }
+    })
+}
+
+fn zip_file(filename: &str, bytes: &[u8]) -> anyhow::Result<Vec<u8>> {
+    let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    let options = zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    zip.start_file(filename, options)?;
+    zip.write_all(bytes)?;
+
+    Ok(zip.finish()?.into_inner())
+}
+
+/// The leaderboard of a country or the global one, filtered by rank, with the number of exported players.
+pub async fn export_file(
+    format: ExportFormatOption,
+    rank: Option<&UserRank>,
+    country: Option<&str>,
+    context: &Context<'_>,
+) -> anyhow::Result<anyhow::Result<(Attachment, usize)>> {
+    let Some(leaderboard) = context.leaderboard_cache.leaderboard(country, context).await? else {
+        return Ok(Err(anyhow!("❌ Couldn't fetch leaderboard data!")));
+    };
+
+    let players = leaderboard
+        .iter()
+        .enumerate()
+        .filter(|(_, user)| rank.map_or(true, |rank| user.league.rank.as_ref() == Some(rank)))
+        .collect::<Vec<_>>();
+    let bytes = export(players.iter().copied(), format)?;
+
+    let filename = format!(
+        "leaderboard{}{}.{}",
+        country.map(|country| format!("_{}", country.to_lowercase())).unwrap_or_default(),
+        rank.map(|rank| format!("_{rank}")).unwrap_or_default(),
+        format.value()
+    );
+    let attachment = if bytes.len() > MAX_ATTACHMENT_SIZE {
+        let zipped = zip_file(&filename, &bytes)?;
+        if zipped.len() > MAX_ATTACHMENT_SIZE {
+            return Ok(Err(anyhow!(
+                "❌ The export is larger than {} MiB even zipped, filter it by rank or country",
+                MAX_ATTACHMENT_SIZE / 1024 / 1024
+            )));
+        }
+        Attachment::from_bytes(format!("{filename}.zip"), zipped, 0)
+    } else {
+        Attachment::from_bytes(filename, bytes, 0)
+    };
+
+    Ok(Ok((attachment, players.len())))
+}
+
+/// Sends the exports that weren't sent during the last day.
+pub async fn send_due_exports(context: &Context<'_>) -> anyhow::Result<()> {
+    for scheduled in LeaderboardExportPDO::fetch_due_exports(context).await? {
+        let (Ok(channel_id), Some(format)) =
+            (scheduled.channel_id.parse::<u64>(), ExportFormatOption::from_value(&scheduled.format))
+        else {
+            continue;
+        };
+        let rank = scheduled.rank.as_deref().and_then(parse_rank);
+
+        // a failing channel is still marked, so that it isn't retried until the next day
+        if let Err(err) = post(channel_id, format, rank.as_ref(), scheduled.country.as_deref(), context).await {
+            log::warn!("couldn't send the leaderboard export of channel {channel_id}: {err:?}");
+        }
+        LeaderboardExportPDO::mark_exported(context, &scheduled.channel_id).await?;
+    }
+
+    Ok(())
+}
+
+async fn post(
+    channel_id: u64,
+    format: ExportFormatOption,
+    rank: Option<&UserRank>,
+    country: Option<&str>,
+    context: &Context<'_>,
+) -> anyhow::Result<()> {
+    let (attachment, players) = export_file(format, rank, country, context).await??;
+
+    context
+        .http_client
+        .create_message(Id::new(channel_id))
+        .content(&format!("Daily leaderboard export, {players} players"))?
+        .attachments(&[attachment])?
+        .await?;
+
+    Ok(())
+}
+
+/// The daily export job, looking for exports to send every `LEADERBOARD_EXPORT_CHECK_MINUTES` minutes.
+pub fn spawn_export_job(context: Arc<Context<'static>>) -> ScheduledJob {
+    let interval = interval_from_env(
+        "LEADERBOARD_EXPORT_CHECK_MINUTES",
+        Duration::from_secs(60),
+        DEFAULT_CHECK_INTERVAL_MINUTES,
+    );
+    ScheduledJob::spawn("leaderboard exports", interval, context, |context| async move {
+        send_due_exports(&context).await
+    })
+}
@@ -0,0 +1,282 @@
+#![cfg(all(feature = "tetrio", feature = "database"))]
+//! Feed of the new tetra league matches of subscribed players.
+//!
+//! The recent records are read from the raw json of the tetra channel api, as the typed records don't expose the
+//! TR of the players before and after the match. A match is posted once per channel, even when both players are
+//! subscribed to it.
+
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use serde_json::Value;
+use twilight_model::{channel::message::Embed, id::Id};
+
+use crate::{context::Context, services::match_feed::MatchFeedPDO};
+
+use super::{
+    create_embed::create_embed,
+    download::url_with_segments,
+    scheduled_job::{interval_from_env, ScheduledJob},
+    table::format_table,
+};
+
+const USERS_URL: &str = "https://ch.tetr.io/api/users";
+const TIMEOUT: Duration = Duration::from_secs(15);
+/// Wait between two players, the tetra channel api asks to keep a low request rate
+const POLL_DELAY: Duration = Duration::from_secs(1);
+const DEFAULT_POLL_INTERVAL_MINUTES: u64 = 5;
+
+/// Stats of a player over a round or a whole match.
+#[derive(Clone, Debug, Default)]
+pub struct MatchStats {
+    pub apm: f64,
+    pub pps: f64,
+    pub vs: f64,
+}
+
+#[derive(Clone, Debug)]
+pub struct MatchPlayer {
+    pub id: String,
+    pub username: String,
+    pub wins: u64,
+    pub average: MatchStats,
+    pub tr_before: Option<f64>,
+    pub tr_after: Option<f64>,
+}
+
+#[derive(Clone, Debug)]
+pub struct RoundPlayer {
+    pub id: String,
+    pub stats: MatchStats,
+    /// The player still alive at the end of the round won it
+    pub alive: bool,
+}
+
+#[derive(Clone, Debug)]
+pub struct LeagueMatch {
+    pub replay_id: String,
+    pub players: Vec<MatchPlayer>,
+    pub rounds: Vec<Vec<RoundPlayer>>,
+}
+
+impl MatchStats {
+    fn from_value(stats: Option<&Value>) -> Self {
+        let stat = |name: &str| stats.and_then(|stats| stats.get(name)).and_then(Value::as_f64).unwrap_or(0.0);
+        Self {
+            apm: stat("apm"),
+            pps: stat("pps"),
+            vs: stat("vsscore"),
+        }
+    }
+
+    fn format(&self) -> String {
+        format!("{:.1} / {:.2} / {:.1}", self.apm, self.pps, self.vs)
+    }
+}
+
+impl LeagueMatch {
+    /// Reads a record of the recent tetra league matches of a player.
+    pub fn from_record(record: &Value) -> Option<Self> {
+        let replay_id = record.get("replayid")?.as_str()?.to_string();
+        let league = record.pointer("/extras/league");
+        let tr = |id: &str, index: usize| {
+            league?
+                .get(id)?
+                .get(index)?
+                .get("tr")?
+                .as_f64()
+                .filter(|tr| *tr >= 0.0)
+        };
+
+        let players = record
+            .pointer("/results/leaderboard")?
+            .as_array()?
+            .iter()
+            .filter_map(|player| {
+                let id = player.get("id")?.as_str()?.to_string();
+                Some(MatchPlayer {
+                    username: player.get("username")?.as_str()?.to_string(),
+                    wins: player.get("wins").and_then(Value::as_u64).unwrap_or(0),
+                    average: MatchStats::from_value(player.get("stats")),
+                    tr_before: tr(&id, 0),
+                    tr_after: tr(&id, 1),
+                    id,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let rounds = record
+            .pointer("/results/rounds")
+            .and_then(Value::as_array)
+            .map(|rounds| {
+                rounds
+                    .iter()
+                    .filter_map(Value::as_array)
+                    .map(|round| {
+                        round
+                            .iter()
+                            .filter_map(|player| {
+                                Some(RoundPlayer {
+                                    id: player.get("id")?.as_str()?.to_string(),
+                                    stats: MatchStats::from_value(player.get("stats")),
+                                    alive: player.get("alive").and_then(Value::as_bool).unwrap_or(false),
+                                })
+                            })
+                            .collect()
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        (players.len() == 2).then_some(Self { replay_id, players, rounds })
+    }
+
+    /// Score, TR changes and stats of every round.
+    pub async fn embed(&self, context: &Context<'_>) -> anyhow::Result<Embed> {
+        let [left, right] = &self.players[..] else {
+            return Err(anyhow::anyhow!("match {} doesn't have two players", self.replay_id));
+        };
+
+        let tr_changes = self
+            .players
+            .iter()
+            .filter_map(|player| {
+                let (before, after) = (player.tr_before?, player.tr_after?);
+                let change = after - before;
+                Some(format!(
+                    "**{}**: {before:.0} → {after:.0} TR ({}{change:.0})",
+                    player.username,
+                    if change >= 0.0 { "+" } else { "" }
+                ))
+            })
+            .collect::<Vec<_>>();
+
+        let stats_of = |round: &[RoundPlayer], player: &MatchPlayer| {
+            round.iter().find(|stats| stats.id == player.id).cloned()
+        };
+        let rows = std::iter::once(vec![
+            "Round".to_string(),
+            left.username.clone(),
+            right.username.clone(),
+            "Winner".to_string(),
+        ])
+        .chain(self.rounds.iter().enumerate().map(|(index, round)| {
+            let (left_round, right_round) = (stats_of(round, left), stats_of(round, right));
+            let winner = match (&left_round, &right_round) {
+                (Some(left_round), _) if left_round.alive => left.username.clone(),
+                (_, Some(right_round)) if right_round.alive => right.username.clone(),
+                _ => "?".to_string(),
+            };
+            vec![
+                (index + 1).to_string(),
+                left_round.map(|round| round.stats.format()).unwrap_or_default(),
+                right_round.map(|round| round.stats.format()).unwrap_or_default(),
+                winner,
+            ]
+        }))
+        .chain(std::iter::once(vec![
+            "Avg".to_string(),
+            left.average.format(),
+            right.average.format(),
+            String::new(),
+        ]))
+        .collect::<Vec<_>>();
+
+        let mut description = tr_changes.join("\n");
+        description.push_str(&format!("\n```\n{}\n```\nAPM / PPS / VS", format_table(&rows)));
+
+        Ok(create_embed(Some(0x3498db), context)
+            .await?
+            .title(format!("{} {} - {} {}", left.username, left.wins, right.wins, right.username))
+            .url(format!("https://tetr.io/#r:{}", self.replay_id))
+            .description(description)
+            .build())
+    }
+}
+
+/// The recent tetra league matches of a player, the most recent first.
+pub async fn fetch_recent_matches(client: &reqwest::Client, tetrio_id: &str) -> anyhow::Result<Vec<LeagueMatch>> {
+    let response: Value = client
+        .get(url_with_segments(USERS_URL, &[tetrio_id, "records", "league", "recent"])?)
+        .send()
+        .await?
+        .json()
+        .await?;
+
+    if let Some(error) = response.get("error") {
+        return Err(anyhow::anyhow!("couldn't fetch the recent matches of {tetrio_id}: {error}"));
+    }
+
+    Ok(response
+        .pointer("/data/entries")
+        .and_then(Value::as_array)
+        .map(|entries| entries.iter().filter_map(LeagueMatch::from_record).collect())
+        .unwrap_or_default())
+}
+
+fn client() -> reqwest::Result<reqwest::Client> {
+    reqwest::Client::builder().timeout(TIMEOUT).build()
+}
+
+/// Posts the matches of the subscribed players that haven't been posted in their channels yet.
+pub async fn poll(context: &Context<'_>) -> anyhow::Result<()> {
+    let mut channels: HashMap<String, (String, Vec<u64>)> = HashMap::new();
+    for subscription in MatchFeedPDO::fetch_subscriptions(context).await? {
+        let Ok(channel_id) = subscription.channel_id.parse() else {
+            continue;
+        };
+        channels
+            .entry(subscription.tetrio_id)
+            .or_insert_with(|| (subscription.username, vec![]))
+            .1
+            .push(channel_id);
+    }
+
+    let client = client()?;
+    for (tetrio_id, (username, channel_ids)) in channels {
+        tokio::time::sleep(POLL_DELAY).await;
+
+        let matches = match fetch_recent_matches(&client, &tetrio_id).await {
+            Ok(matches) => matches,
+            Err(err) => {
+                log::warn!("couldn't fetch the recent matches of {username}: {err:?}");
+                continue;
+            }
+        };
+
+        for league_match in matches.iter().rev() {
+            for channel_id in &channel_ids {
+                if !MatchFeedPDO::insert_post(context, *channel_id, &league_match.replay_id).await? {
+                    continue;
+                }
+                // the match is claimed before posting so that it isn't posted twice, a failed post gives it back
+                if let Err(err) = post(*channel_id, league_match, context).await {
+                    log::warn!("couldn't post match {} in channel {channel_id}: {err:?}", league_match.replay_id);
+                    MatchFeedPDO::delete_post(context, *channel_id, &league_match.replay_id).await?;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn post(channel_id: u64, league_match: &LeagueMatch, context: &Context<'_>) -> anyhow::Result<()> {
+    let embed = league_match.embed(context).await?;
+    context.http_client.create_message(Id::new(channel_id)).embeds(&[embed])?.await?;
+    Ok(())
+}
+
+/// Marks the current recent matches of a player as posted, so that subscribing doesn't post old matches.
+pub async fn mark_recent_as_posted(channel_id: u64, tetrio_id: &str, context: &Context<'_>) -> anyhow::Result<()> {
+    for league_match in fetch_recent_matches(&client()?, tetrio_id).await? {
+        MatchFeedPDO::insert_post(context, channel_id, &league_match.replay_id).await?;
+    }
+
+    Ok(())
+}
+
+/// The match feed poller, running every `MATCH_FEED_POLL_MINUTES` minutes.
+pub fn spawn_poller(context: Arc<Context<'static>>) -> ScheduledJob {
+    let interval = interval_from_env("MATCH_FEED_POLL_MINUTES", Duration::from_secs(60), DEFAULT_POLL_INTERVAL_MINUTES);
+    ScheduledJob::spawn("match feed poller", interval, context, |context| async move { poll(&context).await })
+}
@@ -1,6 +1,33 @@
+pub mod account_link;
+pub mod aggregate_leaderboard;
+pub mod announcements;
+pub mod archetype;
 pub mod average_of_rank;
 pub mod box_commands;
+pub mod charts;
 pub mod create_embed;
 pub mod create_error_message;
+pub mod download;
+pub mod guild_members;
+pub mod image_server;
+pub mod leaderboard_browser;
+pub mod leaderboard_cache;
+pub mod leaderboard_export;
+pub mod match_feed;
+pub mod player_source;
+pub mod rank_cutoffs;
+pub mod rank_roles;
+pub mod replay_analysis;
+pub mod replay_archive;
+pub mod replay_batch;
+pub mod replay_board;
+pub mod replay_fetcher;
+pub mod scheduled_job;
+pub mod similarity;
+pub mod solo_replay;
+pub mod stat_card;
+pub mod stat_formula;
+pub mod stat_solver;
 pub mod stats;
+pub mod table;
 pub mod timer;
\ No newline at end of file
@@ -0,0 +1,419 @@
+#![cfg(feature = "tetrio")]
+//! Resolves the player a tetrio command is talking about.
+//!
+//! Every option naming a player accepts the following grammar:
+//!
+//! ```text
+//! source   := mention | average | stats | replay | tetrio
+//! mention  := "<@" ["!"] DISCORD_ID ">" [selector]
+//! average  := "$avg" [RANK] [":" COUNTRY]
+//! stats    := ["("] PPS "," APM "," VS [")"]
+//! replay   := URL ".ttrm" "#" USERNAME [":" ROUND]
+//! tetrio   := USERNAME_OR_ID [selector]
+//! selector := ":" GAME [":" ROUND]
+//! ```
+//!
+//! `GAME` is the position of a tetra league game in the recent games of the player and `ROUND` a round of
+//! that game, both starting at 1. Without a round, the stats of the whole game are used.
+//! Replay urls have to point to tetr.io or the discord cdn.
+
+use std::fmt::Display;
+
+use anyhow::anyhow;
+use itertools::Itertools;
+use tetrio_api::{
+    http::parameters::personal_user_records::{PersonalLeaderboard, PersonalRecordsQuery},
+    models::users::user_rank::UserRank,
+};
+use twilight_interactions::command::ResolvedUser;
+use twilight_model::gateway::payload::incoming::InteractionCreate;
+
+use crate::context::Context;
+
+use super::{
+    average_of_rank::{average_of_rank, average_of_rank_in_country},
+    download::{download, is_trusted_url, MAX_REPLAY_SIZE},
+    replay_analysis::ReplayAnalysis,
+    stats::PlayerStats,
+};
+
+/// Which tetra league game of a player to take the stats from, starting at 1.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct GameSelector {
+    pub game: Option<usize>,
+    pub round: Option<usize>,
+    /// Tetra league games won and played, for the players read from their tetrio account
+    pub games: Option<(f64, f64)>,
+}
+
+impl GameSelector {
+    pub fn new(game: Option<i64>, round: Option<i64>) -> Self {
+        Self {
+            game: game.map(|game| game.max(1) as usize),
+            round: round.map(|round| round.max(1) as usize),
+        }
+    }
+
+    fn parse(params: &[&str]) -> anyhow::Result<Self> {
+        let parse = |param: Option<&&str>| {
+            param
+                .map(|param| {
+                    param
+                        .trim()
+                        .parse::<usize>()
+                        .map(|value| value.max(1))
+                        .map_err(|_| anyhow!("❌ `{param}` is not a valid game or round number"))
+                })
+                .transpose()
+        };
+
+        Ok(Self {
+            game: parse(params.first())?,
+            round: parse(params.get(1))?,
+        })
+    }
+
+    fn suffix(&self) -> String {
+        match (self.game, self.round) {
+            (Some(game), Some(round)) => format!(":{game}:{round}"),
+            (Some(game), None) => format!(":{game}"),
+            _ => String::new(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum PlayerSource {
+    Tetrio { user: String, selector: GameSelector },
+    Discord { id: u64, selector: GameSelector },
+    Stats { pps: f64, apm: f64, vs: f64 },
+    Average { rank: Option<UserRank>, country: Option<String> },
+    Replay { url: String, username: String, round: Option<usize> },
+}
+
+/// The stats of a player, along with where they came from.
+#[derive(Clone)]
+pub struct ResolvedPlayer {
+    pub name: String,
+    pub stats: PlayerStats,
+    pub avatar_url: Option<String>,
+    /// Link to the tetra league game or replay the stats were taken from
+    pub replay_url: Option<String>,
+    pub round: Option<usize>,
+}
+
+impl PlayerSource {
+    /// Parses a source following the grammar of the module, errors are meant to be shown to the user.
+    pub fn parse(input: &str) -> anyhow::Result<Self> {
+        let input = input.trim();
+
+        if input.is_empty() {
+            return Err(anyhow!("❌ No player was given"));
+        }
+
+        if let Some(average) = input.strip_prefix("$avg") {
+            let (rank, country) = match average.split_once(':') {
+                Some((rank, country)) => (rank, Some(country.trim().to_uppercase())),
+                None => (average, None),
+            };
+
+            let rank = match rank.trim() {
+                "" => None,
+                rank => match serde_json::from_str::<UserRank>(&format!("\"{}\"", rank.to_lowercase())) {
+                    Ok(rank) => Some(rank),
+                    Err(err) => return Err(anyhow!("❌ Couldn't find rank in {rank} because {err}")),
+                },
+            };
+
+            return Ok(Self::Average {
+                rank,
+                country: country.filter(|country| !country.is_empty()),
+            });
+        }
+
+        if input.starts_with("http://") || input.starts_with("https://") {
+            let Some((url, player)) = input.split_once('#') else {
+                return Err(anyhow!("❌ Replay urls need the player to use, e.g `<url>#username` or `<url>#username:round`"));
+            };
+
+            if !url.ends_with(".ttrm") {
+                return Err(anyhow!("❌ Only .ttrm replays are supported"));
+            }
+            if !is_trusted_url(url) {
+                return Err(anyhow!("❌ Replays can only be downloaded from tetr.io or discord"));
+            }
+
+            let (username, round) = match player.split_once(':') {
+                Some((username, round)) => (
+                    username,
+                    Some(
+                        round
+                            .trim()
+                            .parse::<usize>()
+                            .map_err(|_| anyhow!("❌ `{round}` is not a valid round number"))?
+                            .max(1),
+                    ),
+                ),
+                None => (player, None),
+            };
+
+            return Ok(Self::Replay {
+                url: url.to_string(),
+                username: username.trim().to_lowercase(),
+                round,
+            });
+        }
+
+        let params = input.split(':').collect_vec();
+
+        if let Some(mention) = params[0].trim().strip_prefix("<@") {
+            let id = mention.trim_start_matches('!').trim_end_matches('>');
+            let Ok(id) = id.parse::<u64>() else {
+                return Err(anyhow!("❌ Couldn't find discord user <@{id}>"));
+            };
+
+            return Ok(Self::Discord {
+                id,
+                selector: GameSelector::parse(&params[1..])?,
+            });
+        }
+
+        if let Some((pps, apm, vs)) = input
+            .trim_start_matches('(')
+            .trim_end_matches(')')
+            .split(',')
+            .collect_tuple()
+        {
+            let parse = |value: &str| {
+                value
+                    .trim()
+                    .parse::<f64>()
+                    .map_err(|_| anyhow!("❌ Couldn't parse stats {input}"))
+            };
+
+            return Ok(Self::Stats {
+                pps: parse(pps)?,
+                apm: parse(apm)?,
+                vs: parse(vs)?,
+            });
+        }
+
+        Ok(Self::Tetrio {
+            user: params[0].trim().to_lowercase(),
+            selector: GameSelector::parse(&params[1..])?,
+        })
+    }
+
+    pub async fn resolve(
+        &self,
+        context: &Context<'_>,
+    ) -> anyhow::Result<anyhow::Result<ResolvedPlayer>> {
+        match self {
+            PlayerSource::Tetrio { user, selector } => {
+                resolve_tetrio_user(user, *selector, context).await
+            }
+            PlayerSource::Discord { id, selector } => {
+                let user = match resolve_discord_user(*id, context).await? {
+                    Ok(user) => user,
+                    Err(err) => return Ok(Err(err)),
+                };
+                resolve_tetrio_user(&user, *selector, context).await
+            }
+            PlayerSource::Stats { pps, apm, vs } => Ok(Ok(ResolvedPlayer {
+                name: format!("{pps},{apm},{vs}"),
+                stats: PlayerStats {
+                    apm: *apm,
+                    pps: *pps,
+                    vs: *vs,
+                    rd: None,
+                    tr: None,
+                    glicko: None,
+                    rank: None,
+                },
+                avatar_url: None,
+                replay_url: None,
+                round: None,
+                games: None,
+            })),
+            PlayerSource::Average { rank, country } => {
+                let stats = match country {
+                    Some(country) => average_of_rank_in_country(rank.clone(), country, context).await?,
+                    None => average_of_rank(rank.clone(), context).await?,
+                };
+                let stats = match stats {
+                    Ok(stats) => stats,
+                    Err(err) => return Ok(Err(err)),
+                };
+
+                let name = format!(
+                    "$avg{}{}",
+                    rank.as_ref().map(|rank| rank.to_string()).unwrap_or_default(),
+                    country.as_ref().map(|country| format!(":{country}")).unwrap_or_default()
+                );
+
+                Ok(Ok(ResolvedPlayer {
+                    name,
+                    stats: stats.0.into(),
+                    avatar_url: None,
+                    replay_url: None,
+                    round: None,
+                    games: None,
+                }))
+            }
+            PlayerSource::Replay { url, username, round } => {
+                let bytes = match download(url, MAX_REPLAY_SIZE).await? {
+                    Ok(bytes) => bytes,
+                    Err(err) => return Ok(Err(err)),
+                };
+                let stats = match replay_stats(&bytes, username, *round) {
+                    Ok(stats) => stats,
+                    Err(err) => return Ok(Err(err)),
+                };
+
+                Ok(Ok(ResolvedPlayer {
+                    name: match round {
+                        Some(round) => format!("{username}#{round}"),
+                        None => username.clone(),
+                    },
+                    stats,
+                    avatar_url: None,
+                    replay_url: Some(url.clone()),
+                    round: *round,
+                    games: None,
+                }))
+            }
+        }
+    }
+}
+
+/// Url of the avatar of a tetrio user, users without an avatar have a revision of 0.
+pub fn avatar_url<R: Display + Default + PartialEq>(id: &str, avatar_revision: R) -> Option<String> {
+    (avatar_revision != R::default())
+        .then(|| format!("https://tetr.io/user-content/avatars/{id}.jpg?rv={avatar_revision}"))
+}
+
+/// The discord user given to a command, or the caller so their linked account is used by default.
+pub fn discord_user_or_caller(user: Option<&ResolvedUser>, interaction: &InteractionCreate) -> anyhow::Result<u64> {
+    user.map(|user| user.resolved.id.get())
+        .or_else(|| interaction.author_id().map(|id| id.get()))
+        .ok_or_else(|| anyhow!("❌ Give a user, the author of the command is unknown"))
+}
+
+/// A player source given to a command, or a mention of the caller so their linked account is used by default.
+pub fn source_or_caller(source: Option<String>, interaction: &InteractionCreate) -> anyhow::Result<String> {
+    match source {
+        Some(source) => Ok(source),
+        None => discord_user_or_caller(None, interaction).map(|id| format!("<@{id}>")),
+    }
+}
+
+/// Finds the tetrio id linked to a discord account, with `/link` or else with the public discord connection.
+pub async fn resolve_discord_user(
+    id: u64,
+    context: &Context<'_>,
+) -> anyhow::Result<anyhow::Result<String>> {
+    #[cfg(feature = "database")]
+    match crate::services::tetrio_link::TetrioLinkPDO::fetch_link(context, id).await {
+        Ok(Some(link)) => return Ok(Ok(link.tetrio_id)),
+        Ok(None) => {}
+        Err(err) => log::warn!("couldn't fetch the tetrio link of {id}: {err:?}"),
+    }
+
+    let discord_user = context
+        .tetrio_client
+        .search_discord_user(&id.to_string())
+        .await?;
+
+    match &discord_user.data {
+        Some(data) => Ok(Ok(data.user.id.to_string())),
+        None => Ok(Err(anyhow!("❌ Couldn't find tetrio user linked to discord user <@{id}>, they might have not linked their discord account to their tetrio account or can use /link"))),
+    }
+}
+
+async fn resolve_tetrio_user(
+    user_name: &str,
+    selector: GameSelector,
+    context: &Context<'_>,
+) -> anyhow::Result<anyhow::Result<ResolvedPlayer>> {
+    let user = context
+        .tetrio_client
+        .fetch_user_info(&user_name.to_lowercase())
+        .await?;
+
+    if let Some(err) = &user.error {
+        return Ok(Err(anyhow!("❌ Couldn't find user data for {user_name} because {}", err.msg)));
+    }
+
+    let Some(data) = &user.data else {
+        return Ok(Err(anyhow!("❌ No data has been found. User might be anonymous or banned.")));
+    };
+    let id = &data.id;
+
+    let tetrio_league_summary = context.tetrio_client.fetch_user_league_summaries(id).await?;
+
+    let Some(league_data) = &tetrio_league_summary.data else {
+        return Ok(Err(anyhow!("❌ No data has been found. User might be anonymous or banned.")));
+    };
+
+    let (Some(mut pps), Some(mut apm), Some(mut vs)) = (league_data.pps, league_data.apm, league_data.vs) else {
+        return Ok(Err(anyhow!("❌ {user_name} doesn't have a valid tetra league record")));
+    };
+
+    let mut replay_url = None;
+
+    if let Some(tetra_league_game) = selector.game {
+        let game = context.tetrio_client.fetch_user_personal_league_records(id, PersonalLeaderboard::Recent, PersonalRecordsQuery::None).await?;
+        let Some(game_data) = game.data else {
+            return Ok(Err(anyhow!("❌ Couldn't find tetra league game")));
+        };
+        let Some(records) = game_data.entries.get(tetra_league_game - 1) else {
+            return Ok(Err(anyhow!("❌ Couldn't find tetra league game")));
+        };
+
+        replay_url = Some(format!("https://tetr.io/#r:{}", records.replayid));
+
+        if let Some(tetra_league_round) = selector.round {
+            let Some(round) = records.results.rounds.get(tetra_league_round - 1) else {
+                return Ok(Err(anyhow!("❌ Invalid round!")));
+            };
+
+            let Some(round) = round.iter().find(|user| &user.id == id) else {
+                return Ok(Err(anyhow!("❌ Couldn't find stats!")));
+            };
+
+            pps = round.stats.pps;
+            apm = round.stats.apm;
+            vs = round.stats.vsscore;
+        } else {
+            let Some(player) = records.results.leaderboard.iter().find(|user| &user.id == id) else {
+                return Ok(Err(anyhow!("❌ Couldn't find tetra league game")));
+            };
+
+            pps = player.stats.pps.unwrap_or(0.0);
+            apm = player.stats.apm.unwrap_or(0.0);
+            vs = player.stats.vsscore.unwrap_or(0.0);
+        }
+    }
+
+    Ok(Ok(ResolvedPlayer {
+        name: format!("{}{}", data.username, selector.suffix()),
+        stats: PlayerStats {
+            apm,
+            pps,
+            vs,
+            rd: league_data.rd,
+            tr: league_data.tr,
+            glicko: league_data.glicko,
+            rank: league_data.rank.clone(),
+        },
+        avatar_url: avatar_url(&id.to_string(), data.avatar_revision.unwrap_or_default()),
+        replay_url,
+        round: selector.round,
+        games: Some((league_data.gameswon as f64, league_data.gamesplayed as f64)),
+    }))
+}
+
+/// Stats of a player in a ttrm replay, from a round starting at 1 or averaged over every round.
+pub fn replay_stats(bytes: &[u8], username: &str, round: Option<usize>) -> anyhow::Result<PlayerStats> {
+    ReplayAnalysis::parse(bytes)?.player(username)?.stats(round)
+}
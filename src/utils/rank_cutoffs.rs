@@ -0,0 +1,106 @@
+#![cfg(all(feature = "tetrio", feature = "database"))]
+//! History of the cutoffs of the ranks, snapshotted from the tetra league ranks of tetrio labs.
+
+use std::{sync::Arc, time::Duration};
+
+use anyhow::anyhow;
+use tetrio_api::models::packet::Packet;
+
+use crate::{context::Context, services::rank_cutoff::RankCutoffPDO};
+
+use super::{
+    aggregate_leaderboard::RANKS,
+    scheduled_job::{interval_from_env, ScheduledJob},
+};
+
+const DEFAULT_SNAPSHOT_INTERVAL_HOURS: u64 = 1;
+
+/// Stores the current cutoff of every rank.
+pub async fn take_snapshot(context: &Context<'_>) -> anyhow::Result<()> {
+    let Packet { data: Some(data), .. } = context.tetrio_client.fetch_leagueranks().await? else {
+        return Err(anyhow!("Couldn't find user ranks data!"));
+    };
+
+    for rank in &RANKS {
+        let Some(league_rank) = data.data.ranks.get(rank) else {
+            continue;
+        };
+        RankCutoffPDO::insert_snapshot(
+            context,
+            &rank.to_string(),
+            league_rank.tr,
+            league_rank.targettr,
+            league_rank.percentile,
+            league_rank.count as i64,
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// The cutoff snapshot job, running every `RANK_CUTOFF_SNAPSHOT_HOURS` hours.
+pub fn spawn_snapshot_job(context: Arc<Context<'static>>) -> ScheduledJob {
+    let interval = interval_from_env(
+        "RANK_CUTOFF_SNAPSHOT_HOURS",
+        Duration::from_secs(60 * 60),
+        DEFAULT_SNAPSHOT_INTERVAL_HOURS,
+    );
+    ScheduledJob::spawn("rank cutoff snapshots", interval, context, |context| async move {
+        take_snapshot(&context).await
+    })
+}
+
+/// Unix timestamp of the end of the current season, from the `SEASON_END` environment variable.
+pub fn season_end() -> Option<i64> {
+    std::env::var("SEASON_END").ok()?.trim().parse().ok()
+}
+
+/// Least squares line through the snapshots of a cutoff.
+#[derive(Clone, Copy, Debug)]
+pub struct Trend {
+    /// TR per second
+    pub slope: f64,
+    /// TR at `origin`
+    pub intercept: f64,
+    /// Timestamp of the first snapshot, the others are relative to it to keep the sums precise
+    pub origin: i64,
+}
+
+impl Trend {
+    /// Fits `(timestamp, tr)` points, `None` with less than two distinct timestamps.
+    pub fn fit(points: &[(i64, f64)]) -> Option<Self> {
+        if points.len() < 2 {
+            return None;
+        }
+
+        let origin = points[0].0;
+        let count = points.len() as f64;
+        let x = |timestamp: i64| (timestamp - origin) as f64;
+        let mean_x = points.iter().map(|(timestamp, _)| x(*timestamp)).sum::<f64>() / count;
+        let mean_y = points.iter().map(|(_, tr)| tr).sum::<f64>() / count;
+
+        let covariance = points
+            .iter()
+            .map(|(timestamp, tr)| (x(*timestamp) - mean_x) * (tr - mean_y))
+            .sum::<f64>();
+        let variance = points
+            .iter()
+            .map(|(timestamp, _)| (x(*timestamp) - mean_x).powi(2))
+            .sum::<f64>();
+        if variance == 0.0 {
+            return None;
+        }
+
+        let slope = covariance / variance;
+        Some(Self {
+            slope,
+            intercept: mean_y - slope * mean_x,
+            origin,
+        })
+    }
+
+    pub fn at(&self, timestamp: i64) -> f64 {
+        self.intercept + self.slope * (timestamp - self.origin) as f64
+    }
+}
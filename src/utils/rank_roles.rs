@@ -0,0 +1,290 @@
+#![cfg(all(feature = "tetrio", feature = "database"))]
+//! Discord roles given from the tetra league standing of linked members.
+//!
+//! Each guild maps roles to a rank, a TR band or a country. The sync goes through the linked members of the
+//! guild, gives them the roles they match and takes away the mapped roles they don't match anymore. Roles that
+//! aren't mapped are never touched.
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    time::Duration,
+};
+
+use tetrio_api::models::users::user_rank::UserRank;
+use twilight_http::{error::ErrorType, request::AuditLogReason};
+use twilight_model::id::Id;
+
+use crate::{
+    context::Context,
+    models::rank_role::RankRoleData,
+    services::{rank_role::RankRolePDO, tetrio_link::TetrioLinkPDO},
+};
+
+use super::scheduled_job::{interval_from_env, ScheduledJob};
+
+/// Members synced before waiting, to leave room to the commands in the discord rate limits
+const SYNC_BATCH_SIZE: usize = 10;
+const SYNC_BATCH_DELAY: Duration = Duration::from_secs(2);
+const DEFAULT_SYNC_INTERVAL_HOURS: u64 = 6;
+
+/// What the roles of a member are given from.
+#[derive(Clone, Debug)]
+pub struct LeagueStanding {
+    pub rank: Option<UserRank>,
+    pub tr: Option<f64>,
+    pub country: Option<String>,
+}
+
+/// A role given to or taken from a member.
+#[derive(Clone, Debug)]
+pub struct RoleChange {
+    pub discord_id: u64,
+    pub role_id: u64,
+    pub added: bool,
+    pub reason: String,
+}
+
+#[derive(Default, Debug)]
+pub struct SyncReport {
+    /// Linked members found in the guild
+    pub members: usize,
+    pub changes: Vec<RoleChange>,
+    /// Members or changes skipped because tetrio or discord failed
+    pub errors: usize,
+}
+
+impl RankRoleData {
+    pub fn matches(&self, standing: &LeagueStanding) -> bool {
+        let rank = match (&self.rank, &standing.rank) {
+            (Some(rule), Some(rank)) => rank.to_string().eq_ignore_ascii_case(rule),
+            (Some(_), None) => false,
+            (None, _) => true,
+        };
+        let tr = match standing.tr {
+            Some(tr) => self.min_tr.map_or(true, |min| tr >= min) && self.max_tr.map_or(true, |max| tr < max),
+            None => self.min_tr.is_none() && self.max_tr.is_none(),
+        };
+        let country = match (&self.country, &standing.country) {
+            (Some(rule), Some(country)) => country.eq_ignore_ascii_case(rule),
+            (Some(_), None) => false,
+            (None, _) => true,
+        };
+
+        rank && tr && country
+    }
+
+    /// The conditions of the role, like `rank X, 20000 ≤ TR < 22000`.
+    pub fn describe(&self) -> String {
+        let mut conditions = vec![];
+        if let Some(rank) = &self.rank {
+            conditions.push(format!("rank {}", rank.to_uppercase()));
+        }
+        match (self.min_tr, self.max_tr) {
+            (Some(min), Some(max)) => conditions.push(format!("{min:.0} ≤ TR < {max:.0}")),
+            (Some(min), None) => conditions.push(format!("TR ≥ {min:.0}")),
+            (None, Some(max)) => conditions.push(format!("TR < {max:.0}")),
+            (None, None) => {}
+        }
+        if let Some(country) = &self.country {
+            conditions.push(format!("country {}", country.to_uppercase()));
+        }
+
+        conditions.join(", ")
+    }
+}
+
+/// Standings already fetched during a sync, a member linked in several guilds is fetched once.
+#[derive(Default)]
+pub struct StandingCache {
+    standings: HashMap<String, Option<LeagueStanding>>,
+}
+
+impl StandingCache {
+    /// `None` when the account has no tetra league data, errors mean the roles of the member should be left as is.
+    async fn fetch(
+        &mut self,
+        tetrio_id: &str,
+        with_country: bool,
+        context: &Context<'_>,
+    ) -> anyhow::Result<Option<LeagueStanding>> {
+        if let Some(standing) = self.standings.get(tetrio_id) {
+            let has_country = standing.as_ref().map_or(true, |standing| standing.country.is_some());
+            if has_country || !with_country {
+                return Ok(standing.clone());
+            }
+        }
+
+        let summary = context.tetrio_client.fetch_user_league_summaries(tetrio_id).await?;
+        let standing = match &summary.data {
+            Some(league) => {
+                let country = if with_country {
+                    let user = context.tetrio_client.fetch_user_info(tetrio_id).await?;
+                    user.data.and_then(|data| data.country)
+                } else {
+                    None
+                };
+                Some(LeagueStanding {
+                    rank: league.rank.clone(),
+                    tr: league.tr,
+                    country,
+                })
+            }
+            None => None,
+        };
+
+        self.standings.insert(tetrio_id.to_string(), standing.clone());
+        Ok(standing)
+    }
+}
+
+/// Roles to add and remove so that the member has exactly the mapped roles they match.
+fn plan_member(
+    discord_id: u64,
+    member_roles: &[u64],
+    rules: &[RankRoleData],
+    standing: Option<&LeagueStanding>,
+) -> Vec<RoleChange> {
+    rules
+        .iter()
+        .filter_map(|rule| {
+            let role_id = rule.role_id.parse::<u64>().ok()?;
+            let matches = standing.is_some_and(|standing| rule.matches(standing));
+            let has_role = member_roles.contains(&role_id);
+
+            match (matches, has_role) {
+                (true, false) => Some(RoleChange {
+                    discord_id,
+                    role_id,
+                    added: true,
+                    reason: format!("matches {}", rule.describe()),
+                }),
+                (false, true) => Some(RoleChange {
+                    discord_id,
+                    role_id,
+                    added: false,
+                    reason: match standing {
+                        Some(_) => format!("no longer matches {}", rule.describe()),
+                        None => "no tetra league standing".to_string(),
+                    },
+                }),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// The roles of a member of the guild, `None` when they are not in it.
+async fn member_roles(guild_id: u64, discord_id: u64, context: &Context<'_>) -> anyhow::Result<Option<Vec<u64>>> {
+    match context.http_client.guild_member(Id::new(guild_id), Id::new(discord_id)).await {
+        Ok(response) => {
+            let member = response.model().await?;
+            Ok(Some(member.roles.iter().map(|role| role.get()).collect()))
+        }
+        Err(err) if matches!(err.kind(), ErrorType::Response { status, .. } if status.get() == 404) => Ok(None),
+        Err(err) => Err(err.into()),
+    }
+}
+
+async fn apply_change(guild_id: u64, change: &RoleChange, context: &Context<'_>) -> anyhow::Result<()> {
+    let reason = format!("Rank roles: {}", change.reason);
+    let (guild, user, role) = (Id::new(guild_id), Id::new(change.discord_id), Id::new(change.role_id));
+
+    if change.added {
+        context.http_client.add_guild_member_role(guild, user, role).reason(&reason)?.await?;
+    } else {
+        context.http_client.remove_guild_member_role(guild, user, role).reason(&reason)?.await?;
+    }
+
+    RankRolePDO::insert_audit(context, guild_id, change.discord_id, change.role_id, change.added, &change.reason).await
+}
+
+/// Syncs the linked members of the guild, or only lists the changes when `apply` is false.
+pub async fn sync_guild(
+    guild_id: u64,
+    apply: bool,
+    standings: &mut StandingCache,
+    context: &Context<'_>,
+) -> anyhow::Result<SyncReport> {
+    let mut report = SyncReport::default();
+    let rules = RankRolePDO::fetch_rank_roles(context, guild_id).await?;
+    if rules.is_empty() {
+        return Ok(report);
+    }
+    let with_country = rules.iter().any(|rule| rule.country.is_some());
+    // the links are kept to the members of the guild, most linked users aren't in it
+    let members = context.member_cache.members(guild_id, context).await?.iter().copied().collect::<HashSet<_>>();
+    let links = TetrioLinkPDO::fetch_links(context)
+        .await?
+        .into_iter()
+        .filter_map(|link| Some((link.discord_id.parse::<u64>().ok()?, link)))
+        .filter(|(discord_id, _)| members.contains(discord_id))
+        .collect::<Vec<_>>();
+
+    for (index, batch) in links.chunks(SYNC_BATCH_SIZE).enumerate() {
+        if index > 0 {
+            tokio::time::sleep(SYNC_BATCH_DELAY).await;
+        }
+
+        for (discord_id, link) in batch {
+            let discord_id = *discord_id;
+            let roles = match member_roles(guild_id, discord_id, context).await {
+                Ok(Some(roles)) => roles,
+                Ok(None) => continue,
+                Err(err) => {
+                    log::warn!("couldn't fetch member {discord_id} of guild {guild_id}: {err:?}");
+                    report.errors += 1;
+                    continue;
+                }
+            };
+            report.members += 1;
+
+            let standing = match standings.fetch(&link.tetrio_id, with_country, context).await {
+                Ok(standing) => standing,
+                Err(err) => {
+                    log::warn!("couldn't fetch the standing of {}: {err:?}", link.username);
+                    report.errors += 1;
+                    continue;
+                }
+            };
+
+            for change in plan_member(discord_id, &roles, &rules, standing.as_ref()) {
+                if apply {
+                    if let Err(err) = apply_change(guild_id, &change, context).await {
+                        log::warn!("couldn't update role {} of {discord_id} in guild {guild_id}: {err:?}", change.role_id);
+                        report.errors += 1;
+                        continue;
+                    }
+                }
+                report.changes.push(change);
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+/// Syncs every guild with rank roles.
+pub async fn sync_all(context: &Context<'_>) -> anyhow::Result<()> {
+    let mut standings = StandingCache::default();
+
+    for guild_id in RankRolePDO::fetch_guilds(context).await? {
+        match sync_guild(guild_id, true, &mut standings, context).await {
+            Ok(report) => log::info!(
+                "synced rank roles of guild {guild_id}: {} members, {} changes, {} errors",
+                report.members,
+                report.changes.len(),
+                report.errors
+            ),
+            Err(err) => log::error!("couldn't sync rank roles of guild {guild_id}: {err:?}"),
+        }
+    }
+
+    Ok(())
+}
+
+/// The scheduled sync, running every `RANK_ROLE_SYNC_HOURS` hours.
+pub fn spawn_sync_job(context: Arc<Context<'static>>) -> ScheduledJob {
+    let interval = interval_from_env("RANK_ROLE_SYNC_HOURS", Duration::from_secs(60 * 60), DEFAULT_SYNC_INTERVAL_HOURS);
+    ScheduledJob::spawn("rank role sync", interval, context, |context| async move { sync_all(&context).await })
+}
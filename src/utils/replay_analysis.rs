@@ -0,0 +1,237 @@
+#![cfg(feature = "tetrio")]
+//! Per-round and per-player stats of ttrm replays.
+
+use anyhow::anyhow;
+use common::replay::ttrm::models::{events::Event, Root};
+use serde_json::Value;
+
+use super::stats::{calculate_stats, PlayerStats, Stats};
+
+/// Stats of a player during a single round.
+#[derive(Clone, Debug)]
+pub struct RoundStats {
+    /// Starting at 1
+    pub round: usize,
+    pub pieces: f64,
+    pub lines: f64,
+    pub attack: f64,
+    pub garbage_received: f64,
+    /// In seconds
+    pub time: f64,
+    pub apm: f64,
+    pub pps: f64,
+    pub vs: f64,
+}
+
+impl RoundStats {
+    pub fn player_stats(&self) -> PlayerStats {
+        PlayerStats {
+            apm: self.apm,
+            pps: self.pps,
+            vs: self.vs,
+            rd: None,
+            tr: None,
+            glicko: None,
+            rank: None,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct PlayerAnalysis {
+    pub username: String,
+    pub rounds: Vec<RoundStats>,
+}
+
+impl PlayerAnalysis {
+    /// Mean of the APM, PPS and VS of every round, each round having the same weight.
+    pub fn average(&self) -> PlayerStats {
+        let count = self.rounds.len().max(1) as f64;
+        let (apm, pps, vs) = self.rounds.iter().fold((0.0, 0.0, 0.0), |(apm, pps, vs), round| {
+            (apm + round.apm, pps + round.pps, vs + round.vs)
+        });
+
+        PlayerStats {
+            apm: apm / count,
+            pps: pps / count,
+            vs: vs / count,
+            rd: None,
+            tr: None,
+            glicko: None,
+            rank: None,
+        }
+    }
+
+    /// Sum of the counters of every round, the rates are the ones of the average.
+    pub fn total(&self) -> RoundStats {
+        let average = self.average();
+        self.rounds.iter().fold(
+            RoundStats {
+                round: 0,
+                pieces: 0.0,
+                lines: 0.0,
+                attack: 0.0,
+                garbage_received: 0.0,
+                time: 0.0,
+                apm: average.apm,
+                pps: average.pps,
+                vs: average.vs,
+            },
+            |total, round| RoundStats {
+                pieces: total.pieces + round.pieces,
+                lines: total.lines + round.lines,
+                attack: total.attack + round.attack,
+                garbage_received: total.garbage_received + round.garbage_received,
+                time: total.time + round.time,
+                ..total
+            },
+        )
+    }
+
+    /// Stats from a round starting at 1, or the average of every round.
+    pub fn stats(&self, round: Option<usize>) -> anyhow::Result<PlayerStats> {
+        match round {
+            Some(round) => self
+                .rounds
+                .iter()
+                .find(|stats| stats.round == round)
+                .map(RoundStats::player_stats)
+                .ok_or_else(|| anyhow!("❌ Couldn't find round {round} for {} in the replay", self.username)),
+            None if self.rounds.is_empty() => Err(anyhow!("❌ Couldn't find user in replay")),
+            None => Ok(self.average()),
+        }
+    }
+
+    pub fn derived(&self, round: Option<usize>) -> anyhow::Result<Stats> {
+        self.stats(round).map(calculate_stats)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ReplayAnalysis {
+    pub rounds: usize,
+    pub players: Vec<PlayerAnalysis>,
+    /// Username of the winner of each round, when the replay tells it
+    pub winners: Vec<Option<String>>,
+    /// When the game was played, as written in the replay
+    pub ts: Option<String>,
+}
+
+/// Winner of a round from the raw json, the typed models don't describe the round results.
+///
+/// The round board lists the players with a `success` flag, older replays only end the winner with a `winner` reason.
+fn round_winner(round: &Value) -> Option<String> {
+    let username = |value: &Value| {
+        value
+            .pointer("/user/username")
+            .or_else(|| value.get("username"))
+            .and_then(Value::as_str)
+            .map(str::to_string)
+    };
+
+    let from_board = round
+        .get("board")
+        .and_then(Value::as_array)
+        .and_then(|board| {
+            board
+                .iter()
+                .find(|player| player.get("success").and_then(Value::as_bool) == Some(true))
+        })
+        .and_then(username);
+
+    from_board.or_else(|| {
+        round
+            .get("replays")?
+            .as_array()?
+            .iter()
+            .flat_map(|replay| replay.get("events").and_then(Value::as_array).into_iter().flatten())
+            .filter(|event| event.get("type").and_then(Value::as_str) == Some("end"))
+            .find(|event| event.pointer("/data/reason").and_then(Value::as_str) == Some("winner"))
+            .and_then(|event| event.pointer("/data/export/options/username"))
+            .and_then(Value::as_str)
+            .map(str::to_string)
+    })
+}
+
+impl ReplayAnalysis {
+    pub fn parse(bytes: &[u8]) -> anyhow::Result<Self> {
+        let raw: Value = serde_json::from_slice(bytes)
+            .map_err(|err| anyhow!("❌ Couldn't parse ttrm replay: {:?}", err))?;
+        Self::from_raw(raw)
+    }
+
+    /// Analysis of a replay already read as json, for the commands that read it for something else too.
+    pub fn from_raw(raw: Value) -> anyhow::Result<Self> {
+        let ts = raw.get("ts").and_then(Value::as_str).map(str::to_string);
+        let winners = raw
+            .get("data")
+            .and_then(Value::as_array)
+            .map(|rounds| rounds.iter().map(round_winner).collect())
+            .unwrap_or_default();
+        let replay: Root = serde_json::from_value(raw)
+            .map_err(|err| anyhow!("❌ Couldn't parse ttrm replay: {:?}", err))?;
+
+        Ok(Self {
+            winners,
+            ts,
+            ..Self::from_replay(replay)
+        })
+    }
+
+    pub fn from_replay(replay: Root) -> Self {
+        let mut players: Vec<PlayerAnalysis> = vec![];
+        let rounds = replay.data.len();
+
+        for (index, round) in replay.data.into_iter().enumerate() {
+            for replay in round.replays {
+                for event in replay.events {
+                    let Event::End { data, .. } = event else {
+                        continue;
+                    };
+
+                    let stats = &data.export.stats;
+                    let round_stats = RoundStats {
+                        round: index + 1,
+                        pieces: stats.piecesplaced as f64,
+                        lines: stats.lines as f64,
+                        attack: stats.garbage.sent as f64,
+                        garbage_received: stats.garbage.received as f64,
+                        time: stats.finaltime as f64 / 1000.0,
+                        apm: data.export.aggregatestats.apm,
+                        pps: data.export.aggregatestats.pps,
+                        vs: data.export.aggregatestats.vsscore,
+                    };
+
+                    let username = data.export.options.username;
+                    match players.iter_mut().find(|player| player.username == username) {
+                        Some(player) => player.rounds.push(round_stats),
+                        None => players.push(PlayerAnalysis {
+                            username,
+                            rounds: vec![round_stats],
+                        }),
+                    }
+                }
+            }
+        }
+
+        Self {
+            rounds,
+            players,
+            winners: vec![None; rounds],
+            ts: None,
+        }
+    }
+
+    /// Winner of a round starting at 1.
+    pub fn winner(&self, round: usize) -> Option<&str> {
+        self.winners.get(round.checked_sub(1)?)?.as_deref()
+    }
+
+    /// Finds a player by username, ignoring the case.
+    pub fn player(&self, username: &str) -> anyhow::Result<&PlayerAnalysis> {
+        self.players
+            .iter()
+            .find(|player| player.username.eq_ignore_ascii_case(username))
+            .ok_or_else(|| anyhow!("❌ Couldn't find {username} in the replay"))
+    }
+}
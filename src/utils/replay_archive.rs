@@ -0,0 +1,226 @@
+#![cfg(feature = "tetrio")]
+//! Uploaded replays, kept on disk by hash and indexed in the database to be used again by their id.
+
+use anyhow::anyhow;
+use twilight_model::{channel::Attachment, gateway::payload::incoming::InteractionCreate};
+
+use crate::context::Context;
+
+use super::{
+    download::{download, MAX_REPLAY_SIZE},
+    replay_analysis::ReplayAnalysis,
+    replay_fetcher::parse_replay_id,
+    solo_replay::{format_duration, SoloMode, SoloReplay},
+};
+
+/// What is indexed about a replay to search it.
+#[derive(Clone, Debug)]
+pub struct ReplayMetadata {
+    pub filename: String,
+    /// Lowercase usernames
+    pub players: Vec<String>,
+    pub ts: Option<String>,
+    pub rounds: usize,
+    pub result: Option<String>,
+}
+
+/// Where the replay of a command comes from, the first one given is used.
+#[derive(Clone, Copy)]
+pub struct ReplayInput<'a> {
+    pub attachment: Option<&'a Attachment>,
+    /// A tetr.io replay id or url
+    pub tetrio: Option<&'a str>,
+    /// Id in the archive
+    pub archive_id: Option<i64>,
+}
+
+/// A replay given to a command, either uploaded, downloaded from tetr.io or taken from the archive.
+pub struct UploadedReplay {
+    pub filename: String,
+    pub bytes: Vec<u8>,
+    /// Id in the archive when the replay was taken from it, `None` when it still has to be archived
+    pub id: Option<i32>,
+}
+
+impl ReplayMetadata {
+    /// Metadata of a ttrm replay, from the analysis the command already made.
+    pub fn from_analysis(filename: &str, analysis: &ReplayAnalysis) -> Self {
+        let wins = |username: &str| {
+            analysis
+                .winners
+                .iter()
+                .flatten()
+                .filter(|winner| winner.eq_ignore_ascii_case(username))
+                .count()
+        };
+        let result = match analysis.players.as_slice() {
+            [left, right] if analysis.winners.iter().any(Option::is_some) => Some(format!(
+                "{} {}-{} {}",
+                left.username,
+                wins(&left.username),
+                wins(&right.username),
+                right.username
+            )),
+            _ => None,
+        };
+
+        Self {
+            filename: filename.to_string(),
+            players: analysis.players.iter().map(|player| player.username.to_lowercase()).collect(),
+            ts: analysis.ts.clone(),
+            rounds: analysis.rounds,
+            result,
+        }
+    }
+
+    /// Metadata of a ttr replay.
+    pub fn from_solo(filename: &str, replay: &SoloReplay) -> Self {
+        let result = match replay.mode {
+            SoloMode::Blitz => format!("{} {}", replay.mode, replay.stats.score),
+            _ => format!("{} {}", replay.mode, format_duration(replay.stats.final_time)),
+        };
+
+        Self {
+            filename: filename.to_string(),
+            players: vec![replay.username.to_lowercase()],
+            ts: replay.ts.clone(),
+            rounds: 1,
+            result: Some(result),
+        }
+    }
+}
+
+/// Gets the replay of a command from its attachment, from tetr.io or from the archive of the guild.
+///
+/// The replay isn't archived yet, the command archives it with [`archive_replay`] once it has read it.
+pub async fn fetch_replay(
+    input: ReplayInput<'_>,
+    interaction: &InteractionCreate,
+    context: &Context<'_>,
+) -> anyhow::Result<anyhow::Result<UploadedReplay>> {
+    let (filename, bytes) = match input {
+        ReplayInput { attachment: Some(attachment), .. } => match download(&attachment.url, MAX_REPLAY_SIZE).await? {
+            Ok(bytes) => (attachment.filename.clone(), bytes),
+            Err(err) => return Ok(Err(err)),
+        },
+        ReplayInput { tetrio: Some(replay), .. } => {
+            let Some(replay_id) = parse_replay_id(replay) else {
+                return Ok(Err(anyhow!("❌ `{replay}` is not a tetr.io replay id or url")));
+            };
+            match context.replay_fetcher.fetch(&replay_id).await {
+                Ok(replay) => (replay.filename(), replay.bytes.to_vec()),
+                Err(err) => return Ok(Err(anyhow!("❌ {err}"))),
+            }
+        }
+        #[cfg(feature = "database")]
+        ReplayInput { archive_id: Some(id), .. } => return archive::load(id, interaction, context).await,
+        #[cfg(not(feature = "database"))]
+        ReplayInput { archive_id: Some(_), .. } => {
+            return Ok(Err(anyhow!("❌ The replay archive is not available on this bot")))
+        }
+        _ => return Ok(Err(anyhow!("❌ Upload a replay, or give a tetr.io replay or the id of an archived one"))),
+    };
+    #[cfg(not(feature = "database"))]
+    let _ = interaction;
+
+    Ok(Ok(UploadedReplay { filename, bytes, id: None }))
+}
+
+/// Archives a replay read by a command, in the guild it was used in.
+///
+/// Replays taken from the archive, used outside of a server or failing to be archived are skipped, archiving never
+/// stops the command.
+pub async fn archive_replay(
+    filename: &str,
+    bytes: &[u8],
+    metadata: &ReplayMetadata,
+    interaction: &InteractionCreate,
+    context: &Context<'_>,
+) {
+    #[cfg(feature = "database")]
+    if let Some(guild_id) = interaction.guild_id {
+        if let Err(err) = archive::store(guild_id.get(), bytes, metadata, interaction, context).await {
+            log::warn!("couldn't archive replay {filename}: {err:?}");
+        }
+    }
+    #[cfg(not(feature = "database"))]
+    let _ = (filename, bytes, metadata, interaction, context);
+}
+
+impl UploadedReplay {
+    /// Archives the replay unless it was taken from the archive.
+    pub async fn archive(&self, metadata: &ReplayMetadata, interaction: &InteractionCreate, context: &Context<'_>) {
+        if self.id.is_none() {
+            archive_replay(&self.filename, &self.bytes, metadata, interaction, context).await;
+        }
+    }
+}
+
+#[cfg(feature = "database")]
+mod archive {
+    use std::path::PathBuf;
+
+    use anyhow::anyhow;
+    use sha2::{Digest, Sha256};
+    use twilight_model::gateway::payload::incoming::InteractionCreate;
+
+    use crate::{context::Context, services::replay_archive::ReplayArchivePDO};
+
+    use super::{ReplayMetadata, UploadedReplay};
+
+    fn path(hash: &str, filename: &str, context: &Context<'_>) -> PathBuf {
+        let extension = if filename.ends_with(".ttr") { "ttr" } else { "ttrm" };
+        context.replay_storage_dir.join(format!("{hash}.{extension}"))
+    }
+
+    /// Writes the replay named by its hash, so the same replay uploaded twice is kept once.
+    pub async fn store(
+        guild_id: u64,
+        bytes: &[u8],
+        metadata: &ReplayMetadata,
+        interaction: &InteractionCreate,
+        context: &Context<'_>,
+    ) -> anyhow::Result<i32> {
+        let hash = format!("{:x}", Sha256::digest(bytes));
+
+        let path = path(&hash, &metadata.filename, context);
+        if !tokio::fs::try_exists(&path).await? {
+            tokio::fs::create_dir_all(&context.replay_storage_dir).await?;
+            tokio::fs::write(&path, bytes).await?;
+        }
+
+        let uploader = interaction.author_id().map(|id| id.get());
+        ReplayArchivePDO::insert_replay(context, guild_id, &hash, metadata, uploader).await
+    }
+
+    /// A replay archived in the guild of the interaction.
+    pub async fn load(
+        id: i64,
+        interaction: &InteractionCreate,
+        context: &Context<'_>,
+    ) -> anyhow::Result<anyhow::Result<UploadedReplay>> {
+        let Some(guild_id) = interaction.guild_id else {
+            return Ok(Err(anyhow!("❌ The replay archive is only available in servers")));
+        };
+        let Ok(id) = i32::try_from(id) else {
+            return Ok(Err(anyhow!("❌ There is no replay with the id {id}")));
+        };
+        let Some(replay) = ReplayArchivePDO::fetch_replay_by_id(context, guild_id.get(), id).await? else {
+            return Ok(Err(anyhow!("❌ There is no replay with the id {id}")));
+        };
+
+        let bytes = match tokio::fs::read(path(&replay.hash, &replay.filename, context)).await {
+            Ok(bytes) => bytes,
+            Err(err) => {
+                log::error!("archived replay {id} is missing from the storage: {err}");
+                return Ok(Err(anyhow!("❌ The replay {id} is missing from the archive")));
+            }
+        };
+
+        Ok(Ok(UploadedReplay {
+            filename: replay.filename,
+            bytes,
+            id: Some(replay.id_replay),
+        }))
+    }
+}
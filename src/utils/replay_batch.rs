@@ -0,0 +1,271 @@
+#![cfg(feature = "tetrio")]
+//! Several ttrm replays uploaded at once, directly or in a zip, and the stats of a player across all of them.
+
+use std::io::{Cursor, Read};
+
+use anyhow::anyhow;
+use twilight_model::channel::Attachment;
+
+use super::{
+    download::{download, MAX_REPLAY_SIZE},
+    replay_analysis::ReplayAnalysis,
+    stats::PlayerStats,
+};
+
+/// Most replays read from a single upload, zips included
+pub const MAX_BATCH_REPLAYS: usize = 30;
+/// Most bytes read from a single upload once decompressed, a zip declaring small files can still inflate to gigabytes
+const MAX_BATCH_SIZE: usize = 128 * 1024 * 1024;
+
+/// A replay of the batch with the name of the file it came from.
+pub struct BatchReplay {
+    pub filename: String,
+    pub bytes: Vec<u8>,
+    pub analysis: ReplayAnalysis,
+}
+
+/// Stats of a single game of the session.
+pub struct SessionGame {
+    pub filename: String,
+    pub stats: PlayerStats,
+    pub rounds: usize,
+    pub rounds_won: usize,
+    /// `None` when the replay doesn't tell who won its rounds
+    pub won: Option<bool>,
+}
+
+pub struct SessionStats {
+    pub username: String,
+    pub games: Vec<SessionGame>,
+    /// Average of every round of every game, each round having the same weight
+    pub overall: PlayerStats,
+    /// Average of the game averages, each game having the same weight
+    pub per_game: PlayerStats,
+}
+
+fn too_many_replays() -> anyhow::Error {
+    anyhow!("❌ Too many replays, at most {MAX_BATCH_REPLAYS} can be analyzed at once")
+}
+
+fn too_large_batch() -> anyhow::Error {
+    anyhow!("❌ The upload is larger than {} MiB once decompressed", MAX_BATCH_SIZE / 1024 / 1024)
+}
+
+/// The replays of the zip, `read` being the replays and bytes already read from the rest of the upload.
+fn extract_zip(bytes: &[u8], read: (usize, usize)) -> anyhow::Result<Vec<(String, Vec<u8>)>> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(bytes))
+        .map_err(|err| anyhow!("❌ Couldn't open the zip: {err}"))?;
+    let (mut count, mut size) = read;
+    let mut replays = vec![];
+
+    for index in 0..archive.len() {
+        let file = archive
+            .by_index(index)
+            .map_err(|err| anyhow!("❌ Couldn't read the zip: {err}"))?;
+        if file.is_dir() || !file.name().ends_with(".ttrm") {
+            continue;
+        }
+
+        count += 1;
+        if count > MAX_BATCH_REPLAYS {
+            return Err(too_many_replays());
+        }
+
+        // the declared size can lie, the entry is read up to the limit whatever it says
+        let name = file.name().to_string();
+        let mut content = vec![];
+        file.take(MAX_REPLAY_SIZE as u64 + 1)
+            .read_to_end(&mut content)
+            .map_err(|err| anyhow!("❌ Couldn't read {name} from the zip: {err}"))?;
+        if content.len() > MAX_REPLAY_SIZE {
+            return Err(anyhow!("❌ {name} is too big to be a replay"));
+        }
+
+        size += content.len();
+        if size > MAX_BATCH_SIZE {
+            return Err(too_large_batch());
+        }
+        replays.push((name, content));
+    }
+
+    Ok(replays)
+}
+
+/// Downloads the attachments and opens the zips, every file has to be a ttrm replay or a zip of them.
+pub async fn download_replays(attachments: &[&Attachment]) -> anyhow::Result<anyhow::Result<Vec<(String, Vec<u8>)>>> {
+    if let Some(attachment) = attachments
+        .iter()
+        .find(|attachment| !attachment.filename.ends_with(".ttrm") && !attachment.filename.ends_with(".zip"))
+    {
+        return Ok(Err(anyhow!("❌ File type not supported: {:?}", attachment.filename)));
+    }
+    if attachments.len() > MAX_BATCH_REPLAYS {
+        return Ok(Err(too_many_replays()));
+    }
+
+    let downloads = attachments.iter().map(|attachment| async move {
+        let max_size = if attachment.filename.ends_with(".zip") {
+            MAX_BATCH_SIZE
+        } else {
+            MAX_REPLAY_SIZE
+        };
+        let bytes = download(&attachment.url, max_size).await?;
+        anyhow::Ok((attachment.filename.clone(), bytes))
+    });
+
+    let mut replays = vec![];
+    let mut size = 0;
+    for download in futures::future::join_all(downloads).await {
+        let (filename, bytes) = download?;
+        let bytes = match bytes {
+            Ok(bytes) => bytes,
+            Err(err) => return Ok(Err(anyhow!("{err} ({filename})"))),
+        };
+
+        if filename.ends_with(".zip") {
+            let read = (replays.len(), size);
+            match tokio::task::spawn_blocking(move || extract_zip(&bytes, read)).await? {
+                Ok(files) => {
+                    size += files.iter().map(|(_, content)| content.len()).sum::<usize>();
+                    replays.extend(files);
+                }
+                Err(err) => return Ok(Err(err)),
+            }
+        } else {
+            size += bytes.len();
+            replays.push((filename, bytes));
+        }
+
+        if replays.len() > MAX_BATCH_REPLAYS {
+            return Ok(Err(too_many_replays()));
+        }
+        if size > MAX_BATCH_SIZE {
+            return Ok(Err(too_large_batch()));
+        }
+    }
+
+    if replays.is_empty() {
+        return Ok(Err(anyhow!("❌ Couldn't find any ttrm replay in the upload")));
+    }
+
+    Ok(Ok(replays))
+}
+
+/// Parses every replay on the blocking pool, they can be several megabytes each.
+pub async fn parse_replays(replays: Vec<(String, Vec<u8>)>) -> anyhow::Result<anyhow::Result<Vec<BatchReplay>>> {
+    let tasks = replays.into_iter().map(|(filename, bytes)| {
+        tokio::task::spawn_blocking(move || {
+            match ReplayAnalysis::parse(&bytes) {
+                Ok(analysis) => Ok(BatchReplay { filename, bytes, analysis }),
+                Err(err) => Err(anyhow!("{err} ({filename})")),
+            }
+        })
+    });
+
+    let mut parsed = vec![];
+    for result in futures::future::join_all(tasks).await {
+        match result? {
+            Ok(replay) => parsed.push(replay),
+            Err(err) => return Ok(Err(err)),
+        }
+    }
+
+    Ok(Ok(parsed))
+}
+
+fn mean(stats: &[PlayerStats]) -> PlayerStats {
+    let count = stats.len().max(1) as f64;
+    let (apm, pps, vs) = stats.iter().fold((0.0, 0.0, 0.0), |(apm, pps, vs), stats| {
+        (apm + stats.apm, pps + stats.pps, vs + stats.vs)
+    });
+
+    PlayerStats {
+        apm: apm / count,
+        pps: pps / count,
+        vs: vs / count,
+        rd: None,
+        tr: None,
+        glicko: None,
+        rank: None,
+    }
+}
+
+/// Least squares slope of the values, in change per game.
+pub fn trend(values: &[f64]) -> f64 {
+    let count = values.len() as f64;
+    if values.len() < 2 {
+        return 0.0;
+    }
+
+    let mean_x = (count - 1.0) / 2.0;
+    let mean_y = values.iter().sum::<f64>() / count;
+    let (covariance, variance) = values
+        .iter()
+        .enumerate()
+        .fold((0.0, 0.0), |(covariance, variance), (x, y)| {
+            let dx = x as f64 - mean_x;
+            (covariance + dx * (y - mean_y), variance + dx * dx)
+        });
+
+    covariance / variance
+}
+
+impl SessionStats {
+    /// Stats of `username` across the replays, in the order they were uploaded.
+    pub fn new(username: &str, replays: &[BatchReplay]) -> anyhow::Result<Self> {
+        let mut rounds = vec![];
+        let mut games = vec![];
+        let mut found_name = None;
+
+        for replay in replays {
+            let Ok(player) = replay.analysis.player(username) else {
+                continue;
+            };
+            found_name.get_or_insert_with(|| player.username.clone());
+
+            let winners = player
+                .rounds
+                .iter()
+                .filter_map(|round| replay.analysis.winner(round.round))
+                .collect::<Vec<_>>();
+            let rounds_won = winners
+                .iter()
+                .filter(|winner| winner.eq_ignore_ascii_case(username))
+                .count();
+
+            rounds.extend(player.rounds.iter().map(|round| round.player_stats()));
+            games.push(SessionGame {
+                filename: replay.filename.clone(),
+                stats: player.average(),
+                rounds: player.rounds.len(),
+                rounds_won,
+                won: (!winners.is_empty()).then(|| rounds_won * 2 > winners.len()),
+            });
+        }
+
+        let username = found_name.ok_or_else(|| anyhow!("❌ Couldn't find {username} in any of the replays"))?;
+        let per_game = mean(&games.iter().map(|game| game.stats.clone()).collect::<Vec<_>>());
+
+        Ok(Self {
+            username,
+            overall: mean(&rounds),
+            per_game,
+            games,
+        })
+    }
+
+    pub fn rounds(&self) -> usize {
+        self.games.iter().map(|game| game.rounds).sum()
+    }
+
+    /// Share of the games won, among the games with a known winner.
+    pub fn win_rate(&self) -> Option<f64> {
+        let decided = self.games.iter().filter_map(|game| game.won).collect::<Vec<_>>();
+        (!decided.is_empty()).then(|| decided.iter().filter(|won| **won).count() as f64 / decided.len() as f64)
+    }
+
+    /// Trend of a stat across the games, in change per game.
+    pub fn trend(&self, stat: impl Fn(&PlayerStats) -> f64) -> f64 {
+        trend(&self.games.iter().map(|game| stat(&game.stats)).collect::<Vec<_>>())
+    }
+}
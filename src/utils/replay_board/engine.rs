@@ -0,0 +1,871 @@
+//! Deterministic simulation of a TETR.IO board from the inputs of a replay.
+//!
+//! The simulation follows the rules used by tetra league: 7-bag randomizer seeded like the game, SRS kicks
+//! with the 180 kicks of SRS+, DAS/ARR/SDF handling in frames, gravity, lock delay, T-spin detection, the
+//! attack table with the combo multiplier and b2b chaining, and garbage cancelling.
+
+use std::collections::VecDeque;
+
+pub const WIDTH: usize = 10;
+pub const HEIGHT: usize = 40;
+/// Rows of the matrix below the buffer zone
+pub const MATRIX_HEIGHT: usize = 20;
+
+const LOCK_DELAY: f64 = 30.0;
+const MAX_LOCK_RESETS: u32 = 15;
+/// Frames between the arrival of garbage and the moment it can enter the board
+const GARBAGE_DELAY: u32 = 20;
+/// Maximum number of garbage lines entering the board at once
+const GARBAGE_CAP: u32 = 8;
+const PERFECT_CLEAR_ATTACK: f64 = 10.0;
+/// From this soft drop factor, soft dropping is instant
+const INSTANT_SOFT_DROP: f64 = 41.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PieceKind {
+    Z,
+    L,
+    O,
+    S,
+    I,
+    J,
+    T,
+}
+
+impl PieceKind {
+    const BAG: [PieceKind; 7] = [
+        PieceKind::Z,
+        PieceKind::L,
+        PieceKind::O,
+        PieceKind::S,
+        PieceKind::I,
+        PieceKind::J,
+        PieceKind::T,
+    ];
+
+    /// Size of the bounding box and cells of the spawn orientation, y going down.
+    fn shape(&self) -> (i32, [(i32, i32); 4]) {
+        match self {
+            PieceKind::Z => (3, [(0, 0), (1, 0), (1, 1), (2, 1)]),
+            PieceKind::L => (3, [(2, 0), (0, 1), (1, 1), (2, 1)]),
+            PieceKind::O => (2, [(0, 0), (1, 0), (0, 1), (1, 1)]),
+            PieceKind::S => (3, [(1, 0), (2, 0), (0, 1), (1, 1)]),
+            PieceKind::I => (4, [(0, 1), (1, 1), (2, 1), (3, 1)]),
+            PieceKind::J => (3, [(0, 0), (0, 1), (1, 1), (2, 1)]),
+            PieceKind::T => (3, [(1, 0), (0, 1), (1, 1), (2, 1)]),
+        }
+    }
+
+    fn cells(&self, rotation: u8) -> [(i32, i32); 4] {
+        let (size, mut cells) = self.shape();
+        for _ in 0..rotation % 4 {
+            cells = cells.map(|(x, y)| (size - 1 - y, x));
+        }
+        cells
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Cell {
+    Empty,
+    Piece(PieceKind),
+    Garbage,
+}
+
+pub type Board = [[Cell; WIDTH]; HEIGHT];
+
+/// Kicks of SRS, `(x, y)` with y going up, for a rotation starting from `from`.
+fn kicks(kind: PieceKind, from: u8, to: u8) -> &'static [(i32, i32)] {
+    const JLSTZ: [[(i32, i32); 5]; 8] = [
+        [(0, 0), (-1, 0), (-1, 1), (0, -2), (-1, -2)], // 0 -> R
+        [(0, 0), (1, 0), (1, -1), (0, 2), (1, 2)],     // R -> 0
+        [(0, 0), (1, 0), (1, -1), (0, 2), (1, 2)],     // R -> 2
+        [(0, 0), (-1, 0), (-1, 1), (0, -2), (-1, -2)], // 2 -> R
+        [(0, 0), (1, 0), (1, 1), (0, -2), (1, -2)],    // 2 -> L
+        [(0, 0), (-1, 0), (-1, -1), (0, 2), (-1, 2)],  // L -> 2
+        [(0, 0), (-1, 0), (-1, -1), (0, 2), (-1, 2)],  // L -> 0
+        [(0, 0), (1, 0), (1, 1), (0, -2), (1, -2)],    // 0 -> L
+    ];
+    const I: [[(i32, i32); 5]; 8] = [
+        [(0, 0), (-2, 0), (1, 0), (-2, -1), (1, 2)],
+        [(0, 0), (2, 0), (-1, 0), (2, 1), (-1, -2)],
+        [(0, 0), (-1, 0), (2, 0), (-1, 2), (2, -1)],
+        [(0, 0), (1, 0), (-2, 0), (1, -2), (-2, 1)],
+        [(0, 0), (2, 0), (-1, 0), (2, 1), (-1, -2)],
+        [(0, 0), (-2, 0), (1, 0), (-2, -1), (1, 2)],
+        [(0, 0), (1, 0), (-2, 0), (1, -2), (-2, 1)],
+        [(0, 0), (-1, 0), (2, 0), (-1, 2), (2, -1)],
+    ];
+    const HALF_TURN: [[(i32, i32); 6]; 4] = [
+        [(0, 0), (0, 1), (1, 1), (-1, 1), (1, 0), (-1, 0)],   // 0 -> 2
+        [(0, 0), (1, 0), (1, 2), (1, 1), (0, 2), (0, 1)],     // R -> L
+        [(0, 0), (0, -1), (-1, -1), (1, -1), (-1, 0), (1, 0)], // 2 -> 0
+        [(0, 0), (-1, 0), (-1, 2), (-1, 1), (0, 2), (0, 1)],  // L -> R
+    ];
+    const NONE: [(i32, i32); 1] = [(0, 0)];
+
+    if kind == PieceKind::O {
+        return &NONE;
+    }
+
+    if (from + 2) % 4 == to {
+        return &HALF_TURN[from as usize];
+    }
+
+    let index = match (from, to) {
+        (0, 1) => 0,
+        (1, 0) => 1,
+        (1, 2) => 2,
+        (2, 1) => 3,
+        (2, 3) => 4,
+        (3, 2) => 5,
+        (3, 0) => 6,
+        _ => 7,
+    };
+
+    match kind {
+        PieceKind::I => &I[index],
+        _ => &JLSTZ[index],
+    }
+}
+
+/// Park-Miller generator used by the game to shuffle the bags.
+struct Rng(i64);
+
+impl Rng {
+    fn new(seed: i64) -> Self {
+        let seed = seed % 2147483647;
+        Self(if seed <= 0 { seed + 2147483646 } else { seed })
+    }
+
+    fn next_float(&mut self) -> f64 {
+        self.0 = 16807 * self.0 % 2147483647;
+        (self.0 - 1) as f64 / 2147483646.0
+    }
+
+    fn bag(&mut self) -> [PieceKind; 7] {
+        let mut bag = PieceKind::BAG;
+        for i in (1..bag.len()).rev() {
+            let j = (self.next_float() * (i + 1) as f64).floor() as usize;
+            bag.swap(i, j);
+        }
+        bag
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Key {
+    MoveLeft,
+    MoveRight,
+    SoftDrop,
+    HardDrop,
+    RotateCW,
+    RotateCCW,
+    Rotate180,
+    Hold,
+}
+
+impl Key {
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "moveLeft" => Key::MoveLeft,
+            "moveRight" => Key::MoveRight,
+            "softDrop" => Key::SoftDrop,
+            "hardDrop" => Key::HardDrop,
+            "rotateCW" => Key::RotateCW,
+            "rotateCCW" => Key::RotateCCW,
+            "rotate180" => Key::Rotate180,
+            "hold" => Key::Hold,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Input {
+    KeyDown(Key),
+    KeyUp(Key),
+    /// Garbage sent by an opponent, entering through a single hole
+    Garbage { amount: u32, column: usize },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TimedInput {
+    pub frame: u32,
+    pub input: Input,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GameOptions {
+    pub seed: i64,
+    /// Cells per frame
+    pub gravity: f64,
+    pub gravity_increase: f64,
+    /// Frames before the gravity starts increasing
+    pub gravity_margin: u32,
+    /// Frames
+    pub das: f64,
+    /// Frames
+    pub arr: f64,
+    pub sdf: f64,
+}
+
+impl Default for GameOptions {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            gravity: 0.02,
+            gravity_increase: 0.0,
+            gravity_margin: 0,
+            das: 10.0,
+            arr: 2.0,
+            sdf: 6.0,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Active {
+    kind: PieceKind,
+    x: i32,
+    y: i32,
+    rotation: u8,
+}
+
+impl Active {
+    fn spawn(kind: PieceKind) -> Self {
+        // centered, right above the matrix
+        let x = if kind == PieceKind::O { 4 } else { 3 };
+        let y = (HEIGHT - MATRIX_HEIGHT) as i32 - 2;
+        Self { kind, x, y, rotation: 0 }
+    }
+
+    fn cells(&self) -> impl Iterator<Item = (i32, i32)> + '_ {
+        self.kind
+            .cells(self.rotation)
+            .into_iter()
+            .map(move |(x, y)| (self.x + x, self.y + y))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Spin {
+    None,
+    Mini,
+    Full,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct IncomingGarbage {
+    ready_frame: u32,
+    amount: u32,
+    column: usize,
+}
+
+/// The board right after a piece locked.
+#[derive(Clone, Debug)]
+pub struct Placement {
+    pub frame: u32,
+    pub board: Board,
+    pub lines: u32,
+    pub attack: u32,
+    /// Garbage waiting to enter the board
+    pub incoming: u32,
+}
+
+pub struct Game {
+    pub board: Board,
+    pub hold: Option<PieceKind>,
+    pub placements: Vec<Placement>,
+    pub topped_out: bool,
+    pub frame: u32,
+    options: GameOptions,
+    rng: Rng,
+    queue: VecDeque<PieceKind>,
+    active: Option<Active>,
+    hold_used: bool,
+    gravity_progress: f64,
+    lock_timer: f64,
+    lock_resets: u32,
+    left_held: bool,
+    right_held: bool,
+    soft_drop_held: bool,
+    /// -1 or 1, the direction pressed last
+    direction: i32,
+    das_timer: f64,
+    arr_timer: f64,
+    /// Index of the kick used by the last action, if it was a rotation
+    last_kick: Option<usize>,
+    combo: i32,
+    b2b: i32,
+    incoming: VecDeque<IncomingGarbage>,
+}
+
+impl Game {
+    pub fn new(options: GameOptions) -> Self {
+        let mut game = Self {
+            board: [[Cell::Empty; WIDTH]; HEIGHT],
+            hold: None,
+            placements: vec![],
+            topped_out: false,
+            frame: 0,
+            options,
+            rng: Rng::new(options.seed),
+            queue: VecDeque::new(),
+            active: None,
+            hold_used: false,
+            gravity_progress: 0.0,
+            lock_timer: 0.0,
+            lock_resets: 0,
+            left_held: false,
+            right_held: false,
+            soft_drop_held: false,
+            direction: 0,
+            das_timer: 0.0,
+            arr_timer: 0.0,
+            last_kick: None,
+            combo: -1,
+            b2b: -1,
+            incoming: VecDeque::new(),
+        };
+        game.spawn_next();
+        game
+    }
+
+    /// Plays the inputs, sorted by frame, until `frames` or until the player tops out.
+    pub fn run(options: GameOptions, inputs: &[TimedInput], frames: u32) -> Self {
+        let mut game = Self::new(options);
+        let mut inputs = inputs.iter().peekable();
+
+        for frame in 0..=frames {
+            game.frame = frame;
+            while let Some(input) = inputs.next_if(|input| input.frame <= frame) {
+                game.apply(input.input);
+            }
+            if game.topped_out {
+                break;
+            }
+            game.tick();
+            if game.topped_out {
+                break;
+            }
+        }
+
+        game
+    }
+
+    /// Board with the falling piece drawn in it.
+    pub fn board_with_active(&self) -> Board {
+        let mut board = self.board;
+        if let Some(active) = &self.active {
+            for (x, y) in active.cells() {
+                if (0..WIDTH as i32).contains(&x) && (0..HEIGHT as i32).contains(&y) {
+                    board[y as usize][x as usize] = Cell::Piece(active.kind);
+                }
+            }
+        }
+        board
+    }
+
+    pub fn incoming(&self) -> u32 {
+        self.incoming.iter().map(|garbage| garbage.amount).sum()
+    }
+
+    fn is_free(&self, x: i32, y: i32) -> bool {
+        (0..WIDTH as i32).contains(&x)
+            && y < HEIGHT as i32
+            && (y < 0 || self.board[y as usize][x as usize] == Cell::Empty)
+    }
+
+    fn fits(&self, piece: &Active) -> bool {
+        piece.cells().all(|(x, y)| self.is_free(x, y))
+    }
+
+    fn spawn_next(&mut self) {
+        if self.queue.len() < 7 {
+            let bag = self.rng.bag();
+            self.queue.extend(bag);
+        }
+        if let Some(kind) = self.queue.pop_front() {
+            self.spawn(kind);
+        }
+    }
+
+    fn spawn(&mut self, kind: PieceKind) {
+        let piece = Active::spawn(kind);
+        self.topped_out = !self.fits(&piece);
+        self.active = Some(piece);
+        self.gravity_progress = 0.0;
+        self.lock_timer = 0.0;
+        self.lock_resets = 0;
+        self.last_kick = None;
+    }
+
+    fn apply(&mut self, input: Input) {
+        match input {
+            Input::KeyDown(Key::MoveLeft) => {
+                self.left_held = true;
+                self.start_shift(-1);
+            }
+            Input::KeyDown(Key::MoveRight) => {
+                self.right_held = true;
+                self.start_shift(1);
+            }
+            Input::KeyUp(Key::MoveLeft) => {
+                self.left_held = false;
+                if self.right_held {
+                    self.start_shift(1);
+                }
+            }
+            Input::KeyUp(Key::MoveRight) => {
+                self.right_held = false;
+                if self.left_held {
+                    self.start_shift(-1);
+                }
+            }
+            Input::KeyDown(Key::SoftDrop) => {
+                self.soft_drop_held = true;
+                if self.options.sdf >= INSTANT_SOFT_DROP {
+                    while self.shift(0, 1) {}
+                }
+            }
+            Input::KeyUp(Key::SoftDrop) => self.soft_drop_held = false,
+            Input::KeyDown(Key::HardDrop) => {
+                while self.shift(0, 1) {}
+                self.lock();
+            }
+            Input::KeyDown(Key::RotateCW) => self.rotate(1),
+            Input::KeyDown(Key::RotateCCW) => self.rotate(3),
+            Input::KeyDown(Key::Rotate180) => self.rotate(2),
+            Input::KeyDown(Key::Hold) => self.swap_hold(),
+            Input::KeyUp(_) => {}
+            Input::Garbage { amount, column } => self.incoming.push_back(IncomingGarbage {
+                ready_frame: self.frame + GARBAGE_DELAY,
+                amount,
+                column: column.min(WIDTH - 1),
+            }),
+        }
+    }
+
+    fn start_shift(&mut self, direction: i32) {
+        self.direction = direction;
+        self.das_timer = 0.0;
+        self.arr_timer = 0.0;
+        self.shift(direction, 0);
+    }
+
+    fn held_direction(&self) -> i32 {
+        match (self.left_held, self.right_held) {
+            (true, true) => self.direction,
+            (true, false) => -1,
+            (false, true) => 1,
+            (false, false) => 0,
+        }
+    }
+
+    /// Moves the piece, resetting the lock delay when it moved sideways while on the ground.
+    fn shift(&mut self, dx: i32, dy: i32) -> bool {
+        let Some(mut piece) = self.active else {
+            return false;
+        };
+        piece.x += dx;
+        piece.y += dy;
+        if !self.fits(&piece) {
+            return false;
+        }
+
+        self.active = Some(piece);
+        self.last_kick = None;
+        if dy > 0 {
+            self.lock_timer = 0.0;
+        } else {
+            self.reset_lock_delay();
+        }
+        true
+    }
+
+    fn reset_lock_delay(&mut self) {
+        if self.lock_resets < MAX_LOCK_RESETS {
+            self.lock_timer = 0.0;
+            self.lock_resets += 1;
+        }
+    }
+
+    fn rotate(&mut self, turns: u8) {
+        let Some(piece) = self.active else {
+            return;
+        };
+        let rotation = (piece.rotation + turns) % 4;
+
+        for (i, (dx, dy)) in kicks(piece.kind, piece.rotation, rotation).iter().enumerate() {
+            let kicked = Active {
+                x: piece.x + dx,
+                y: piece.y - dy,
+                rotation,
+                ..piece
+            };
+            if self.fits(&kicked) {
+                self.active = Some(kicked);
+                self.last_kick = Some(i);
+                self.reset_lock_delay();
+                return;
+            }
+        }
+    }
+
+    fn swap_hold(&mut self) {
+        if self.hold_used {
+            return;
+        }
+        let Some(piece) = self.active else {
+            return;
+        };
+
+        match self.hold.replace(piece.kind) {
+            Some(kind) => self.spawn(kind),
+            None => self.spawn_next(),
+        }
+        self.hold_used = true;
+    }
+
+    fn tick(&mut self) {
+        let direction = self.held_direction();
+        if direction != 0 {
+            self.das_timer += 1.0;
+            if self.das_timer >= self.options.das {
+                if self.options.arr <= 0.0 {
+                    while self.shift(direction, 0) {}
+                } else {
+                    self.arr_timer += 1.0;
+                    while self.arr_timer >= self.options.arr {
+                        self.arr_timer -= self.options.arr;
+                        if !self.shift(direction, 0) {
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+
+        let elapsed = self.frame.saturating_sub(self.options.gravity_margin) as f64;
+        let gravity = self.options.gravity + self.options.gravity_increase * elapsed;
+        self.gravity_progress += if self.soft_drop_held {
+            gravity * self.options.sdf.max(1.0)
+        } else {
+            gravity
+        };
+        while self.gravity_progress >= 1.0 {
+            self.gravity_progress -= 1.0;
+            if !self.shift(0, 1) {
+                self.gravity_progress = 0.0;
+                break;
+            }
+        }
+
+        let Some(mut below) = self.active else {
+            return;
+        };
+        below.y += 1;
+        if self.fits(&below) {
+            return;
+        }
+
+        self.lock_timer += 1.0;
+        if self.lock_timer >= LOCK_DELAY {
+            self.lock();
+        }
+    }
+
+    fn spin(&self, piece: &Active) -> Spin {
+        if piece.kind != PieceKind::T || self.last_kick.is_none() {
+            return Spin::None;
+        }
+
+        let filled = |(x, y): (i32, i32)| !self.is_free(piece.x + x, piece.y + y);
+        // corners in front of the flat side of the T, for every rotation
+        let (front, back) = match piece.rotation {
+            0 => ([(0, 0), (2, 0)], [(0, 2), (2, 2)]),
+            1 => ([(2, 0), (2, 2)], [(0, 0), (0, 2)]),
+            2 => ([(0, 2), (2, 2)], [(0, 0), (2, 0)]),
+            _ => ([(0, 0), (0, 2)], [(2, 0), (2, 2)]),
+        };
+        let front = front.into_iter().filter(|corner| filled(*corner)).count();
+        let back = back.into_iter().filter(|corner| filled(*corner)).count();
+
+        if front + back < 3 {
+            Spin::None
+        } else if front == 2 || self.last_kick == Some(4) {
+            Spin::Full
+        } else {
+            Spin::Mini
+        }
+    }
+
+    fn attack(&mut self, lines: u32, spin: Spin, perfect_clear: bool) -> u32 {
+        if lines == 0 {
+            self.combo = -1;
+            return 0;
+        }
+
+        self.combo += 1;
+        let difficult = lines == 4 || spin != Spin::None;
+        self.b2b = if difficult { self.b2b + 1 } else { -1 };
+
+        let mut attack = match (spin, lines) {
+            (Spin::None, 1) => 0.0,
+            (Spin::None, 2) => 1.0,
+            (Spin::None, 3) => 2.0,
+            (Spin::None, _) => 4.0,
+            (Spin::Mini, 1) => 0.0,
+            (Spin::Mini, _) => 1.0,
+            (Spin::Full, 1) => 2.0,
+            (Spin::Full, 2) => 4.0,
+            (Spin::Full, _) => 6.0,
+        };
+
+        if self.b2b > 0 {
+            attack += (1.0 + (self.b2b as f64 * 0.8).ln_1p()).floor();
+        }
+
+        if self.combo > 0 {
+            attack = if attack > 0.0 {
+                attack * (1.0 + 0.25 * self.combo as f64)
+            } else {
+                (1.0 + 1.25 * self.combo as f64).ln()
+            };
+        }
+
+        if perfect_clear {
+            attack += PERFECT_CLEAR_ATTACK;
+        }
+
+        attack.floor() as u32
+    }
+
+    fn lock(&mut self) {
+        let Some(piece) = self.active.take() else {
+            return;
+        };
+        let spin = self.spin(&piece);
+
+        for (x, y) in piece.cells() {
+            if y < 0 {
+                self.topped_out = true;
+                continue;
+            }
+            self.board[y as usize][x as usize] = Cell::Piece(piece.kind);
+        }
+
+        let remaining = self
+            .board
+            .iter()
+            .filter(|row| row.iter().any(|cell| *cell == Cell::Empty))
+            .copied()
+            .collect::<Vec<_>>();
+        let lines = (HEIGHT - remaining.len()) as u32;
+        let mut board = [[Cell::Empty; WIDTH]; HEIGHT];
+        board[HEIGHT - remaining.len()..].copy_from_slice(&remaining);
+        self.board = board;
+
+        let perfect_clear = lines > 0 && self.board.iter().flatten().all(|cell| *cell == Cell::Empty);
+        let attack = self.attack(lines, spin, perfect_clear);
+
+        let mut cancelled = attack;
+        while cancelled > 0 {
+            let Some(garbage) = self.incoming.front_mut() else {
+                break;
+            };
+            let amount = garbage.amount.min(cancelled);
+            garbage.amount -= amount;
+            cancelled -= amount;
+            if garbage.amount == 0 {
+                self.incoming.pop_front();
+            }
+        }
+
+        if lines == 0 {
+            self.receive_garbage();
+        }
+
+        self.placements.push(Placement {
+            frame: self.frame,
+            board: self.board,
+            lines,
+            attack,
+            incoming: self.incoming(),
+        });
+
+        self.hold_used = false;
+        if !self.topped_out {
+            self.spawn_next();
+        }
+    }
+
+    fn receive_garbage(&mut self) {
+        let mut received = 0;
+        while received < GARBAGE_CAP {
+            let Some(garbage) = self.incoming.front_mut() else {
+                break;
+            };
+            if garbage.ready_frame > self.frame {
+                break;
+            }
+
+            let amount = garbage.amount.min(GARBAGE_CAP - received);
+            let column = garbage.column;
+            garbage.amount -= amount;
+            if garbage.amount == 0 {
+                self.incoming.pop_front();
+            }
+            received += amount;
+
+            for _ in 0..amount {
+                if self.board[0].iter().any(|cell| *cell != Cell::Empty) {
+                    self.topped_out = true;
+                }
+                self.board.rotate_left(1);
+                let mut row = [Cell::Garbage; WIDTH];
+                row[column] = Cell::Empty;
+                self.board[HEIGHT - 1] = row;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BOTTOM: usize = HEIGHT - 1;
+
+    /// A T pointing down whose bounding box starts in the third row from the bottom, left edge in column 0.
+    fn t_down() -> Active {
+        Active {
+            kind: PieceKind::T,
+            x: 0,
+            y: HEIGHT as i32 - 3,
+            rotation: 2,
+        }
+    }
+
+    fn game_with_filled(cells: &[(usize, usize)]) -> Game {
+        let mut game = Game::new(GameOptions::default());
+        for (x, y) in cells {
+            game.board[*y][*x] = Cell::Garbage;
+        }
+        game
+    }
+
+    #[test]
+    fn pieces_come_back_to_spawn_after_four_rotations() {
+        for kind in PieceKind::BAG {
+            assert_eq!(kind.cells(4), kind.cells(0));
+        }
+        // the T points right after a clockwise rotation
+        assert_eq!(PieceKind::T.cells(1), [(2, 1), (1, 0), (1, 1), (1, 2)]);
+    }
+
+    #[test]
+    fn kick_tables() {
+        assert_eq!(kicks(PieceKind::T, 0, 1), &[(0, 0), (-1, 0), (-1, 1), (0, -2), (-1, -2)]);
+        assert_eq!(kicks(PieceKind::J, 3, 0), &[(0, 0), (-1, 0), (-1, -1), (0, 2), (-1, 2)]);
+        assert_eq!(kicks(PieceKind::I, 0, 1), &[(0, 0), (-2, 0), (1, 0), (-2, -1), (1, 2)]);
+        assert_eq!(kicks(PieceKind::I, 1, 2), &[(0, 0), (-1, 0), (2, 0), (-1, 2), (2, -1)]);
+        assert_eq!(kicks(PieceKind::O, 0, 1), &[(0, 0)]);
+        assert_eq!(kicks(PieceKind::S, 0, 2), &[(0, 0), (0, 1), (1, 1), (-1, 1), (1, 0), (-1, 0)]);
+        assert_eq!(kicks(PieceKind::T, 3, 1), &[(0, 0), (-1, 0), (-1, 2), (-1, 1), (0, 2), (0, 1)]);
+    }
+
+    #[test]
+    fn bags_hold_every_piece_once() {
+        let mut rng = Rng::new(1234);
+        for _ in 0..10 {
+            let bag = rng.bag();
+            for kind in PieceKind::BAG {
+                assert_eq!(bag.iter().filter(|piece| **piece == kind).count(), 1);
+            }
+        }
+    }
+
+    #[test]
+    fn rotated_t_with_three_corners_is_a_spin() {
+        // both corners in front of the flat side and one behind it
+        let mut game = game_with_filled(&[(0, BOTTOM), (2, BOTTOM), (0, BOTTOM - 2)]);
+        game.last_kick = Some(0);
+        assert_eq!(game.spin(&t_down()), Spin::Full);
+
+        // moving after the rotation cancels the spin
+        game.last_kick = None;
+        assert_eq!(game.spin(&t_down()), Spin::None);
+    }
+
+    #[test]
+    fn t_spin_with_a_single_front_corner_is_a_mini_unless_it_used_the_last_kick() {
+        let mut game = game_with_filled(&[(0, BOTTOM), (0, BOTTOM - 2), (2, BOTTOM - 2)]);
+        game.last_kick = Some(1);
+        assert_eq!(game.spin(&t_down()), Spin::Mini);
+
+        game.last_kick = Some(4);
+        assert_eq!(game.spin(&t_down()), Spin::Full);
+    }
+
+    #[test]
+    fn t_with_two_corners_is_not_a_spin() {
+        let mut game = game_with_filled(&[(0, BOTTOM), (2, BOTTOM)]);
+        game.last_kick = Some(0);
+        assert_eq!(game.spin(&t_down()), Spin::None);
+    }
+
+    #[test]
+    fn attack_table() {
+        let attack = |lines, spin| Game::new(GameOptions::default()).attack(lines, spin, false);
+
+        assert_eq!(attack(1, Spin::None), 0);
+        assert_eq!(attack(2, Spin::None), 1);
+        assert_eq!(attack(3, Spin::None), 2);
+        assert_eq!(attack(4, Spin::None), 4);
+        assert_eq!(attack(1, Spin::Mini), 0);
+        assert_eq!(attack(2, Spin::Mini), 1);
+        assert_eq!(attack(1, Spin::Full), 2);
+        assert_eq!(attack(2, Spin::Full), 4);
+        assert_eq!(attack(3, Spin::Full), 6);
+        assert_eq!(attack(0, Spin::Full), 0);
+    }
+
+    #[test]
+    fn back_to_back_and_combo_add_attack() {
+        let mut game = Game::new(GameOptions::default());
+        assert_eq!(game.attack(4, Spin::None, false), 4);
+        // b2b 1 adds a line, then the first combo multiplies by 1.25
+        assert_eq!(game.attack(4, Spin::None, false), 6);
+        // a single breaks the b2b chain, combo 2 of a 0 attack clear sends ln(1 + 2.5)
+        assert_eq!(game.attack(1, Spin::None, false), 1);
+        // no clear resets the combo
+        assert_eq!(game.attack(0, Spin::None, false), 0);
+        assert_eq!(game.attack(2, Spin::None, false), 1);
+    }
+
+    #[test]
+    fn perfect_clear_adds_attack() {
+        let mut game = Game::new(GameOptions::default());
+        assert_eq!(game.attack(1, Spin::None, true), 10);
+    }
+
+    #[test]
+    fn locking_a_t_spin_double_clears_the_lines_and_sends_attack() {
+        let mut cells = (3..WIDTH).map(|x| (x, BOTTOM - 1)).collect::<Vec<_>>();
+        cells.extend((0..WIDTH).filter(|x| *x != 1).map(|x| (x, BOTTOM)));
+        cells.push((0, BOTTOM - 2));
+        let mut game = game_with_filled(&cells);
+
+        game.active = Some(t_down());
+        game.last_kick = Some(0);
+        game.lock();
+
+        let placement = game.placements.last().unwrap();
+        assert_eq!((placement.lines, placement.attack), (2, 4));
+        // only the cell above the T remains, fallen to the bottom row
+        assert_eq!(game.board[BOTTOM][0], Cell::Garbage);
+        assert_eq!(game.board.iter().flatten().filter(|cell| **cell != Cell::Empty).count(), 1);
+    }
+}
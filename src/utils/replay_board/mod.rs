@@ -0,0 +1,289 @@
+#![cfg(feature = "tetrio")]
+//! Rebuilds the board of a player from the input events of a ttrm replay.
+//!
+//! The typed replay models only describe the end frames, so the events are read from the raw json.
+
+use anyhow::anyhow;
+use serde_json::Value;
+
+use self::engine::{Board, Cell, Game, GameOptions, Input, Key, Placement, TimedInput, HEIGHT, MATRIX_HEIGHT};
+
+pub mod engine;
+#[cfg(feature = "local_image_generation")]
+pub mod render;
+
+/// Frames in a second of game
+pub const FPS: f64 = 60.0;
+/// Attacks sent within this many frames of each other belong to the same spike
+const SPIKE_WINDOW: u32 = 60;
+/// Frames played after the last input, for the pieces still falling and the garbage still queued
+const FRAMES_AFTER_LAST_INPUT: u32 = 10 * FPS as u32;
+/// Longest round that is played, an hour of frames
+const MAX_FRAMES: u32 = 60 * 60 * FPS as u32;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Moment {
+    TopOut,
+    BiggestSpike,
+    Final,
+}
+
+/// The inputs of a player during a round.
+pub struct RoundInputs {
+    pub username: String,
+    pub options: GameOptions,
+    pub inputs: Vec<TimedInput>,
+    pub frames: u32,
+    /// Board of the end frame of the replay, to check the reconstruction
+    pub expected_board: Option<Vec<Vec<bool>>>,
+}
+
+/// A spike of attack, from the first attacking piece to the last one.
+#[derive(Clone, Copy, Debug)]
+pub struct Spike {
+    pub attack: u32,
+    pub start_frame: u32,
+    /// Index of the placement ending the spike
+    pub placement: usize,
+}
+
+pub struct Reconstruction {
+    pub username: String,
+    pub game: Game,
+    /// Whether the final board is the same as the one of the replay, if it contains one
+    pub matches_replay: Option<bool>,
+}
+
+fn number(value: &Value) -> Option<f64> {
+    value.as_f64().or_else(|| value.as_str()?.parse().ok())
+}
+
+/// Finds the first value named `key` in nested objects, depth first.
+fn find<'a>(value: &'a Value, key: &str) -> Option<&'a Value> {
+    match value {
+        Value::Object(map) => map
+            .get(key)
+            .or_else(|| map.values().find_map(|value| find(value, key))),
+        _ => None,
+    }
+}
+
+fn event_username(event: &Value) -> Option<&str> {
+    let data = event.get("data")?;
+    data.pointer("/export/options/username")
+        .or_else(|| data.pointer("/options/username"))
+        .and_then(Value::as_str)
+}
+
+fn parse_options(events: &[Value]) -> GameOptions {
+    let defaults = GameOptions::default();
+    let options = events.iter().find_map(|event| {
+        let data = event.get("data")?;
+        data.get("options")
+            .or_else(|| data.pointer("/export/options"))
+            .filter(|options| options.is_object())
+    });
+    let Some(options) = options else {
+        return defaults;
+    };
+    let handling = |key: &str| {
+        options
+            .get("handling")
+            .and_then(|handling| handling.get(key))
+            .and_then(number)
+    };
+
+    GameOptions {
+        seed: options.get("seed").and_then(number).map(|seed| seed as i64).unwrap_or(defaults.seed),
+        gravity: options.get("g").and_then(number).unwrap_or(defaults.gravity),
+        gravity_increase: options.get("gincrease").and_then(number).unwrap_or(defaults.gravity_increase),
+        gravity_margin: options.get("gmargin").and_then(number).map(|margin| margin as u32).unwrap_or(defaults.gravity_margin),
+        das: handling("das").unwrap_or(defaults.das),
+        arr: handling("arr").unwrap_or(defaults.arr),
+        sdf: handling("sdf").unwrap_or(defaults.sdf),
+    }
+}
+
+/// Garbage from an `ige` event, old replays send every interaction twice and only the confirmation counts.
+fn parse_garbage(event: &Value) -> Option<Input> {
+    let data = event.get("data")?;
+    if data.pointer("/data/type").and_then(Value::as_str) == Some("interaction") {
+        return None;
+    }
+
+    let amount = find(data, "amt").or_else(|| find(data, "lines")).and_then(number)?;
+    let column = find(data, "column").and_then(number).unwrap_or(0.0);
+    (amount > 0.0).then_some(Input::Garbage {
+        amount: amount as u32,
+        column: column as usize,
+    })
+}
+
+fn parse_board(board: &Value) -> Option<Vec<Vec<bool>>> {
+    board
+        .as_array()?
+        .iter()
+        .map(|row| Some(row.as_array()?.iter().map(|cell| !cell.is_null()).collect()))
+        .collect()
+}
+
+impl RoundInputs {
+    /// Reads the inputs of `username` in a round starting at 1, from the json of the replay.
+    pub fn parse(replay: &Value, username: &str, round: usize) -> anyhow::Result<Self> {
+        let rounds = replay
+            .get("data")
+            .and_then(Value::as_array)
+            .ok_or_else(|| anyhow!("❌ The replay doesn't contain any round"))?;
+        let round_data = rounds
+            .get(round.max(1) - 1)
+            .ok_or_else(|| anyhow!("❌ The replay only has {} rounds", rounds.len()))?;
+
+        let (replay, username) = round_data
+            .get("replays")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .find_map(|replay| {
+                let events = replay.get("events")?.as_array()?;
+                let name = events.iter().find_map(event_username)?;
+                name.eq_ignore_ascii_case(username).then(|| (replay, name.to_string()))
+            })
+            .ok_or_else(|| anyhow!("❌ Couldn't find {username} in round {round}"))?;
+
+        let events = replay.get("events").and_then(Value::as_array).cloned().unwrap_or_default();
+
+        let mut inputs = events
+            .iter()
+            .filter_map(|event| {
+                let frame = event.get("frame").and_then(number)? as u32;
+                let input = match event.get("type")?.as_str()? {
+                    kind @ ("keydown" | "keyup") => {
+                        let key = Key::from_name(event.pointer("/data/key")?.as_str()?)?;
+                        if kind == "keydown" {
+                            Input::KeyDown(key)
+                        } else {
+                            Input::KeyUp(key)
+                        }
+                    }
+                    "ige" => parse_garbage(event)?,
+                    _ => return None,
+                };
+                Some(TimedInput { frame, input })
+            })
+            .collect::<Vec<_>>();
+        inputs.sort_by_key(|input| input.frame);
+
+        // the frame count comes from the upload, a round without gravity would otherwise be played for billions of frames
+        let last_input = inputs.last().map(|input| input.frame).unwrap_or(0);
+        let frames = replay
+            .get("frames")
+            .and_then(number)
+            .map(|frames| frames as u32)
+            .unwrap_or(last_input)
+            .min(last_input.saturating_add(FRAMES_AFTER_LAST_INPUT))
+            .min(MAX_FRAMES);
+
+        let expected_board = events
+            .iter()
+            .rev()
+            .find(|event| event.get("type").and_then(Value::as_str) == Some("end"))
+            .and_then(|event| event.pointer("/data/export/game/board"))
+            .and_then(parse_board);
+
+        Ok(Self {
+            username,
+            options: parse_options(&events),
+            inputs,
+            frames,
+            expected_board,
+        })
+    }
+}
+
+/// Compares the occupied cells of the matrix, the buffer zone is ignored.
+fn same_board(board: &Board, expected: &[Vec<bool>]) -> bool {
+    let rows = expected.len().min(MATRIX_HEIGHT);
+    board[HEIGHT - rows..]
+        .iter()
+        .zip(&expected[expected.len() - rows..])
+        .all(|(row, expected)| {
+            row.iter()
+                .zip(expected)
+                .all(|(cell, filled)| (*cell != Cell::Empty) == *filled)
+        })
+}
+
+impl Reconstruction {
+    pub fn new(inputs: RoundInputs) -> Self {
+        let game = Game::run(inputs.options, &inputs.inputs, inputs.frames);
+        let matches_replay = inputs
+            .expected_board
+            .as_deref()
+            .map(|expected| same_board(&game.board, expected));
+
+        Self {
+            username: inputs.username,
+            game,
+            matches_replay,
+        }
+    }
+
+    pub fn parse(replay: &Value, username: &str, round: usize) -> anyhow::Result<Self> {
+        RoundInputs::parse(replay, username, round).map(Self::new)
+    }
+
+    pub fn biggest_spike(&self) -> Option<Spike> {
+        let mut best: Option<Spike> = None;
+        let mut current: Option<Spike> = None;
+        let mut last_attack_frame = 0;
+
+        for (i, placement) in self.game.placements.iter().enumerate() {
+            if placement.attack == 0 {
+                continue;
+            }
+
+            current = match current {
+                Some(spike) if placement.frame - last_attack_frame <= SPIKE_WINDOW => Some(Spike {
+                    attack: spike.attack + placement.attack,
+                    placement: i,
+                    ..spike
+                }),
+                _ => Some(Spike {
+                    attack: placement.attack,
+                    start_frame: placement.frame,
+                    placement: i,
+                }),
+            };
+            last_attack_frame = placement.frame;
+
+            if current.map(|spike| spike.attack) > best.map(|spike| spike.attack) {
+                best = current;
+            }
+        }
+
+        best
+    }
+
+    /// Board at a moment of the round, with the frame it happened at and the incoming garbage.
+    pub fn moment(&self, moment: Moment) -> anyhow::Result<(Board, u32, u32)> {
+        match moment {
+            Moment::TopOut if !self.game.topped_out => {
+                Err(anyhow!("❌ {} didn't top out in this round", self.username))
+            }
+            Moment::TopOut | Moment::Final => Ok((self.game.board_with_active(), self.game.frame, self.game.incoming())),
+            Moment::BiggestSpike => {
+                let spike = self
+                    .biggest_spike()
+                    .ok_or_else(|| anyhow!("❌ {} didn't send any attack in this round", self.username))?;
+                let Placement { board, frame, incoming, .. } = &self.game.placements[spike.placement];
+                Ok((*board, *frame, *incoming))
+            }
+        }
+    }
+}
+
+/// Formats a frame as `m:ss.s`.
+pub fn format_time(frame: u32) -> String {
+    let seconds = frame as f64 / FPS;
+    format!("{}:{:04.1}", (seconds / 60.0).floor(), seconds % 60.0)
+}
@@ -0,0 +1,105 @@
+use std::io::Cursor;
+
+use image::{
+    codecs::gif::{GifEncoder, Repeat},
+    Delay, Frame, ImageFormat, Rgba, RgbaImage,
+};
+use imageproc::{drawing::draw_filled_rect_mut, rect::Rect};
+
+use super::{
+    engine::{Board, Cell, PieceKind, Placement, HEIGHT, MATRIX_HEIGHT, WIDTH},
+    FPS,
+};
+
+pub const BOARD_FILENAME: &str = "board.png";
+pub const ANIMATION_FILENAME: &str = "board.gif";
+
+const CELL_SIZE: u32 = 16;
+/// Rows of the buffer zone drawn above the matrix
+const BUFFER_ROWS: usize = 3;
+const METER_WIDTH: u32 = 6;
+const MARGIN: u32 = 4;
+/// The animation keeps the last placements of the round
+const MAX_ANIMATION_FRAMES: usize = 150;
+const MIN_FRAME_DELAY_MS: u32 = 40;
+const MAX_FRAME_DELAY_MS: u32 = 1000;
+
+const BACKGROUND: Rgba<u8> = Rgba([12, 12, 16, 255]);
+const GRID: Rgba<u8> = Rgba([28, 28, 36, 255]);
+const GARBAGE_METER: Rgba<u8> = Rgba([230, 40, 40, 255]);
+
+fn cell_color(cell: Cell) -> Option<Rgba<u8>> {
+    Some(match cell {
+        Cell::Empty => return None,
+        Cell::Garbage => Rgba([110, 110, 110, 255]),
+        Cell::Piece(PieceKind::Z) => Rgba([214, 54, 64, 255]),
+        Cell::Piece(PieceKind::L) => Rgba([232, 128, 40, 255]),
+        Cell::Piece(PieceKind::O) => Rgba([230, 196, 48, 255]),
+        Cell::Piece(PieceKind::S) => Rgba([120, 190, 48, 255]),
+        Cell::Piece(PieceKind::I) => Rgba([48, 190, 160, 255]),
+        Cell::Piece(PieceKind::J) => Rgba([72, 80, 200, 255]),
+        Cell::Piece(PieceKind::T) => Rgba([170, 64, 180, 255]),
+    })
+}
+
+/// Draws the matrix and the top of the buffer zone, with the incoming garbage on the left.
+pub fn render_board(board: &Board, incoming: u32) -> RgbaImage {
+    let rows = MATRIX_HEIGHT + BUFFER_ROWS;
+    let board_x = MARGIN + METER_WIDTH + MARGIN;
+    let width = board_x + WIDTH as u32 * CELL_SIZE + MARGIN;
+    let height = rows as u32 * CELL_SIZE + 2 * MARGIN;
+    let mut image = RgbaImage::from_pixel(width, height, BACKGROUND);
+
+    for (row, cells) in board[HEIGHT - rows..].iter().enumerate() {
+        for (column, cell) in cells.iter().enumerate() {
+            let rect = Rect::at(
+                (board_x + column as u32 * CELL_SIZE) as i32,
+                (MARGIN + row as u32 * CELL_SIZE) as i32,
+            )
+            .of_size(CELL_SIZE - 1, CELL_SIZE - 1);
+            let color = cell_color(*cell).unwrap_or(if row < BUFFER_ROWS { BACKGROUND } else { GRID });
+            draw_filled_rect_mut(&mut image, rect, color);
+        }
+    }
+
+    let meter = (incoming as usize).min(MATRIX_HEIGHT) as u32 * CELL_SIZE;
+    if meter > 0 {
+        draw_filled_rect_mut(
+            &mut image,
+            Rect::at(MARGIN as i32, (height - MARGIN - meter) as i32).of_size(METER_WIDTH, meter),
+            GARBAGE_METER,
+        );
+    }
+
+    image
+}
+
+pub fn render_png(board: &Board, incoming: u32) -> anyhow::Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    render_board(board, incoming).write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)?;
+    Ok(bytes)
+}
+
+/// Animates the placements in real time, one frame per piece.
+pub fn render_gif(placements: &[Placement]) -> anyhow::Result<Vec<u8>> {
+    let placements = &placements[placements.len().saturating_sub(MAX_ANIMATION_FRAMES)..];
+
+    let frames = placements.iter().enumerate().map(|(i, placement)| {
+        let next_frame = placements.get(i + 1).map(|next| next.frame).unwrap_or(placement.frame + FPS as u32);
+        let delay = ((next_frame - placement.frame) as f64 * 1000.0 / FPS) as u32;
+        Frame::from_parts(
+            render_board(&placement.board, placement.incoming),
+            0,
+            0,
+            Delay::from_numer_denom_ms(delay.clamp(MIN_FRAME_DELAY_MS, MAX_FRAME_DELAY_MS), 1),
+        )
+    });
+
+    let mut bytes = Vec::new();
+    {
+        let mut encoder = GifEncoder::new_with_speed(&mut bytes, 10);
+        encoder.set_repeat(Repeat::Infinite)?;
+        encoder.encode_frames(frames)?;
+    }
+    Ok(bytes)
+}
@@ -0,0 +1,243 @@
+#![cfg(feature = "tetrio")]
+//! Downloads replays from their tetr.io replay id, so the replay commands don't need an uploaded file.
+//!
+//! tetr.io has no public endpoint for replays, [`HttpReplayFetcher`] calls the game client api with `TETRIO_TOKEN`,
+//! the bearer token of a personal tetr.io account. The token gives full access to that account, so use a dedicated
+//! account, keep it out of logs and only use it to download the replays players ask for, within the tetr.io terms of
+//! service. Without a token, run the bot with `REPLAY_FETCHER="local"` instead.
+
+use std::{
+    collections::VecDeque,
+    fmt::Display,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use reqwest::StatusCode;
+use serde_json::Value;
+
+use super::download::MAX_REPLAY_SIZE;
+
+const GAMES_URL: &str = "https://tetr.io/api/games";
+const TIMEOUT: Duration = Duration::from_secs(30);
+/// Replays kept in memory, a tetra league replay weighs a few megabytes
+const CACHE_SIZE: usize = 16;
+
+#[derive(Debug)]
+pub enum ReplayFetchError {
+    NotFound(String),
+    /// The replay source couldn't be reached or refused the request
+    Unavailable(String),
+    Invalid(String),
+}
+
+impl Display for ReplayFetchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReplayFetchError::NotFound(id) => write!(f, "Couldn't find the replay {id}"),
+            ReplayFetchError::Unavailable(msg) => write!(f, "Couldn't download the replay: {msg}"),
+            ReplayFetchError::Invalid(msg) => write!(f, "The downloaded replay is invalid: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for ReplayFetchError {}
+
+/// A replay downloaded from its id.
+#[derive(Clone)]
+pub struct FetchedReplay {
+    pub replay_id: String,
+    pub bytes: Arc<[u8]>,
+    /// Tetra league games are multiplayer, 40 lines and blitz are not
+    pub multiplayer: bool,
+}
+
+impl FetchedReplay {
+    fn new(replay_id: &str, replay: &Value) -> Self {
+        Self {
+            replay_id: replay_id.to_string(),
+            bytes: replay.to_string().into_bytes().into(),
+            multiplayer: replay.get("ismulti").and_then(Value::as_bool).unwrap_or(true),
+        }
+    }
+
+    /// Name given to the replay, with the extension of its kind.
+    pub fn filename(&self) -> String {
+        let extension = if self.multiplayer { "ttrm" } else { "ttr" };
+        format!("{}.{extension}", self.replay_id)
+    }
+}
+
+#[async_trait::async_trait]
+pub trait ReplayFetcher: Send + Sync {
+    async fn fetch(&self, replay_id: &str) -> Result<FetchedReplay, ReplayFetchError>;
+}
+
+/// Extracts the replay id from a tetr.io replay url like `https://tetr.io/#r:ID`, or takes the input as the id.
+pub fn parse_replay_id(input: &str) -> Option<String> {
+    let input = input.trim();
+    let id = ["#r:", "#R:", "r:"]
+        .into_iter()
+        .find_map(|prefix| input.split_once(prefix).map(|(_, id)| id))
+        .unwrap_or(input);
+
+    let valid = !id.is_empty()
+        && id.len() <= 64
+        && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    valid.then(|| id.to_string())
+}
+
+/// Builds the fetcher from the `REPLAY_FETCHER` environment variable, `local` reads the replays from
+/// `REPLAY_FETCHER_DIR` and anything else downloads them from tetr.io with `TETRIO_TOKEN`.
+pub fn from_env() -> Box<dyn ReplayFetcher> {
+    let fetcher: Box<dyn ReplayFetcher> = match std::env::var("REPLAY_FETCHER").map(|fetcher| fetcher.to_lowercase()).as_deref() {
+        Ok("local") => {
+            let directory = std::env::var("REPLAY_FETCHER_DIR").unwrap_or("replays/tetrio".to_string());
+            log::info!("Reading replays by id from {directory}");
+            Box::new(LocalReplayFetcher::new(directory.into()))
+        }
+        _ => Box::new(
+            HttpReplayFetcher::new(std::env::var("TETRIO_TOKEN").ok()).expect("Couldn't build the replay fetcher"),
+        ),
+    };
+
+    Box::new(CachedReplayFetcher::new(fetcher))
+}
+
+pub struct HttpReplayFetcher {
+    client: reqwest::Client,
+    /// Bearer token of the personal tetr.io account downloading the replays, tetr.io only serves them to logged in accounts
+    token: Option<String>,
+}
+
+impl HttpReplayFetcher {
+    pub fn new(token: Option<String>) -> reqwest::Result<Self> {
+        Ok(Self {
+            client: reqwest::Client::builder().timeout(TIMEOUT).build()?,
+            token,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl ReplayFetcher for HttpReplayFetcher {
+    async fn fetch(&self, replay_id: &str) -> Result<FetchedReplay, ReplayFetchError> {
+        let Some(token) = &self.token else {
+            return Err(ReplayFetchError::Unavailable("the bot has no tetr.io token".to_string()));
+        };
+
+        let mut response = self
+            .client
+            .get(format!("{GAMES_URL}/{replay_id}"))
+            .bearer_auth(token)
+            .send()
+            .await
+            .map_err(|err| ReplayFetchError::Unavailable(err.to_string()))?;
+
+        match response.status() {
+            StatusCode::NOT_FOUND => return Err(ReplayFetchError::NotFound(replay_id.to_string())),
+            status if !status.is_success() => {
+                return Err(ReplayFetchError::Unavailable(format!("tetr.io answered with {status}")))
+            }
+            _ => {}
+        }
+
+        let too_large = || ReplayFetchError::Invalid(format!("larger than {} MiB", MAX_REPLAY_SIZE / 1024 / 1024));
+        if response.content_length().is_some_and(|length| length > MAX_REPLAY_SIZE as u64) {
+            return Err(too_large());
+        }
+
+        // the announced length can lie, the body is read chunk by chunk up to the limit
+        let mut bytes = Vec::new();
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|err| ReplayFetchError::Unavailable(err.to_string()))?
+        {
+            if bytes.len() + chunk.len() > MAX_REPLAY_SIZE {
+                return Err(too_large());
+            }
+            bytes.extend_from_slice(&chunk);
+        }
+
+        let body: Value = serde_json::from_slice(&bytes).map_err(|err| ReplayFetchError::Invalid(err.to_string()))?;
+
+        if body.get("success").and_then(Value::as_bool) != Some(true) {
+            return Err(ReplayFetchError::NotFound(replay_id.to_string()));
+        }
+
+        body.get("game")
+            .filter(|game| game.is_object())
+            .map(|game| FetchedReplay::new(replay_id, game))
+            .ok_or_else(|| ReplayFetchError::Invalid("no game in the response".to_string()))
+    }
+}
+
+/// Reads `ID.ttrm` or `ID.ttr` from a directory, to run the bot without a tetr.io account.
+pub struct LocalReplayFetcher {
+    directory: PathBuf,
+}
+
+impl LocalReplayFetcher {
+    pub fn new(directory: PathBuf) -> Self {
+        Self { directory }
+    }
+}
+
+#[async_trait::async_trait]
+impl ReplayFetcher for LocalReplayFetcher {
+    async fn fetch(&self, replay_id: &str) -> Result<FetchedReplay, ReplayFetchError> {
+        for extension in ["ttrm", "ttr"] {
+            let Ok(bytes) = tokio::fs::read(self.directory.join(format!("{replay_id}.{extension}"))).await else {
+                continue;
+            };
+            let replay: Value =
+                serde_json::from_slice(&bytes).map_err(|err| ReplayFetchError::Invalid(err.to_string()))?;
+            return Ok(FetchedReplay::new(replay_id, &replay));
+        }
+
+        Err(ReplayFetchError::NotFound(replay_id.to_string()))
+    }
+}
+
+/// Keeps the last replays in memory, the same game is often looked at several times in a row.
+pub struct CachedReplayFetcher {
+    inner: Box<dyn ReplayFetcher>,
+    cache: Mutex<VecDeque<FetchedReplay>>,
+}
+
+impl CachedReplayFetcher {
+    pub fn new(inner: Box<dyn ReplayFetcher>) -> Self {
+        Self {
+            inner,
+            cache: Mutex::new(VecDeque::with_capacity(CACHE_SIZE)),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl ReplayFetcher for CachedReplayFetcher {
+    async fn fetch(&self, replay_id: &str) -> Result<FetchedReplay, ReplayFetchError> {
+        let cached = self
+            .cache
+            .lock()
+            .expect("replay cache poisoned")
+            .iter()
+            .find(|replay| replay.replay_id == replay_id)
+            .cloned();
+        if let Some(replay) = cached {
+            return Ok(replay);
+        }
+
+        let replay = self.inner.fetch(replay_id).await?;
+
+        let mut cache = self.cache.lock().expect("replay cache poisoned");
+        if cache.len() >= CACHE_SIZE {
+            cache.pop_front();
+        }
+        cache.push_back(replay.clone());
+
+        Ok(replay)
+    }
+}
@@ -0,0 +1,57 @@
+//! Background jobs of the bot, like the rank role sync or the announcement poller.
+
+use std::{future::Future, sync::Arc, time::Duration};
+
+use crate::context::Context;
+
+/// A job running at a fixed interval, the first run happens right away. It stops when dropped, so a restarted
+/// bot doesn't run it twice.
+pub struct ScheduledJob(tokio::task::JoinHandle<()>);
+
+impl ScheduledJob {
+    pub fn spawn<F, Fut>(name: &'static str, interval: Duration, context: Arc<Context<'static>>, job: F) -> Self
+    where
+        F: Fn(Arc<Context<'static>>) -> Fut + Send + 'static,
+        Fut: Future<Output = anyhow::Result<()>> + Send,
+    {
+        log::info!("scheduling {name} every {}s", interval.as_secs());
+
+        Self(tokio::spawn(async move {
+            let mut interval = tokio::time::interval(interval);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                interval.tick().await;
+                if let Err(err) = job(Arc::clone(&context)).await {
+                    log::error!("{name} failed: {err:?}");
+                }
+            }
+        }))
+    }
+}
+
+impl Drop for ScheduledJob {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+/// Reads an interval from an environment variable, falling back to the default when it is missing, zero or too large.
+pub fn interval_from_env(variable: &str, unit: Duration, default: u64) -> Duration {
+    let interval = |count: u64| {
+        u32::try_from(count)
+            .ok()
+            .filter(|count| *count > 0)
+            .and_then(|count| unit.checked_mul(count))
+    };
+
+    let Ok(value) = std::env::var(variable) else {
+        return interval(default).unwrap_or(unit);
+    };
+    match value.parse::<u64>().ok().and_then(interval) {
+        Some(interval) => interval,
+        None => {
+            log::warn!("{variable}={value} is not a valid interval, using the default of {default}");
+            interval(default).unwrap_or(unit)
+        }
+    }
+}
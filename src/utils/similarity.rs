@@ -0,0 +1,75 @@
+#![cfg(feature = "tetrio")]
+
+use super::stats::Stats;
+
+pub const PLAYSTYLE_DIMENSIONS: usize = 10;
+
+pub type PlaystyleVector = [f64; PLAYSTYLE_DIMENSIONS];
+
+/// Position of a player in the playstyle space: the normalized stats and the playstyle axes of `calculate_stats`.
+pub fn playstyle_vector(stats: &Stats) -> Option<PlaystyleVector> {
+    let vector = [
+        stats.napm,
+        stats.npps,
+        stats.napp,
+        stats.ndsp,
+        stats.nge,
+        stats.nvsapm,
+        stats.opener,
+        stats.plonk,
+        stats.stride,
+        stats.infds,
+    ];
+
+    vector.iter().all(|value| value.is_finite()).then_some(vector)
+}
+
+/// Mean and standard deviation of every dimension of the vectors, a deviation of 1 for a dimension that doesn't vary.
+pub fn distribution<'a, const N: usize>(
+    vectors: impl Iterator<Item = &'a [f64; N]> + Clone,
+) -> ([f64; N], [f64; N]) {
+    let count = vectors.clone().count().max(1) as f64;
+    let mut mean = [0.0; N];
+    for vector in vectors.clone() {
+        for (mean, value) in mean.iter_mut().zip(vector) {
+            *mean += value / count;
+        }
+    }
+
+    let mut variance = [0.0; N];
+    for vector in vectors {
+        for ((variance, value), mean) in variance.iter_mut().zip(vector).zip(&mean) {
+            *variance += (value - mean).powi(2) / count;
+        }
+    }
+    let deviation = variance.map(|variance| if variance > 0.0 { variance.sqrt() } else { 1.0 });
+
+    (mean, deviation)
+}
+
+/// Standardizes every dimension of the playstyle space so that no stat outweighs the others.
+pub struct PlaystyleSpace {
+    deviation: PlaystyleVector,
+}
+
+impl PlaystyleSpace {
+    pub fn from_vectors<'a>(vectors: impl Iterator<Item = &'a PlaystyleVector> + Clone) -> Self {
+        let (_, deviation) = distribution(vectors);
+
+        Self { deviation }
+    }
+
+    fn distance(&self, left: &PlaystyleVector, right: &PlaystyleVector) -> f64 {
+        left.iter()
+            .zip(right)
+            .zip(&self.deviation)
+            .map(|((left, right), deviation)| ((left - right) / deviation).powi(2))
+            .sum::<f64>()
+            .sqrt()
+    }
+
+    /// Similarity between two players, from 0% to 100% for identical playstyles.
+    pub fn similarity(&self, left: &PlaystyleVector, right: &PlaystyleVector) -> f64 {
+        100.0 / (1.0 + self.distance(left, right) / (PLAYSTYLE_DIMENSIONS as f64).sqrt())
+    }
+}
@@ -0,0 +1,235 @@
+#![cfg(feature = "tetrio")]
+//! Stats of single player ttr replays, like 40 lines and blitz.
+//!
+//! Old replays keep the stats in `endcontext` and newer ones in `results.stats`, both are read from the raw json
+//! with the same field names, as are the personal bests of the tetra channel api.
+
+use std::{fmt::Display, time::Duration};
+
+use anyhow::anyhow;
+use serde_json::Value;
+
+use super::download::url_with_segments;
+
+const USERS_URL: &str = "https://ch.tetr.io/api/users";
+const TIMEOUT: Duration = Duration::from_secs(15);
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SoloMode {
+    Sprint,
+    Blitz,
+    Other(String),
+}
+
+impl SoloMode {
+    fn parse(mode: &str) -> Self {
+        match mode {
+            "40l" => Self::Sprint,
+            "blitz" => Self::Blitz,
+            mode => Self::Other(mode.to_string()),
+        }
+    }
+
+    /// Name of the mode in the tetra channel api
+    pub fn api_name(&self) -> Option<&'static str> {
+        match self {
+            Self::Sprint => Some("40l"),
+            Self::Blitz => Some("blitz"),
+            Self::Other(_) => None,
+        }
+    }
+}
+
+impl Display for SoloMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Sprint => write!(f, "40 Lines"),
+            Self::Blitz => write!(f, "Blitz"),
+            Self::Other(mode) => write!(f, "{mode}"),
+        }
+    }
+}
+
+/// Line clears of a game, T-spins are counted apart from the normal clears.
+#[derive(Clone, Debug, Default)]
+pub struct ClearBreakdown {
+    pub singles: u64,
+    pub doubles: u64,
+    pub triples: u64,
+    pub quads: u64,
+    pub tspin_minis: u64,
+    pub tspin_mini_singles: u64,
+    pub tspin_mini_doubles: u64,
+    pub tspin_singles: u64,
+    pub tspin_doubles: u64,
+    pub tspin_triples: u64,
+    pub tspin_quads: u64,
+    pub all_clears: u64,
+}
+
+#[derive(Clone, Debug)]
+pub struct SoloStats {
+    /// In seconds
+    pub final_time: f64,
+    pub score: u64,
+    pub lines: u64,
+    pub pieces: u64,
+    pub inputs: u64,
+    pub finesse_faults: u64,
+    pub perfect_pieces: u64,
+    pub max_combo: u64,
+    pub max_b2b: u64,
+    pub clears: ClearBreakdown,
+}
+
+impl SoloStats {
+    pub fn pps(&self) -> f64 {
+        self.pieces as f64 / self.final_time.max(f64::EPSILON)
+    }
+
+    /// Keys per piece
+    pub fn kpp(&self) -> f64 {
+        self.inputs as f64 / self.pieces.max(1) as f64
+    }
+
+    /// Share of the pieces placed without finesse fault
+    pub fn finesse(&self) -> f64 {
+        self.perfect_pieces as f64 / self.pieces.max(1) as f64
+    }
+
+    /// Reads the stats of a personal best record of the tetra channel api.
+    pub fn from_record(record: &Value) -> Option<Self> {
+        stats_object(record).and_then(Self::from_value)
+    }
+
+    /// Reads the stats object of a replay or a record.
+    fn from_value(stats: &Value) -> Option<Self> {
+        let count = |pointer: &str| stats.pointer(pointer).and_then(number).unwrap_or(0.0) as u64;
+        let final_time = stats
+            .get("finaltime")
+            .or_else(|| stats.get("finalTime"))
+            .and_then(number)?;
+
+        Some(Self {
+            final_time: final_time / 1000.0,
+            score: count("/score"),
+            lines: count("/lines"),
+            pieces: count("/piecesplaced"),
+            inputs: count("/inputs"),
+            finesse_faults: count("/finesse/faults"),
+            perfect_pieces: count("/finesse/perfectpieces"),
+            max_combo: count("/topcombo"),
+            max_b2b: count("/topbtb"),
+            clears: ClearBreakdown {
+                singles: count("/clears/singles"),
+                doubles: count("/clears/doubles"),
+                triples: count("/clears/triples"),
+                quads: count("/clears/quads"),
+                tspin_minis: count("/clears/minitspins"),
+                tspin_mini_singles: count("/clears/minitspinsingles"),
+                tspin_mini_doubles: count("/clears/minitspindoubles"),
+                tspin_singles: count("/clears/tspinsingles"),
+                tspin_doubles: count("/clears/tspindoubles"),
+                tspin_triples: count("/clears/tspintriples"),
+                tspin_quads: count("/clears/tspinquads"),
+                all_clears: count("/clears/allclear"),
+            },
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SoloReplay {
+    pub username: String,
+    pub mode: SoloMode,
+    pub stats: SoloStats,
+    /// When the game was played, as written in the replay
+    pub ts: Option<String>,
+}
+
+#[derive(Clone, Debug)]
+pub struct PersonalBest {
+    pub stats: SoloStats,
+    pub replay_id: Option<String>,
+}
+
+fn number(value: &Value) -> Option<f64> {
+    value.as_f64().or_else(|| value.as_str()?.parse().ok())
+}
+
+/// The stats object of a replay or a record, whatever the version of its format.
+fn stats_object(value: &Value) -> Option<&Value> {
+    ["/endcontext", "/results/stats", "/replay/results/stats"]
+        .into_iter()
+        .filter_map(|pointer| value.pointer(pointer))
+        .find(|stats| stats.is_object())
+}
+
+impl SoloReplay {
+    pub fn parse(bytes: &[u8]) -> anyhow::Result<Self> {
+        let replay: Value = serde_json::from_slice(bytes)
+            .map_err(|err| anyhow!("❌ Couldn't parse ttr replay: {:?}", err))?;
+
+        if replay.get("ismulti").and_then(Value::as_bool) == Some(true) {
+            return Err(anyhow!("❌ This is a multiplayer replay, use a ttrm replay command instead"));
+        }
+
+        let stats = stats_object(&replay)
+            .and_then(SoloStats::from_value)
+            .ok_or_else(|| anyhow!("❌ Couldn't find the results of the game in the replay"))?;
+
+        let mode = ["/gamemode", "/gametype", "/endcontext/gametype"]
+            .into_iter()
+            .find_map(|pointer| replay.pointer(pointer)?.as_str())
+            .map(SoloMode::parse)
+            .unwrap_or(SoloMode::Other("unknown".to_string()));
+
+        let username = ["/user/username", "/users/0/username", "/endcontext/username"]
+            .into_iter()
+            .find_map(|pointer| replay.pointer(pointer)?.as_str())
+            .ok_or_else(|| anyhow!("❌ Couldn't find the player of the replay"))?
+            .to_string();
+
+        let ts = replay.get("ts").and_then(Value::as_str).map(str::to_string);
+
+        Ok(Self { username, mode, stats, ts })
+    }
+
+    /// Fetches the personal best of the player of the replay in the same mode.
+    pub async fn personal_best(&self) -> anyhow::Result<anyhow::Result<PersonalBest>> {
+        let Some(mode) = self.mode.api_name() else {
+            return Ok(Err(anyhow!("❌ Personal bests are only available for 40 lines and blitz")));
+        };
+
+        let url = url_with_segments(USERS_URL, &[&self.username.to_lowercase(), "summaries", mode])?;
+        let response: Value = reqwest::Client::builder()
+            .timeout(TIMEOUT)
+            .build()?
+            .get(url)
+            .send()
+            .await?
+            .json()
+            .await?;
+
+        if let Some(error) = response.get("error") {
+            let message = error.get("msg").and_then(Value::as_str).unwrap_or("of an unknown error");
+            return Ok(Err(anyhow!("❌ Couldn't fetch the personal best of {} because {message}", self.username)));
+        }
+
+        let Some(record) = response.pointer("/data/record").filter(|record| !record.is_null()) else {
+            return Ok(Err(anyhow!("❌ {} doesn't have a personal best in {}", self.username, self.mode)));
+        };
+
+        Ok(SoloStats::from_record(record)
+            .map(|stats| PersonalBest {
+                stats,
+                replay_id: record.get("replayid").and_then(Value::as_str).map(str::to_string),
+            })
+            .ok_or_else(|| anyhow!("❌ Couldn't read the personal best of {}", self.username)))
+    }
+}
+
+/// Formats a time in seconds as `m:ss.mmm`.
+pub fn format_duration(seconds: f64) -> String {
+    format!("{}:{:06.3}", (seconds / 60.0).floor(), seconds % 60.0)
+}
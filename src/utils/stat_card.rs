@@ -0,0 +1,322 @@
+#![cfg(all(feature = "tetrio", feature = "local_image_generation"))]
+//! Stat cards rendered as images, so that the stats stay readable on small screens.
+
+use std::io::Cursor;
+
+use ab_glyph::{FontRef, PxScale};
+use image::{
+    imageops::{self, FilterType},
+    ImageFormat, Rgba, RgbaImage,
+};
+use imageproc::{
+    drawing::{draw_filled_rect_mut, draw_text_mut, text_size, Blend},
+    rect::Rect,
+};
+
+use super::{
+    charts::local::{draw_centered_text, parse_color, Canvas, FONT},
+    download::download,
+    stats::{calculate_stats, stringify_stats, PlayerStats, Stats},
+};
+
+pub const CARD_FILENAME: &str = "card.png";
+
+const CARD_WIDTH: u32 = 800;
+const CARD_HEIGHT: u32 = 470;
+const MARGIN: i32 = 24;
+const AVATAR_SIZE: u32 = 96;
+const BADGE_SIZE: u32 = 80;
+const CELL_HEIGHT: i32 = 52;
+const SECTION_TITLE_HEIGHT: i32 = 28;
+const COLUMNS: i32 = 4;
+/// Playstyle axes are drawn as bars, full at this value
+const AXIS_MAX: f64 = 1.2;
+
+const COMPARISON_LABEL_WIDTH: u32 = 180;
+const COMPARISON_COLUMN_WIDTH: u32 = 150;
+const COMPARISON_HEADER_HEIGHT: u32 = 140;
+const COMPARISON_ROW_HEIGHT: u32 = 28;
+/// Avatars and rank badges weigh a few hundred kilobytes at most
+const MAX_IMAGE_SIZE: usize = 4 * 1024 * 1024;
+
+struct Palette {
+    background: Rgba<u8>,
+    stripe: Rgba<u8>,
+    text: Rgba<u8>,
+    muted: Rgba<u8>,
+    accent: Rgba<u8>,
+}
+
+impl Palette {
+    fn new(dark_mode: bool) -> Self {
+        if dark_mode {
+            Self {
+                background: Rgba([32, 34, 37, 255]),
+                stripe: Rgba([44, 47, 51, 255]),
+                text: parse_color("#F5F5F5"),
+                muted: Rgba([160, 160, 165, 255]),
+                accent: parse_color("rgba(254,190,9,1)"),
+            }
+        } else {
+            Self {
+                background: Rgba([245, 245, 245, 255]),
+                stripe: Rgba([232, 232, 236, 255]),
+                text: parse_color("#000000"),
+                muted: Rgba([100, 100, 110, 255]),
+                accent: parse_color("rgba(132,92,248,1)"),
+            }
+        }
+    }
+}
+
+/// A player with the images displayed on their card.
+pub struct CardPlayer {
+    pub name: String,
+    pub avatar: RgbaImage,
+    pub badge: Option<RgbaImage>,
+    pub stats: Stats,
+}
+
+async fn fetch_image(url: &str) -> Option<RgbaImage> {
+    let image = async {
+        let bytes = download(url, MAX_IMAGE_SIZE).await??;
+        anyhow::Ok(image::load_from_memory(&bytes)?.to_rgba8())
+    };
+
+    match image.await {
+        Ok(image) => Some(image),
+        Err(err) => {
+            log::warn!("Couldn't fetch image {url} for a stat card: {err}");
+            None
+        }
+    }
+}
+
+fn default_avatar() -> RgbaImage {
+    image::load_from_memory(include_bytes!("../assets/unkown_avatar.webp"))
+        .map(|avatar| avatar.to_rgba8())
+        .unwrap_or_else(|_| RgbaImage::from_pixel(AVATAR_SIZE, AVATAR_SIZE, Rgba([128, 128, 128, 255])))
+}
+
+impl CardPlayer {
+    /// Downloads the avatar and rank badge of the player, missing images fall back to the default avatar and no badge.
+    pub async fn fetch(name: String, avatar_url: Option<String>, stats: PlayerStats) -> Self {
+        let avatar = async {
+            match &avatar_url {
+                Some(url) => fetch_image(url).await,
+                None => None,
+            }
+        };
+
+        let badge = async {
+            match &stats.rank {
+                Some(rank) => {
+                    fetch_image(&format!(
+                        "https://tetr.io/res/league-ranks/{}.png",
+                        rank.to_string().to_lowercase()
+                    ))
+                    .await
+                }
+                None => None,
+            }
+        };
+        let (avatar, badge) = tokio::join!(avatar, badge);
+
+        Self {
+            name,
+            avatar: avatar.unwrap_or_else(default_avatar),
+            badge,
+            stats: calculate_stats(stats),
+        }
+    }
+}
+
+fn paste(canvas: &mut Canvas, image: &RgbaImage, x: i32, y: i32, size: u32) {
+    let image = imageops::resize(image, size, size, FilterType::Triangle);
+    imageops::overlay(&mut canvas.0, &image, x as i64, y as i64);
+}
+
+fn encode(canvas: Canvas) -> anyhow::Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    canvas.0.write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)?;
+    Ok(bytes)
+}
+
+/// Shortens text until it fits in `width` pixels.
+fn fit_text(font: &FontRef, scale: f32, text: &str, width: u32) -> String {
+    let fits = |text: &str| text_size(PxScale::from(scale), font, text).0 <= width;
+    if fits(text) {
+        return text.to_string();
+    }
+
+    let mut chars = text.chars().collect::<Vec<_>>();
+    while chars.len() > 1 {
+        chars.pop();
+        let shortened = format!("{}…", chars.iter().collect::<String>());
+        if fits(&shortened) {
+            return shortened;
+        }
+    }
+    text.chars().take(1).collect()
+}
+
+struct Cell {
+    label: &'static str,
+    value: String,
+    /// Fills a bar under the value, from 0 to 1
+    bar: Option<f64>,
+}
+
+fn cell(label: &'static str, value: String) -> Cell {
+    Cell { label, value, bar: None }
+}
+
+/// Draws a titled grid of cells and returns the y coordinate below it.
+fn draw_section(canvas: &mut Canvas, font: &FontRef, palette: &Palette, y: i32, title: &str, cells: &[Cell]) -> i32 {
+    draw_text_mut(canvas, palette.accent, MARGIN, y, PxScale::from(20.0), font, title);
+    let y = y + SECTION_TITLE_HEIGHT;
+    let cell_width = (CARD_WIDTH as i32 - 2 * MARGIN) / COLUMNS;
+
+    for (i, cell) in cells.iter().enumerate() {
+        let x = MARGIN + (i as i32 % COLUMNS) * cell_width;
+        let cell_y = y + (i as i32 / COLUMNS) * CELL_HEIGHT;
+
+        draw_text_mut(canvas, palette.muted, x, cell_y, PxScale::from(14.0), font, cell.label);
+        draw_text_mut(canvas, palette.text, x, cell_y + 16, PxScale::from(24.0), font, &cell.value);
+
+        if let Some(bar) = cell.bar {
+            let width = (cell_width - 24) as f64;
+            draw_filled_rect_mut(canvas, Rect::at(x, cell_y + 44).of_size(width as u32, 4), palette.stripe);
+            let filled = (bar.clamp(0.0, 1.0) * width) as u32;
+            if filled > 0 {
+                draw_filled_rect_mut(canvas, Rect::at(x, cell_y + 44).of_size(filled, 4), palette.accent);
+            }
+        }
+    }
+
+    let rows = (cells.len() as i32 + COLUMNS - 1) / COLUMNS;
+    y + rows * CELL_HEIGHT + 8
+}
+
+/// Renders the card of a single player: header, core stats, derived stats and playstyle axes.
+pub fn render_card(player: &CardPlayer, dark_mode: bool) -> anyhow::Result<Vec<u8>> {
+    let font = FontRef::try_from_slice(FONT)?;
+    let palette = Palette::new(dark_mode);
+    let mut canvas = Blend(RgbaImage::from_pixel(CARD_WIDTH, CARD_HEIGHT, palette.background));
+
+    paste(&mut canvas, &player.avatar, MARGIN, MARGIN, AVATAR_SIZE);
+    if let Some(badge) = &player.badge {
+        paste(&mut canvas, badge, CARD_WIDTH as i32 - MARGIN - BADGE_SIZE as i32, MARGIN, BADGE_SIZE);
+    }
+
+    let header_x = MARGIN + AVATAR_SIZE as i32 + 16;
+    let header_width = CARD_WIDTH - header_x as u32 - BADGE_SIZE - 2 * MARGIN as u32;
+    let name = fit_text(&font, 36.0, &player.name.to_uppercase(), header_width);
+    draw_text_mut(&mut canvas, palette.text, header_x, MARGIN + 4, PxScale::from(36.0), &font, &name);
+
+    let stats = stringify_stats(player.stats.clone());
+    let subtitle = match (&stats.tr, &stats.glicko, &stats.rd) {
+        (Some(tr), Some(glicko), Some(rd)) => format!("{tr} TR  •  {glicko} ± {rd} Glicko"),
+        (Some(tr), _, _) => format!("{tr} TR"),
+        _ => format!("Estimated {} TR", stats.esttr),
+    };
+    draw_text_mut(&mut canvas, palette.muted, header_x, MARGIN + 52, PxScale::from(18.0), &font, &subtitle);
+
+    let y = MARGIN + AVATAR_SIZE as i32 + 16;
+    let y = draw_section(&mut canvas, &font, &palette, y, "CORE", &[
+        cell("APM", stats.apm.clone()),
+        cell("PPS", stats.pps.clone()),
+        cell("VS", stats.vs.clone()),
+        cell("EST. TR", stats.esttr.clone()),
+    ]);
+    let y = draw_section(&mut canvas, &font, &palette, y, "DERIVED", &[
+        cell("APP", stats.app.clone()),
+        cell("DS/SECOND", stats.dssecond.clone()),
+        cell("DS/PIECE", stats.dspiece.clone()),
+        cell("APP+DS/PIECE", stats.dsapppiece.clone()),
+        cell("VS/APM", stats.vsapm.clone()),
+        cell("CHEESE INDEX", stats.cheese.clone()),
+        cell("GARBAGE EFFI.", stats.garbage_effi.clone()),
+        cell("WEIGHTED APP", stats.weighted_app.clone()),
+    ]);
+    let axis = |label, value: String, raw: f64| Cell { label, value, bar: Some(raw / AXIS_MAX) };
+    draw_section(&mut canvas, &font, &palette, y, "PLAYSTYLE", &[
+        axis("OPENER", stats.opener.clone(), player.stats.opener),
+        axis("PLONK", stats.plonk.clone(), player.stats.plonk),
+        axis("STRIDE", stats.stride.clone(), player.stats.stride),
+        axis("INF DS", stats.infds.clone(), player.stats.infds),
+    ]);
+
+    encode(canvas)
+}
+
+/// Rows of the comparison card, the highest value is highlighted when the flag is set.
+const COMPARISON_ROWS: &[(&str, fn(&Stats) -> f64, bool)] = &[
+    ("APM", |stats| stats.apm, true),
+    ("PPS", |stats| stats.pps, true),
+    ("VS", |stats| stats.vs, true),
+    ("APP", |stats| stats.app, true),
+    ("DS/Piece", |stats| stats.dspiece, true),
+    ("APP+DS/Piece", |stats| stats.dsapppiece, true),
+    ("DS/Second", |stats| stats.dssecond, true),
+    ("VS/APM", |stats| stats.vsapm, false),
+    ("Cheese Index", |stats| stats.cheese, false),
+    ("Garbage Effi.", |stats| stats.garbage_effi, true),
+    ("Weighted APP", |stats| stats.weighted_app, true),
+    ("Area", |stats| stats.area, true),
+    ("Est. TR", |stats| stats.esttr, true),
+    ("Opener", |stats| stats.opener, false),
+    ("Plonk", |stats| stats.plonk, false),
+    ("Stride", |stats| stats.stride, false),
+    ("Inf DS", |stats| stats.infds, false),
+];
+
+/// Renders the players side by side, one column per player.
+pub fn render_comparison(players: &[CardPlayer], dark_mode: bool) -> anyhow::Result<Vec<u8>> {
+    let font = FontRef::try_from_slice(FONT)?;
+    let palette = Palette::new(dark_mode);
+    let width = COMPARISON_LABEL_WIDTH + COMPARISON_COLUMN_WIDTH * players.len() as u32 + MARGIN as u32;
+    let height = COMPARISON_HEADER_HEIGHT + COMPARISON_ROW_HEIGHT * COMPARISON_ROWS.len() as u32 + MARGIN as u32;
+    let mut canvas = Blend(RgbaImage::from_pixel(width, height, palette.background));
+
+    for (i, player) in players.iter().enumerate() {
+        let column_x = COMPARISON_LABEL_WIDTH as i32 + (COMPARISON_COLUMN_WIDTH * i as u32) as i32;
+        let center_x = column_x as f32 + COMPARISON_COLUMN_WIDTH as f32 / 2.0;
+        let avatar_size = 72;
+
+        paste(&mut canvas, &player.avatar, center_x as i32 - avatar_size / 2, 16, avatar_size as u32);
+        if let Some(badge) = &player.badge {
+            paste(&mut canvas, badge, center_x as i32 + avatar_size / 2 - 16, 16 + avatar_size - 24, 32);
+        }
+
+        let name = fit_text(&font, 16.0, &player.name, COMPARISON_COLUMN_WIDTH - 12);
+        draw_centered_text(&mut canvas, &font, palette.text, 16.0, center_x, 16.0 + avatar_size as f32 + 22.0, &name);
+    }
+
+    for (row, (label, stat, highlight)) in COMPARISON_ROWS.iter().enumerate() {
+        let y = COMPARISON_HEADER_HEIGHT as i32 + (COMPARISON_ROW_HEIGHT * row as u32) as i32;
+        if row % 2 == 0 {
+            draw_filled_rect_mut(
+                &mut canvas,
+                Rect::at(MARGIN / 2, y).of_size(width - MARGIN as u32, COMPARISON_ROW_HEIGHT),
+                palette.stripe,
+            );
+        }
+        draw_text_mut(&mut canvas, palette.muted, MARGIN, y + 6, PxScale::from(16.0), &font, label);
+
+        let values = players.iter().map(|player| stat(&player.stats)).collect::<Vec<_>>();
+        let best = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+
+        for (i, value) in values.iter().enumerate() {
+            let color = if *highlight && players.len() > 1 && *value == best {
+                palette.accent
+            } else {
+                palette.text
+            };
+            let center_x = COMPARISON_LABEL_WIDTH as f32 + COMPARISON_COLUMN_WIDTH as f32 * (i as f32 + 0.5);
+            draw_centered_text(&mut canvas, &font, color, 16.0, center_x, y as f32 + COMPARISON_ROW_HEIGHT as f32 / 2.0, &format!("{value:.4}"));
+        }
+    }
+
+    encode(canvas)
+}
@@ -0,0 +1,570 @@
+#![cfg(feature = "tetrio")]
+
+use std::{collections::HashMap, fmt::Display};
+
+use anyhow::anyhow;
+use twilight_model::id::{marker::GuildMarker, Id};
+
+use crate::context::Context;
+#[cfg(feature = "database")]
+use crate::services::stat_formula::StatFormulaPDO;
+
+use super::stats::Stats;
+
+pub const MAX_FORMULA_LENGTH: usize = 200;
+
+/// Every variable a formula can use, with a short description shown in `/formula variables`.
+pub const VARIABLES: &[(&str, &str)] = &[
+    ("apm", "attacks per minute"),
+    ("pps", "pieces per second"),
+    ("vs", "vs score"),
+    ("app", "attack per piece"),
+    ("dssecond", "downstack per second"),
+    ("dspiece", "downstack per piece"),
+    ("dsapppiece", "downstack + attack per piece"),
+    ("vsapm", "vs / apm"),
+    ("cheese", "cheese index"),
+    ("ge", "garbage efficiency"),
+    ("wapp", "weighted app"),
+    ("area", "area"),
+    ("srarea", "stat rank area"),
+    ("statrank", "stat rank"),
+    ("estglicko", "estimated glicko"),
+    ("esttr", "estimated TR"),
+    ("atr", "accuracy of the estimated TR"),
+    ("tr", "TR"),
+    ("glicko", "glicko"),
+    ("rd", "rating deviation"),
+    ("opener", "opener"),
+    ("plonk", "plonk"),
+    ("stride", "stride"),
+    ("infds", "infinite downstack"),
+    ("napm", "normalized apm"),
+    ("npps", "normalized pps"),
+    ("nvs", "normalized vs"),
+    ("napp", "normalized app"),
+    ("ndss", "normalized downstack per second"),
+    ("ndsp", "normalized downstack per piece"),
+    ("nge", "normalized garbage efficiency"),
+    ("nvsapm", "normalized vs / apm"),
+    ("wins", "tetra league games won"),
+    ("games", "tetra league games played"),
+    ("winrate", "tetra league win rate"),
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Function {
+    Min,
+    Max,
+    Log,
+    Pow,
+    Sqrt,
+    Abs,
+    Exp,
+}
+
+impl Function {
+    pub const ALL: [(&'static str, Function); 7] = [
+        ("min", Function::Min),
+        ("max", Function::Max),
+        ("log", Function::Log),
+        ("pow", Function::Pow),
+        ("sqrt", Function::Sqrt),
+        ("abs", Function::Abs),
+        ("exp", Function::Exp),
+    ];
+
+    fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .find(|(function_name, _)| *function_name == name)
+            .map(|(_, function)| *function)
+    }
+
+    fn accepts(&self, arguments: usize) -> bool {
+        match self {
+            Function::Min | Function::Max => arguments >= 2,
+            Function::Log => arguments == 1 || arguments == 2,
+            Function::Pow => arguments == 2,
+            Function::Sqrt | Function::Abs | Function::Exp => arguments == 1,
+        }
+    }
+
+    fn apply(&self, arguments: &[f64]) -> f64 {
+        match self {
+            Function::Min => arguments.iter().copied().fold(f64::INFINITY, f64::min),
+            Function::Max => arguments.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+            Function::Log => match arguments {
+                [value] => value.ln(),
+                [value, base] => value.log(*base),
+                _ => f64::NAN,
+            },
+            Function::Pow => arguments[0].powf(arguments[1]),
+            Function::Sqrt => arguments[0].sqrt(),
+            Function::Abs => arguments[0].abs(),
+            Function::Exp => arguments[0].exp(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Operator {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Power,
+}
+
+#[derive(Clone, Debug)]
+enum Expression {
+    Number(f64),
+    Variable(&'static str),
+    Negate(Box<Expression>),
+    Binary(Operator, Box<Expression>, Box<Expression>),
+    Call(Function, Vec<Expression>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Number(f64),
+    Identifier(String),
+    Operator(char),
+    LeftParenthesis,
+    RightParenthesis,
+    Comma,
+}
+
+impl Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Number(number) => write!(f, "{number}"),
+            Token::Identifier(name) => write!(f, "{name}"),
+            Token::Operator(operator) => write!(f, "{operator}"),
+            Token::LeftParenthesis => write!(f, "("),
+            Token::RightParenthesis => write!(f, ")"),
+            Token::Comma => write!(f, ","),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum FormulaError {
+    TooLong,
+    Empty,
+    UnexpectedCharacter(char),
+    UnexpectedToken(String),
+    UnexpectedEnd,
+    UnknownVariable(String),
+    UnknownFunction(String),
+    WrongArgumentCount(String, usize),
+}
+
+impl Display for FormulaError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FormulaError::TooLong => write!(f, "formula is longer than {MAX_FORMULA_LENGTH} characters"),
+            FormulaError::Empty => write!(f, "formula is empty"),
+            FormulaError::UnexpectedCharacter(c) => write!(f, "unexpected character `{c}`"),
+            FormulaError::UnexpectedToken(token) => write!(f, "unexpected `{token}`"),
+            FormulaError::UnexpectedEnd => write!(f, "formula ended unexpectedly"),
+            FormulaError::UnknownVariable(name) => write!(f, "unknown variable `{name}`"),
+            FormulaError::UnknownFunction(name) => write!(f, "unknown function `{name}`"),
+            FormulaError::WrongArgumentCount(name, count) => {
+                write!(f, "`{name}` can't be called with {count} argument(s)")
+            }
+        }
+    }
+}
+
+impl std::error::Error for FormulaError {}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, FormulaError> {
+    let mut tokens = vec![];
+    let mut chars = input.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '0'..='9' | '.' => {
+                let mut number = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_ascii_digit() || c == '.' {
+                        number.push(c);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                let number = number
+                    .parse()
+                    .map_err(|_| FormulaError::UnexpectedToken(number))?;
+                tokens.push(Token::Number(number));
+            }
+            c if c.is_ascii_alphabetic() || c == '_' => {
+                let mut identifier = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_ascii_alphanumeric() || c == '_' {
+                        identifier.push(c.to_ascii_lowercase());
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                tokens.push(Token::Identifier(identifier));
+            }
+            '+' | '-' | '*' | '/' | '^' => {
+                tokens.push(Token::Operator(c));
+                chars.next();
+            }
+            '(' => {
+                tokens.push(Token::LeftParenthesis);
+                chars.next();
+            }
+            ')' => {
+                tokens.push(Token::RightParenthesis);
+                chars.next();
+            }
+            ',' => {
+                tokens.push(Token::Comma);
+                chars.next();
+            }
+            c => return Err(FormulaError::UnexpectedCharacter(c)),
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), FormulaError> {
+        match self.next() {
+            Some(token) if token == expected => Ok(()),
+            Some(token) => Err(FormulaError::UnexpectedToken(token.to_string())),
+            None => Err(FormulaError::UnexpectedEnd),
+        }
+    }
+
+    // expression := term (('+' | '-') term)*
+    fn expression(&mut self) -> Result<Expression, FormulaError> {
+        let mut left = self.term()?;
+        while let Some(Token::Operator(c @ ('+' | '-'))) = self.peek() {
+            let operator = if *c == '+' { Operator::Add } else { Operator::Subtract };
+            self.next();
+            left = Expression::Binary(operator, Box::new(left), Box::new(self.term()?));
+        }
+        Ok(left)
+    }
+
+    // term := unary (('*' | '/') unary)*
+    fn term(&mut self) -> Result<Expression, FormulaError> {
+        let mut left = self.unary()?;
+        while let Some(Token::Operator(c @ ('*' | '/'))) = self.peek() {
+            let operator = if *c == '*' { Operator::Multiply } else { Operator::Divide };
+            self.next();
+            left = Expression::Binary(operator, Box::new(left), Box::new(self.unary()?));
+        }
+        Ok(left)
+    }
+
+    // unary := '-' unary | power
+    fn unary(&mut self) -> Result<Expression, FormulaError> {
+        if let Some(Token::Operator('-')) = self.peek() {
+            self.next();
+            return Ok(Expression::Negate(Box::new(self.unary()?)));
+        }
+        self.power()
+    }
+
+    // power := primary ('^' unary)?
+    fn power(&mut self) -> Result<Expression, FormulaError> {
+        let base = self.primary()?;
+        if let Some(Token::Operator('^')) = self.peek() {
+            self.next();
+            return Ok(Expression::Binary(Operator::Power, Box::new(base), Box::new(self.unary()?)));
+        }
+        Ok(base)
+    }
+
+    // primary := number | variable | function '(' expression (',' expression)* ')' | '(' expression ')'
+    fn primary(&mut self) -> Result<Expression, FormulaError> {
+        match self.next() {
+            Some(Token::Number(number)) => Ok(Expression::Number(number)),
+            Some(Token::LeftParenthesis) => {
+                let expression = self.expression()?;
+                self.expect(Token::RightParenthesis)?;
+                Ok(expression)
+            }
+            Some(Token::Identifier(name)) => {
+                if let Some(Token::LeftParenthesis) = self.peek() {
+                    self.next();
+                    let function = Function::from_name(&name)
+                        .ok_or(FormulaError::UnknownFunction(name.clone()))?;
+                    let mut arguments = vec![self.expression()?];
+                    while let Some(Token::Comma) = self.peek() {
+                        self.next();
+                        arguments.push(self.expression()?);
+                    }
+                    self.expect(Token::RightParenthesis)?;
+                    if !function.accepts(arguments.len()) {
+                        return Err(FormulaError::WrongArgumentCount(name, arguments.len()));
+                    }
+                    Ok(Expression::Call(function, arguments))
+                } else {
+                    VARIABLES
+                        .iter()
+                        .find(|(variable, _)| *variable == name)
+                        .map(|(variable, _)| Expression::Variable(*variable))
+                        .ok_or(FormulaError::UnknownVariable(name))
+                }
+            }
+            Some(token) => Err(FormulaError::UnexpectedToken(token.to_string())),
+            None => Err(FormulaError::UnexpectedEnd),
+        }
+    }
+}
+
+pub struct FormulaVariables(HashMap<&'static str, f64>);
+
+impl FormulaVariables {
+    pub fn from_stats(stats: &Stats) -> Self {
+        let mut variables = HashMap::from([
+            ("apm", stats.apm),
+            ("pps", stats.pps),
+            ("vs", stats.vs),
+            ("app", stats.app),
+            ("dssecond", stats.dssecond),
+            ("dspiece", stats.dspiece),
+            ("dsapppiece", stats.dsapppiece),
+            ("vsapm", stats.vsapm),
+            ("cheese", stats.cheese),
+            ("ge", stats.garbage_effi),
+            ("wapp", stats.weighted_app),
+            ("area", stats.area),
+            ("srarea", stats.srarea),
+            ("statrank", stats.stat_rank),
+            ("estglicko", stats.estglicko),
+            ("esttr", stats.esttr),
+            ("opener", stats.opener),
+            ("plonk", stats.plonk),
+            ("stride", stats.stride),
+            ("infds", stats.infds),
+            ("napm", stats.napm),
+            ("npps", stats.npps),
+            ("nvs", stats.nvs),
+            ("napp", stats.napp),
+            ("ndss", stats.ndss),
+            ("ndsp", stats.ndsp),
+            ("nge", stats.nge),
+            ("nvsapm", stats.nvsapm),
+        ]);
+
+        for (name, value) in [
+            ("atr", stats.atr),
+            ("tr", stats.tr),
+            ("glicko", stats.glicko),
+            ("rd", stats.rd),
+        ] {
+            if let Some(value) = value {
+                variables.insert(name, value);
+            }
+        }
+
+        Self(variables)
+    }
+
+    pub fn with_games(mut self, won: f64, played: f64) -> Self {
+        self.0.insert("wins", won);
+        self.0.insert("games", played);
+        if played > 0.0 {
+            self.0.insert("winrate", won / played);
+        }
+        self
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct StatFormula {
+    source: String,
+    expression: Expression,
+}
+
+impl StatFormula {
+    pub fn parse(source: &str) -> Result<Self, FormulaError> {
+        let source = source.trim();
+        if source.len() > MAX_FORMULA_LENGTH {
+            return Err(FormulaError::TooLong);
+        }
+
+        let tokens = tokenize(source)?;
+        if tokens.is_empty() {
+            return Err(FormulaError::Empty);
+        }
+
+        let mut parser = Parser { tokens, position: 0 };
+        let expression = parser.expression()?;
+        if let Some(token) = parser.next() {
+            return Err(FormulaError::UnexpectedToken(token.to_string()));
+        }
+
+        Ok(Self {
+            source: source.to_string(),
+            expression,
+        })
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Returns `None` when a variable used by the formula isn't available or when the result isn't a finite number.
+    pub fn evaluate(&self, variables: &FormulaVariables) -> Option<f64> {
+        Self::evaluate_expression(&self.expression, variables).filter(|value| value.is_finite())
+    }
+
+    fn evaluate_expression(expression: &Expression, variables: &FormulaVariables) -> Option<f64> {
+        Some(match expression {
+            Expression::Number(number) => *number,
+            Expression::Variable(name) => *variables.0.get(name)?,
+            Expression::Negate(expression) => -Self::evaluate_expression(expression, variables)?,
+            Expression::Binary(operator, left, right) => {
+                let left = Self::evaluate_expression(left, variables)?;
+                let right = Self::evaluate_expression(right, variables)?;
+                match operator {
+                    Operator::Add => left + right,
+                    Operator::Subtract => left - right,
+                    Operator::Multiply => left * right,
+                    Operator::Divide => left / right,
+                    Operator::Power => left.powf(right),
+                }
+            }
+            Expression::Call(function, arguments) => {
+                let arguments = arguments
+                    .iter()
+                    .map(|argument| Self::evaluate_expression(argument, variables))
+                    .collect::<Option<Vec<_>>>()?;
+                function.apply(&arguments)
+            }
+        })
+    }
+}
+
+/// Resolves a formula given to a command: a formula saved in the guild under that name, or else an inline expression.
+pub async fn resolve_formula(
+    input: &str,
+    guild_id: Option<Id<GuildMarker>>,
+    context: &Context<'_>,
+) -> anyhow::Result<anyhow::Result<StatFormula>> {
+    #[cfg(feature = "database")]
+    if let Some(guild_id) = guild_id {
+        // names are saved in lowercase
+        let name = input.trim().to_lowercase();
+        if let Some(formula) = StatFormulaPDO::fetch_formula_by_name(context, guild_id.get(), &name).await? {
+            return Ok(StatFormula::parse(&formula.expression)
+                .map_err(|err| anyhow!("❌ Saved formula `{}` is invalid: {err}", formula.name)));
+        }
+    }
+    #[cfg(not(feature = "database"))]
+    let _ = (guild_id, context);
+
+    Ok(StatFormula::parse(input).map_err(|err| anyhow!("❌ Invalid formula `{input}`: {err}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn evaluate(source: &str) -> Option<f64> {
+        let variables = FormulaVariables(HashMap::from([("apm", 60.0), ("pps", 2.0)]));
+        StatFormula::parse(source).unwrap().evaluate(&variables)
+    }
+
+    fn error(source: &str) -> FormulaError {
+        StatFormula::parse(source).unwrap_err()
+    }
+
+    #[test]
+    fn multiplication_binds_tighter_than_addition() {
+        assert_eq!(evaluate("1 + 2 * 3"), Some(7.0));
+        assert_eq!(evaluate("(1 + 2) * 3"), Some(9.0));
+        assert_eq!(evaluate("1 + 6 / 3 - 1"), Some(2.0));
+    }
+
+    #[test]
+    fn operators_of_the_same_level_are_left_associative() {
+        assert_eq!(evaluate("10 - 4 - 3"), Some(3.0));
+        assert_eq!(evaluate("8 / 4 / 2"), Some(1.0));
+    }
+
+    #[test]
+    fn power_is_right_associative_and_binds_tighter_than_negation() {
+        assert_eq!(evaluate("2 ^ 3 ^ 2"), Some(512.0));
+        assert_eq!(evaluate("-2 ^ 2"), Some(-4.0));
+        assert_eq!(evaluate("2 ^ -1"), Some(0.5));
+        assert_eq!(evaluate("2 * 3 ^ 2"), Some(18.0));
+    }
+
+    #[test]
+    fn functions_and_variables() {
+        assert_eq!(evaluate("max(1, 3, 2)"), Some(3.0));
+        assert_eq!(evaluate("min(apm, pps)"), Some(2.0));
+        assert_eq!(evaluate("pow(2, 10)"), Some(1024.0));
+        assert!((evaluate("log(8, 2)").unwrap() - 3.0).abs() < 1e-9);
+        assert_eq!(evaluate("APM / Pps"), Some(30.0));
+    }
+
+    #[test]
+    fn missing_variables_and_non_finite_results_have_no_value() {
+        assert_eq!(evaluate("tr / 2"), None);
+        assert_eq!(evaluate("apm / 0"), None);
+        assert_eq!(evaluate("sqrt(-1)"), None);
+    }
+
+    #[test]
+    fn errors_point_at_the_offending_token() {
+        assert!(matches!(error("apm + $"), FormulaError::UnexpectedCharacter('$')));
+        assert!(matches!(error("1..2"), FormulaError::UnexpectedToken(token) if token == "1..2"));
+        assert!(matches!(error("1 2"), FormulaError::UnexpectedToken(token) if token == "2"));
+        assert!(matches!(error("(1 + 2))"), FormulaError::UnexpectedToken(token) if token == ")"));
+        assert!(matches!(error("max(1 2)"), FormulaError::UnexpectedToken(token) if token == "2"));
+        assert!(matches!(error("* 2"), FormulaError::UnexpectedToken(token) if token == "*"));
+    }
+
+    #[test]
+    fn incomplete_formulas_end_unexpectedly() {
+        assert!(matches!(error("1 +"), FormulaError::UnexpectedEnd));
+        assert!(matches!(error("(1 + 2"), FormulaError::UnexpectedEnd));
+        assert!(matches!(error("max(1,"), FormulaError::UnexpectedEnd));
+    }
+
+    #[test]
+    fn names_and_arguments_are_checked() {
+        assert!(matches!(error("speed * 2"), FormulaError::UnknownVariable(name) if name == "speed"));
+        assert!(matches!(error("foo(1"), FormulaError::UnknownFunction(name) if name == "foo"));
+        assert!(matches!(error("pow(1)"), FormulaError::WrongArgumentCount(name, 1) if name == "pow"));
+        assert!(matches!(error("min(1)"), FormulaError::WrongArgumentCount(name, 1) if name == "min"));
+    }
+
+    #[test]
+    fn empty_and_long_formulas_are_rejected() {
+        assert!(matches!(error("   "), FormulaError::Empty));
+        assert!(matches!(error(&"1".repeat(MAX_FORMULA_LENGTH + 1)), FormulaError::TooLong));
+    }
+}
@@ -0,0 +1,214 @@
+#![cfg(feature = "tetrio")]
+
+use super::stats::{calculate_stats, PlayerStats};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SolvedStat {
+    Apm,
+    Pps,
+    Vs,
+}
+
+impl SolvedStat {
+    pub fn name(&self) -> &'static str {
+        match self {
+            SolvedStat::Apm => "APM",
+            SolvedStat::Pps => "PPS",
+            SolvedStat::Vs => "VS",
+        }
+    }
+
+    /// Range in which the solver looks for a value, wide enough to cover every ranked player.
+    fn search_range(&self) -> (f64, f64) {
+        match self {
+            SolvedStat::Apm => (1.0, 400.0),
+            SolvedStat::Pps => (0.1, 6.0),
+            SolvedStat::Vs => (1.0, 800.0),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct TargetStats {
+    pub apm: f64,
+    pub pps: f64,
+    pub vs: f64,
+}
+
+pub struct TargetPath {
+    pub name: &'static str,
+    pub stats: Option<TargetStats>,
+}
+
+const SAMPLES: usize = 400;
+const ITERATIONS: usize = 64;
+
+pub fn estimated_tr(apm: f64, pps: f64, vs: f64, rd: Option<f64>) -> f64 {
+    calculate_stats(PlayerStats {
+        apm,
+        pps,
+        vs,
+        rd,
+        tr: None,
+        glicko: None,
+        rank: None,
+    })
+    .esttr
+}
+
+/// Finds the `x` in `[low, high]` for which `f(x) == target`, preferring the root that is the closest to `start`.
+fn solve(f: impl Fn(f64) -> f64, target: f64, low: f64, high: f64, start: Option<f64>) -> Option<f64> {
+    let step = (high - low) / SAMPLES as f64;
+    let difference = |x: f64| f(x) - target;
+
+    let mut brackets = vec![];
+    let mut previous = (low, difference(low));
+    for i in 1..=SAMPLES {
+        let x = low + step * i as f64;
+        let value = difference(x);
+        if value.is_finite() && previous.1.is_finite() && (value == 0.0 || previous.1.signum() != value.signum()) {
+            brackets.push((previous.0, x));
+        }
+        previous = (x, value);
+    }
+
+    let (mut left, mut right) = match start {
+        Some(start) => brackets
+            .into_iter()
+            .min_by(|a, b| (a.0 - start).abs().total_cmp(&(b.0 - start).abs()))?,
+        None => brackets.into_iter().next()?,
+    };
+
+    for _ in 0..ITERATIONS {
+        let middle = (left + right) / 2.0;
+        if difference(left).signum() == difference(middle).signum() {
+            left = middle;
+        } else {
+            right = middle;
+        }
+    }
+
+    Some((left + right) / 2.0)
+}
+
+/// Solves for the stat that is missing while the two others are held fixed.
+pub fn solve_missing_stat(
+    missing: SolvedStat,
+    apm: Option<f64>,
+    pps: Option<f64>,
+    vs: Option<f64>,
+    rd: Option<f64>,
+    target_tr: f64,
+) -> Option<TargetStats> {
+    let (low, high) = missing.search_range();
+    let with_value = |value: f64| TargetStats {
+        apm: if missing == SolvedStat::Apm { value } else { apm.unwrap_or(0.0) },
+        pps: if missing == SolvedStat::Pps { value } else { pps.unwrap_or(0.0) },
+        vs: if missing == SolvedStat::Vs { value } else { vs.unwrap_or(0.0) },
+    };
+    let start = match missing {
+        SolvedStat::Apm => apm,
+        SolvedStat::Pps => pps,
+        SolvedStat::Vs => vs,
+    };
+
+    let value = solve(
+        |value| {
+            let stats = with_value(value);
+            estimated_tr(stats.apm, stats.pps, stats.vs, rd)
+        },
+        target_tr,
+        low,
+        high,
+        start,
+    )?;
+
+    Some(with_value(value))
+}
+
+/// Computes the different ways of reaching `target_tr` from a complete set of stats:
+/// improving a single stat or scaling all of them by the same factor, which keeps the playstyle intact.
+pub fn nearest_paths(apm: f64, pps: f64, vs: f64, rd: Option<f64>, target_tr: f64) -> Vec<TargetPath> {
+    let mut paths = [SolvedStat::Apm, SolvedStat::Pps, SolvedStat::Vs]
+        .into_iter()
+        .map(|stat| TargetPath {
+            name: stat.name(),
+            stats: solve_missing_stat(stat, Some(apm), Some(pps), Some(vs), rd, target_tr),
+        })
+        .collect::<Vec<_>>();
+
+    let factor = solve(
+        |factor| estimated_tr(apm * factor, pps * factor, vs * factor, rd),
+        target_tr,
+        0.05,
+        5.0,
+        Some(1.0),
+    );
+
+    paths.push(TargetPath {
+        name: "All stats",
+        stats: factor.map(|factor| TargetStats {
+            apm: apm * factor,
+            pps: pps * factor,
+            vs: vs * factor,
+        }),
+    });
+
+    paths
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_reaches(stats: TargetStats, target_tr: f64) {
+        let tr = estimated_tr(stats.apm, stats.pps, stats.vs, None);
+        assert!((tr - target_tr).abs() < 1e-3, "reached {tr} instead of {target_tr}");
+    }
+
+    #[test]
+    fn solve_converges_on_the_root() {
+        let root = solve(|x| x * x, 2.0, 0.0, 10.0, None).unwrap();
+        assert!((root - 2f64.sqrt()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn solve_prefers_the_root_closest_to_the_start() {
+        let parabola = |x: f64| (x - 2.0) * (x - 5.0);
+        assert!((solve(parabola, 0.0, 0.0, 10.0, Some(4.5)).unwrap() - 5.0).abs() < 1e-9);
+        assert!((solve(parabola, 0.0, 0.0, 10.0, Some(1.0)).unwrap() - 2.0).abs() < 1e-9);
+        assert!((solve(parabola, 0.0, 0.0, 10.0, None).unwrap() - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn solve_gives_up_without_a_root_in_range() {
+        assert_eq!(solve(|x| x, 20.0, 0.0, 10.0, None), None);
+    }
+
+    #[test]
+    fn missing_stat_reaches_the_target_and_keeps_the_others() {
+        let target_tr = estimated_tr(80.0, 1.5, 170.0, None);
+
+        let stats = solve_missing_stat(SolvedStat::Apm, Some(60.0), Some(1.5), Some(170.0), None, target_tr).unwrap();
+        assert_reaches(stats, target_tr);
+        assert_eq!((stats.pps, stats.vs), (1.5, 170.0));
+
+        let stats = solve_missing_stat(SolvedStat::Vs, Some(80.0), Some(1.5), None, None, target_tr).unwrap();
+        assert_reaches(stats, target_tr);
+        assert_eq!((stats.apm, stats.pps), (80.0, 1.5));
+    }
+
+    #[test]
+    fn nearest_paths_reach_the_target() {
+        let target_tr = estimated_tr(72.0, 1.8, 156.0, None);
+        let paths = nearest_paths(60.0, 1.5, 130.0, None, target_tr);
+
+        assert_eq!(paths.len(), 4);
+        for stats in paths.iter().filter_map(|path| path.stats) {
+            assert_reaches(stats, target_tr);
+        }
+
+        let scaled = paths.iter().find(|path| path.name == "All stats").and_then(|path| path.stats).unwrap();
+        assert!((scaled.apm / 60.0 - scaled.pps / 1.5).abs() < 1e-9);
+    }
+}
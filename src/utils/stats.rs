@@ -6,6 +6,7 @@ use std::f64::consts::{self, PI};
 use tetrio_api::models::users::user_rank::UserRank;
 
 #[cfg(feature = "tetrio")]
+#[derive(Clone)]
 pub struct Stats {
     pub apm: f64,
     pub vs: f64,
@@ -0,0 +1,40 @@
+use itertools::Itertools;
+
+/// Formats rows of cells into a box drawn table, every row must have the same number of cells.
+pub fn format_table(rows: &[Vec<String>]) -> String {
+    let Some(first) = rows.first() else {
+        return String::new();
+    };
+
+    let columns_size = (0..first.len())
+        .map(|i| rows.iter().map(|row| row[i].chars().count()).max().unwrap_or(0))
+        .collect_vec();
+    log::debug!("{:?}", columns_size);
+
+    let line = |left: &str, middle: &str, right: &str| {
+        String::from(left)
+            + &columns_size
+                .iter()
+                .map(|size| "═".repeat(size + 2))
+                .join(middle)
+            + right
+    };
+
+    let top = line("╔", "╤", "╗\n");
+    let bottom = line("╚", "╧", "╝");
+    let separator = line("╟", "┼", "╢\n");
+
+    top + &rows
+        .iter()
+        .map(|row| {
+            let cells = row
+                .iter()
+                .enumerate()
+                .map(|(i, cell)| format!(" {:<width$}", cell, width = columns_size[i] + 1))
+                .join("│");
+
+            String::from("║") + &cells + "║\n"
+        })
+        .join(&separator)
+        + &bottom
+}